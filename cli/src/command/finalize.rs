@@ -161,6 +161,8 @@ impl ExonumCommand for Finalize {
                 database: Default::default(),
                 connect_list,
                 thread_pool_size: Default::default(),
+                consensus_events_log: None,
+                transaction_indexes: Default::default(),
                 master_key_path: secret_config.master_key_path,
                 keys: secret_config.keys,
             }
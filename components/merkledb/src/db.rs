@@ -474,6 +474,14 @@ pub struct Fork {
     working_patch: WorkingPatch,
 }
 
+/// Unflushed changes of a fork saved by [`Fork::checkpoint`].
+///
+/// [`Fork::checkpoint`]: struct.Fork.html#method.checkpoint
+#[derive(Debug)]
+pub struct Checkpoint {
+    changes: HashMap<IndexAddress, Option<ViewChanges>>,
+}
+
 /// A set of serial changes that should be applied to a storage atomically.
 ///
 /// This set can contain changes from multiple tables. When a block is added to
@@ -672,6 +680,42 @@ impl Fork {
         self.working_patch = WorkingPatch::new();
     }
 
+    /// Saves the changes made after the latest execution of the `flush` method, so that
+    /// the fork can be reverted to them with [`rollback_to`]. Unlike `flush`, the checkpoint
+    /// does not finalize the changes: `rollback` still reverts all of them.
+    ///
+    /// [`rollback_to`]: #method.rollback_to
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let changes = self
+            .working_patch
+            .changes
+            .borrow()
+            .iter()
+            .map(|(address, changes)| {
+                // Changes are cloned, since sharing them would make them immutably borrowed.
+                let changes = changes.as_ref().map(|changes| (**changes).clone());
+                (address.clone(), changes)
+            })
+            .collect();
+        Checkpoint { changes }
+    }
+
+    /// Rolls back all changes that were made after the creation of the `checkpoint`.
+    /// The changes made before the checkpoint remain unflushed.
+    ///
+    /// The checkpoint must be created from this fork after the latest execution
+    /// of the `flush` method; otherwise, the fork ends up in an inconsistent state.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        let changes = checkpoint
+            .changes
+            .into_iter()
+            .map(|(address, changes)| (address, changes.map(Rc::new)))
+            .collect();
+        self.working_patch = WorkingPatch {
+            changes: RefCell::new(changes),
+        };
+    }
+
     /// Converts the fork into `Patch` consuming the fork instance.
     pub fn into_patch(mut self) -> Patch {
         self.flush();
@@ -133,8 +133,8 @@
 pub use self::{
     backends::{rocksdb::RocksDB, temporarydb::TemporaryDB},
     db::{
-        Change, Changes, ChangesIterator, Database, Fork, Iter, Iterator, Patch, PatchIterator,
-        ReadonlyFork, Snapshot,
+        Change, Changes, ChangesIterator, Checkpoint, Database, Fork, Iter, Iterator, Patch,
+        PatchIterator, ReadonlyFork, Snapshot,
     },
     entry::Entry,
     error::Error,
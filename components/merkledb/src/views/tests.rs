@@ -592,6 +592,43 @@ fn rollbacks_for_indexes_in_same_family() {
     assert_eq!(list2.iter().collect::<Vec<_>>(), vec![2, 3, 5, 8]);
 }
 
+#[test]
+fn rollback_to_checkpoint() {
+    use crate::ProofListIndex;
+
+    fn list(fork: &Fork) -> ProofListIndex<&Fork, i64> {
+        fork.get_proof_list("list")
+    }
+
+    let db = TemporaryDB::new();
+    let mut fork = db.fork();
+    list(&fork).extend(vec![1, 2]);
+    fork.flush();
+
+    list(&fork).push(3);
+    let checkpoint = fork.checkpoint();
+    {
+        let mut list = list(&fork);
+        list.push(4);
+        list.set(0, 10);
+    }
+    fork.get_entry("entry").set(42_u64);
+    fork.rollback_to(checkpoint);
+
+    assert_eq!(list(&fork).iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert!(!fork.get_entry::<_, u64>("entry").exists());
+    // Changes made before the checkpoint are still not flushed.
+    assert!(fork.is_dirty());
+
+    let checkpoint = fork.checkpoint();
+    list(&fork).push(5);
+    fork.rollback();
+    assert_eq!(list(&fork).iter().collect::<Vec<_>>(), vec![1, 2]);
+    // The checkpoint is still valid after the rollback to the latest flush.
+    fork.rollback_to(checkpoint);
+    assert_eq!(list(&fork).iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+}
+
 #[test]
 fn clear_view() {
     test_clear_view(&TemporaryDB::new(), IDX_NAME);
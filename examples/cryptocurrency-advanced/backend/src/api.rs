@@ -19,6 +19,7 @@ use exonum_merkledb::{proof_map_index::Raw, ListProof, MapProof};
 use exonum::{
     blockchain::{BlockProof, IndexCoordinates, SchemaOrigin},
     crypto::{Hash, PublicKey},
+    impl_api_schema,
     messages::{AnyTx, Verified},
     runtime::rust::api::{self, ServiceApiBuilder, ServiceApiState},
 };
@@ -32,6 +33,8 @@ pub struct WalletQuery {
     pub pub_key: PublicKey,
 }
 
+impl_api_schema!(WalletQuery { pub_key: PublicKey });
+
 /// Proof of existence for specific wallet.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletProof {
@@ -41,6 +44,11 @@ pub struct WalletProof {
     pub to_wallet: MapProof<PublicKey, Wallet, Raw>,
 }
 
+impl_api_schema!(WalletProof {
+    to_table: MapProof<IndexCoordinates, Hash>,
+    to_wallet: MapProof<PublicKey, Wallet, Raw>,
+});
+
 /// Wallet history.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletHistory {
@@ -50,6 +58,11 @@ pub struct WalletHistory {
     pub transactions: Vec<Verified<AnyTx>>,
 }
 
+impl_api_schema!(WalletHistory {
+    proof: ListProof<Hash>,
+    transactions: Vec<Verified<AnyTx>>,
+});
+
 /// Wallet information.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalletInfo {
@@ -61,6 +74,12 @@ pub struct WalletInfo {
     pub wallet_history: Option<WalletHistory>,
 }
 
+impl_api_schema!(WalletInfo {
+    block_proof: BlockProof,
+    wallet_proof: WalletProof,
+    wallet_history: Option<WalletHistory>,
+});
+
 /// Public service API description.
 #[derive(Debug, Clone, Copy)]
 pub struct PublicApi;
@@ -51,6 +51,8 @@ fn node_config() -> NodeConfig {
         services_configs: Default::default(),
         database: Default::default(),
         thread_pool_size: Default::default(),
+        consensus_events_log: None,
+        transaction_indexes: Default::default(),
         master_key_path: Default::default(),
         keys: Keys::from_keys(
             consensus_public_key,
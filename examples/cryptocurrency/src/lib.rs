@@ -39,7 +39,10 @@ pub mod proto;
 
 /// Persistent data.
 pub mod schema {
-    use exonum::crypto::{Hash, PublicKey};
+    use exonum::{
+        crypto::{Hash, PublicKey},
+        impl_api_schema,
+    };
     use exonum_merkledb::{access::Access, MapIndex};
     use exonum_proto::ProtobufConvert;
 
@@ -61,6 +64,13 @@ pub mod schema {
         pub balance: u64,
     }
 
+    // Schema of the wallet, which is published in the API description.
+    impl_api_schema!(Wallet {
+        pub_key: PublicKey,
+        name: String,
+        balance: u64,
+    });
+
     /// Additional methods for managing balance of the wallet in an immutable fashion.
     impl Wallet {
         /// Create new Wallet.
@@ -268,6 +278,7 @@ pub mod contracts {
 pub mod api {
     use exonum::{
         crypto::PublicKey,
+        impl_api_schema,
         runtime::rust::api::{self, ServiceApiBuilder, ServiceApiState},
     };
 
@@ -284,6 +295,8 @@ pub mod api {
         pub pub_key: PublicKey,
     }
 
+    impl_api_schema!(WalletQuery { pub_key: PublicKey });
+
     impl CryptocurrencyApi {
         /// Endpoint for getting a single wallet.
         pub fn get_wallet(
@@ -253,6 +253,8 @@ fn node_config() -> NodeConfig {
         services_configs: Default::default(),
         database: Default::default(),
         thread_pool_size: Default::default(),
+        consensus_events_log: None,
+        transaction_indexes: Default::default(),
         master_key_path: Default::default(),
         keys,
     }
@@ -314,6 +316,7 @@ fn main() {
             artifact: "255:sample_artifact".parse().unwrap(),
             name: instance_name.clone(),
             config: 10_u64.into_bytes(),
+            instance_id: None,
         };
 
         api_sender
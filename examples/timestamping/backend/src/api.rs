@@ -18,6 +18,7 @@ use exonum_merkledb::{proof_map_index::Raw, MapProof};
 use exonum::{
     blockchain::{BlockProof, IndexCoordinates, SchemaOrigin},
     crypto::Hash,
+    impl_api_schema,
     runtime::rust::api::{self, ServiceApiBuilder, ServiceApiState},
 };
 
@@ -30,6 +31,8 @@ pub struct TimestampQuery {
     pub hash: Hash,
 }
 
+impl_api_schema!(TimestampQuery { hash: Hash });
+
 impl TimestampQuery {
     /// Creates new `TimestampQuery` with given `hash`.
     pub fn new(hash: Hash) -> Self {
@@ -48,6 +51,12 @@ pub struct TimestampProof {
     pub timestamp_proof: MapProof<Hash, TimestampEntry, Raw>,
 }
 
+impl_api_schema!(TimestampProof {
+    block_info: BlockProof,
+    state_proof: MapProof<IndexCoordinates, Hash>,
+    timestamp_proof: MapProof<Hash, TimestampEntry, Raw>,
+});
+
 /// Public service API.
 #[derive(Debug, Clone, Copy)]
 pub struct PublicApi;
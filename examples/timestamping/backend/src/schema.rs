@@ -15,7 +15,7 @@
 //! Timestamping database schema.
 
 use chrono::{DateTime, Utc};
-use exonum::crypto::Hash;
+use exonum::{api::ApiSchema, crypto::Hash};
 use exonum_merkledb::{
     access::{Access, RawAccessMut},
     Entry, ObjectHash, RawProofMapIndex,
@@ -58,6 +58,9 @@ pub struct TimestampEntry {
     pub time: DateTime<Utc>,
 }
 
+// The entry is serialized via its Protobuf representation, so its schema is left unspecified.
+impl ApiSchema for TimestampEntry {}
+
 impl TimestampEntry {
     /// New TimestampEntry.
     pub fn new(timestamp: Timestamp, tx_hash: Hash, time: DateTime<Utc>) -> Self {
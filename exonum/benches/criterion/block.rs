@@ -37,6 +37,7 @@
 //! - `block_cryptocurrency_rollback`: Transferring cryptocurrency among random accounts.
 //!   Accounts are stored in a `MapIndex`. Transactions are rolled back 50% of the time.
 
+use chrono::Utc;
 use criterion::{Criterion, ParameterizedBenchmark, Throughput};
 use exonum_merkledb::{Database, DbOptions, ObjectHash, Patch, RocksDB};
 use futures::sync::mpsc;
@@ -103,6 +104,7 @@ fn execute_block(blockchain: &BlockchainMut, height: u64, txs: &[Hash]) -> (Hash
     blockchain.create_patch(
         ValidatorId::zero(),
         Height(height),
+        Utc::now(),
         txs,
         &mut BTreeMap::new(),
     )
@@ -21,29 +21,45 @@ pub use actix_web::middleware::cors::Cors;
 
 use actix::{Actor, System};
 use actix_web::{
-    error::ResponseError, AsyncResponder, FromRequest, HttpMessage, HttpResponse, Query,
+    error::ResponseError,
+    http::header,
+    middleware::{Middleware, Started},
+    AsyncResponder, FromRequest, HttpMessage, HttpResponse, Query,
 };
+use chrono::Utc;
 use failure::Error;
 use futures::{sync::mpsc, Future, IntoFuture, Stream};
+use hex::FromHex;
+use rustls::{
+    internal::pemfile, AllowAnyAuthenticatedClient, Certificate, NoClientAuth, PrivateKey,
+    RootCertStore, ServerConfig,
+};
 use serde::{
     de::{self, DeserializeOwned},
     ser, Serialize,
 };
 
 use std::{
+    collections::HashMap,
     fmt,
+    fs::File,
+    io::BufReader,
     net::SocketAddr,
+    path::{Path, PathBuf},
     result,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex},
     thread::{self, JoinHandle},
 };
 
-use crate::api::{
-    self,
-    manager::{ApiManager, UpdateEndpoints},
-    ApiAccess, ApiAggregator, ApiBackend, ApiScope, ExtendApiBackend, FutureResult, Immutable,
-    Mutable, NamedWith,
+use crate::{
+    api::{
+        self,
+        manager::{ApiManager, UpdateEndpoints},
+        ApiAccess, ApiAggregator, ApiBackend, ApiScope, ExtendApiBackend, FutureResult, Immutable,
+        Mutable, NamedWith,
+    },
+    crypto::{self, Hash, PublicKey, SecretKey, Signature},
 };
 
 /// Type alias for the concrete `actix-web` HTTP response.
@@ -88,6 +104,11 @@ impl ApiBuilder {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns the handlers added to the builder.
+    pub fn handlers(&self) -> &[RequestHandler] {
+        &self.handlers
+    }
 }
 
 impl ApiBackend for ApiBuilder {
@@ -172,9 +193,7 @@ where
         let handler = f.inner.handler;
         let index = move |request: HttpRequest| -> FutureResponse {
             let handler = handler.clone();
-            request
-                .json()
-                .from_err()
+            read_json(&request)
                 .and_then(move |query: Q| {
                     handler(query)
                         .map(|value| HttpResponse::Ok().json(value))
@@ -227,9 +246,7 @@ where
         let handler = f.inner.handler;
         let index = move |request: HttpRequest| -> FutureResponse {
             let handler = handler.clone();
-            request
-                .json()
-                .from_err()
+            read_json(&request)
                 .and_then(move |query: Q| {
                     handler(query)
                         .map(|value| HttpResponse::Ok().json(value))
@@ -255,6 +272,11 @@ pub(crate) fn create_app(aggregator: &ApiAggregator, runtime_config: ApiRuntimeC
     if let Some(app_config) = app_config {
         app = app_config(app);
     }
+    // The authentication middleware is registered after the user-provided configuration,
+    // so that CORS preflight requests are answered without credentials.
+    if let Some(auth) = runtime_config.auth {
+        app = app.middleware(auth);
+    }
     app
 }
 
@@ -267,6 +289,10 @@ pub struct ApiRuntimeConfig {
     pub access: ApiAccess,
     /// Optional App configuration.
     pub app_config: Option<AppConfig>,
+    /// Optional authentication of the API requests.
+    pub auth: Option<ApiAuth>,
+    /// Optional TLS settings. If set, the API is served over HTTPS.
+    pub tls: Option<TlsConfig>,
 }
 
 impl ApiRuntimeConfig {
@@ -276,6 +302,8 @@ impl ApiRuntimeConfig {
             listen_address,
             access,
             app_config: Default::default(),
+            auth: None,
+            tls: None,
         }
     }
 }
@@ -286,10 +314,82 @@ impl fmt::Debug for ApiRuntimeConfig {
             .field("listen_address", &self.listen_address)
             .field("access", &self.access)
             .field("app_config", &self.app_config.as_ref().map(drop))
+            .field("auth", &self.auth)
+            .field("tls", &self.tls)
             .finish()
     }
 }
 
+/// TLS settings of the API server.
+///
+/// Both the HTTP and websocket endpoints of the server are served over TLS
+/// if these settings are specified.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to the PEM file with the certificate chain of the server.
+    pub certificate_path: PathBuf,
+    /// Path to the PEM file with the private key of the server in the PKCS#8
+    /// or the PKCS#1 (RSA) format.
+    pub private_key_path: PathBuf,
+    /// Path to the PEM file with the certificates of authorities issuing client
+    /// certificates. If set, clients are required to present a certificate issued
+    /// by one of these authorities.
+    #[serde(default)]
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Loads the certificates and the private key and creates the configuration
+    /// of the TLS server.
+    pub fn server_config(&self) -> result::Result<ServerConfig, Error> {
+        let client_verifier = match self.client_ca_path {
+            Some(ref path) => {
+                let mut roots = RootCertStore::empty();
+                for certificate in read_certificates(path)? {
+                    roots.add(&certificate).map_err(|e| {
+                        format_err!("Invalid client CA certificate in {:?}: {:?}", path, e)
+                    })?;
+                }
+                AllowAnyAuthenticatedClient::new(roots)
+            }
+            None => NoClientAuth::new(),
+        };
+
+        let mut config = ServerConfig::new(client_verifier);
+        config
+            .set_single_cert(
+                read_certificates(&self.certificate_path)?,
+                read_private_key(&self.private_key_path)?,
+            )
+            .map_err(|e| format_err!("Invalid TLS certificate or private key: {}", e))?;
+        Ok(config)
+    }
+}
+
+fn read_certificates(path: &Path) -> result::Result<Vec<Certificate>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certificates = pemfile::certs(&mut reader)
+        .map_err(|()| format_err!("Unable to parse certificates from {:?}", path))?;
+    ensure!(
+        !certificates.is_empty(),
+        "No certificates found in {:?}",
+        path
+    );
+    Ok(certificates)
+}
+
+fn read_private_key(path: &Path) -> result::Result<PrivateKey, Error> {
+    let mut keys = pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(path)?))
+        .map_err(|()| format_err!("Unable to parse private key from {:?}", path))?;
+    if keys.is_empty() {
+        keys = pemfile::rsa_private_keys(&mut BufReader::new(File::open(path)?))
+            .map_err(|()| format_err!("Unable to parse private key from {:?}", path))?;
+    }
+    keys.into_iter()
+        .next()
+        .ok_or_else(|| format_err!("No private key found in {:?}", path))
+}
+
 /// Configuration parameters for the actix system runtime.
 #[derive(Debug, Clone)]
 pub struct SystemRuntimeConfig {
@@ -367,6 +467,301 @@ impl fmt::Debug for SystemRuntime {
     }
 }
 
+/// Authentication of the API requests.
+///
+/// A request is authorized if it satisfies any of the following conditions:
+///
+/// - The `Authorization` header contains one of `bearer_tokens` in the
+///   `Bearer <token>` form.
+/// - The request is signed by one of `admin_keys`. The public key, the Unix timestamp
+///   of the request in seconds, the nonce, the hash of the request body and the signature
+///   are passed in the `X-Exonum-Public-Key`, `X-Exonum-Timestamp`, `X-Exonum-Nonce`,
+///   `X-Exonum-Content-Hash` and `X-Exonum-Signature` headers respectively; the timestamp
+///   and the nonce are decimal numbers, other values are encoded in hex. The signature
+///   is created by the `ApiAuth::sign_request` method. The timestamp must not differ
+///   from the node time by more than `signature_ttl` seconds.
+///
+/// Bearer tokens do not protect the body of the request, so they should only be used
+/// if the API is not accessible over an untrusted network or is protected by TLS.
+///
+/// # Signed requests
+///
+/// The signature covers the method, the path with the query string, the timestamp,
+/// the nonce and the hash of the request body. The body is checked against the signed
+/// hash when it is read by the endpoint handler, and requests with a mismatching body
+/// are rejected.
+///
+/// The node remembers the signatures of the accepted requests while their timestamps
+/// are valid and rejects requests with the already seen signatures, so a signed request
+/// cannot be replayed. Clients should use a new nonce for each request, so that identical
+/// requests sent within the same second are not rejected. At most `MAX_SEEN_SIGNATURES`
+/// signatures are remembered; if the limit is reached, signed requests are rejected until
+/// the timestamps of the remembered requests expire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiAuth {
+    /// Tokens accepted in the `Authorization` header.
+    #[serde(default)]
+    pub bearer_tokens: Vec<String>,
+    /// Public keys of the administrators allowed to sign requests.
+    #[serde(default)]
+    pub admin_keys: Vec<PublicKey>,
+    /// Maximum difference in seconds between the timestamp of a signed request
+    /// and the node time.
+    #[serde(default = "ApiAuth::default_signature_ttl")]
+    pub signature_ttl: u64,
+    /// Signatures of the accepted requests. Clones of the configuration share the signatures,
+    /// so that a request cannot be replayed against another worker of the API server.
+    #[serde(skip)]
+    seen_signatures: SeenSignatures,
+}
+
+impl Default for ApiAuth {
+    fn default() -> Self {
+        Self {
+            bearer_tokens: Vec::new(),
+            admin_keys: Vec::new(),
+            signature_ttl: Self::default_signature_ttl(),
+            seen_signatures: SeenSignatures::default(),
+        }
+    }
+}
+
+impl ApiAuth {
+    /// Name of the header with the public key of the request signer.
+    pub const PUBLIC_KEY_HEADER: &'static str = "X-Exonum-Public-Key";
+    /// Name of the header with the Unix timestamp of the signed request.
+    pub const TIMESTAMP_HEADER: &'static str = "X-Exonum-Timestamp";
+    /// Name of the header with the nonce of the signed request.
+    pub const NONCE_HEADER: &'static str = "X-Exonum-Nonce";
+    /// Name of the header with the hash of the signed request body.
+    pub const CONTENT_HASH_HEADER: &'static str = "X-Exonum-Content-Hash";
+    /// Name of the header with the signature of the request.
+    pub const SIGNATURE_HEADER: &'static str = "X-Exonum-Signature";
+    /// Maximum number of remembered signatures of the accepted requests.
+    pub const MAX_SEEN_SIGNATURES: usize = 10_000;
+
+    fn default_signature_ttl() -> u64 {
+        60
+    }
+
+    /// Creates a new configuration with the given bearer tokens, administrator keys
+    /// and the default `signature_ttl`.
+    pub fn new(bearer_tokens: Vec<String>, admin_keys: Vec<PublicKey>) -> Self {
+        Self {
+            bearer_tokens,
+            admin_keys,
+            ..Self::default()
+        }
+    }
+
+    /// Signs the request with the given method, path with the query string, body,
+    /// Unix timestamp and nonce. The hash of the body to be passed
+    /// in the `X-Exonum-Content-Hash` header is calculated with `crypto::hash`.
+    pub fn sign_request(
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+        timestamp: i64,
+        nonce: u64,
+        secret_key: &SecretKey,
+    ) -> Signature {
+        let content_hash = crypto::hash(body);
+        let message =
+            Self::request_message(method, path_and_query, &content_hash, timestamp, nonce);
+        crypto::sign(message.as_bytes(), secret_key)
+    }
+
+    fn request_message(
+        method: &str,
+        path_and_query: &str,
+        content_hash: &Hash,
+        timestamp: i64,
+        nonce: u64,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}",
+            method,
+            path_and_query,
+            timestamp,
+            nonce,
+            content_hash.to_hex()
+        )
+    }
+
+    /// Checks that the request carries valid credentials.
+    pub fn authorize<S>(&self, request: &actix_web::HttpRequest<S>) -> Result<(), api::Error> {
+        self.authorize_at(request, Utc::now().timestamp())
+    }
+
+    fn authorize_at<S>(
+        &self,
+        request: &actix_web::HttpRequest<S>,
+        now: i64,
+    ) -> Result<(), api::Error> {
+        if self.check_bearer_token(request) || self.check_signature(request, now) {
+            Ok(())
+        } else {
+            Err(api::Error::Unauthorized)
+        }
+    }
+
+    fn check_bearer_token<S>(&self, request: &actix_web::HttpRequest<S>) -> bool {
+        const PREFIX: &str = "Bearer ";
+
+        let value = match header_value(request, header::AUTHORIZATION.as_str()) {
+            Some(value) if value.starts_with(PREFIX) => &value[PREFIX.len()..],
+            _ => return false,
+        };
+        // Every token is checked to make the check time independent of the matched token.
+        self.bearer_tokens.iter().fold(false, |found, token| {
+            constant_time_eq(token.as_bytes(), value.as_bytes()) | found
+        })
+    }
+
+    /// Checks the signature of the request. If the signature is valid, the signed hash
+    /// of the body is attached to the request, so that the body can be checked when read.
+    fn check_signature<S>(&self, request: &actix_web::HttpRequest<S>, now: i64) -> bool {
+        let public_key = header_value(request, Self::PUBLIC_KEY_HEADER)
+            .and_then(|value| PublicKey::from_hex(value).ok());
+        let timestamp = header_value(request, Self::TIMESTAMP_HEADER)
+            .and_then(|value| value.parse::<i64>().ok());
+        let nonce =
+            header_value(request, Self::NONCE_HEADER).and_then(|value| value.parse::<u64>().ok());
+        let content_hash = header_value(request, Self::CONTENT_HASH_HEADER)
+            .and_then(|value| Hash::from_hex(value).ok());
+        let signature = header_value(request, Self::SIGNATURE_HEADER)
+            .and_then(|value| Signature::from_hex(value).ok());
+
+        let (public_key, timestamp, nonce, content_hash, signature) =
+            match (public_key, timestamp, nonce, content_hash, signature) {
+                (Some(public_key), Some(timestamp), Some(nonce), Some(hash), Some(signature)) => {
+                    (public_key, timestamp, nonce, hash, signature)
+                }
+                _ => return false,
+            };
+        if !self.admin_keys.contains(&public_key) || !self.is_timestamp_fresh(timestamp, now) {
+            return false;
+        }
+
+        let path_and_query = request
+            .uri()
+            .path_and_query()
+            .map_or_else(|| request.path(), |path| path.as_str());
+        let method = request.method().as_str();
+        let message =
+            Self::request_message(method, path_and_query, &content_hash, timestamp, nonce);
+        if !crypto::verify(&signature, message.as_bytes(), &public_key) {
+            return false;
+        }
+
+        let is_fresh = |timestamp| self.is_timestamp_fresh(timestamp, now);
+        if !self.seen_signatures.insert(signature, timestamp, is_fresh) {
+            warn!("Rejected the replayed or excessive signed API request");
+            return false;
+        }
+        request
+            .extensions_mut()
+            .insert(SignedContentHash(content_hash));
+        true
+    }
+
+    fn is_timestamp_fresh(&self, timestamp: i64, now: i64) -> bool {
+        // The difference overflows only for timestamps which are obviously wrong.
+        now.checked_sub(timestamp)
+            .and_then(|diff| diff.checked_abs())
+            .map_or(false, |diff| diff as u64 <= self.signature_ttl)
+    }
+}
+
+impl<S> Middleware<S> for ApiAuth {
+    fn start(&self, request: &actix_web::HttpRequest<S>) -> actix_web::Result<Started> {
+        Ok(match self.authorize(request) {
+            Ok(()) => Started::Done,
+            Err(e) => {
+                warn!(
+                    "Rejected unauthorized API request {} {}",
+                    request.method(),
+                    request.path()
+                );
+                Started::Response(e.error_response())
+            }
+        })
+    }
+}
+
+/// Signatures of the accepted signed requests together with their timestamps.
+/// Clones share the same signatures.
+#[derive(Clone, Default)]
+struct SeenSignatures(Arc<Mutex<HashMap<Signature, i64>>>);
+
+impl SeenSignatures {
+    /// Remembers the signature. Returns `false` if the signature has already been seen,
+    /// or if `ApiAuth::MAX_SEEN_SIGNATURES` signatures with fresh timestamps are remembered.
+    fn insert(&self, signature: Signature, timestamp: i64, is_fresh: impl Fn(i64) -> bool) -> bool {
+        let mut signatures = self.0.lock().unwrap();
+        if signatures.contains_key(&signature) {
+            return false;
+        }
+        if signatures.len() >= ApiAuth::MAX_SEEN_SIGNATURES {
+            signatures.retain(|_, &mut timestamp| is_fresh(timestamp));
+            if signatures.len() >= ApiAuth::MAX_SEEN_SIGNATURES {
+                return false;
+            }
+        }
+        signatures.insert(signature, timestamp);
+        true
+    }
+}
+
+impl fmt::Debug for SeenSignatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeenSignatures").finish()
+    }
+}
+
+/// Seen signatures are the runtime state rather than a part of the configuration,
+/// so they are not compared.
+impl PartialEq for SeenSignatures {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Hash of the request body signed by the request author.
+#[derive(Debug, Clone, Copy)]
+struct SignedContentHash(Hash);
+
+/// Reads the JSON body of the request. If the request is signed, the body is checked
+/// against the signed hash.
+fn read_json<Q>(request: &HttpRequest) -> impl Future<Item = Q, Error = actix_web::Error>
+where
+    Q: DeserializeOwned + 'static,
+{
+    let signed_hash = request
+        .extensions()
+        .get::<SignedContentHash>()
+        .map(|hash| hash.0);
+    request.body().from_err().and_then(move |body| {
+        if let Some(signed_hash) = signed_hash {
+            if crypto::hash(&body) != signed_hash {
+                return Err(api::Error::Unauthorized.into());
+            }
+        }
+        serde_json::from_slice(&body).map_err(|e| api::Error::BadRequest(e.to_string()).into())
+    })
+}
+
+fn header_value<'a, S>(request: &'a actix_web::HttpRequest<S>, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.len() == rhs.len() && lhs.iter().zip(rhs).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 /// CORS header specification.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AllowOrigin {
@@ -499,3 +894,190 @@ fn allow_origin_from_str() {
         AllowOrigin::Whitelist(vec!["http://a.org".to_string(), "http://b.org".to_string()]),
     );
 }
+
+#[test]
+fn api_auth_bearer_token() {
+    use actix_web::test::TestRequest;
+
+    let auth = ApiAuth {
+        bearer_tokens: vec!["secret".to_owned()],
+        ..ApiAuth::default()
+    };
+
+    let request = TestRequest::with_header("Authorization", "Bearer secret").finish();
+    assert!(auth.authorize(&request).is_ok());
+    let request = TestRequest::with_header("Authorization", "Bearer other").finish();
+    assert!(auth.authorize(&request).is_err());
+    let request = TestRequest::with_header("Authorization", "secret").finish();
+    assert!(auth.authorize(&request).is_err());
+    assert!(auth.authorize(&TestRequest::default().finish()).is_err());
+}
+
+#[test]
+fn api_auth_signed_request() {
+    use actix_web::{http::Method, test::TestRequest};
+
+    let (public_key, secret_key) = crypto::gen_keypair();
+    let auth = ApiAuth::new(vec![], vec![public_key]);
+    let uri = "/api/system/v1/peers?foo=bar";
+    let body = b"{}";
+    let now = 1_000_000;
+    let signed_request =
+        |key: PublicKey, method, timestamp: i64, nonce: u64, signature: Signature| {
+            TestRequest::with_header(ApiAuth::PUBLIC_KEY_HEADER, key.to_hex())
+                .header(ApiAuth::TIMESTAMP_HEADER, timestamp.to_string())
+                .header(ApiAuth::NONCE_HEADER, nonce.to_string())
+                .header(ApiAuth::CONTENT_HASH_HEADER, crypto::hash(body).to_hex())
+                .header(ApiAuth::SIGNATURE_HEADER, signature.to_hex())
+                .method(method)
+                .uri(uri)
+                .finish()
+        };
+
+    let signature = ApiAuth::sign_request("POST", uri, body, now, 0, &secret_key);
+    let request = signed_request(public_key, Method::POST, now, 0, signature);
+    assert!(auth.authorize_at(&request, now).is_ok());
+    let signature = ApiAuth::sign_request("POST", uri, body, now, 1, &secret_key);
+    let request = signed_request(public_key, Method::POST, now, 1, signature);
+    assert!(auth.authorize_at(&request, now + 60).is_ok());
+    // Expired signature.
+    let signature = ApiAuth::sign_request("POST", uri, body, now, 2, &secret_key);
+    let request = signed_request(public_key, Method::POST, now, 2, signature);
+    assert!(auth.authorize_at(&request, now + 61).is_err());
+    // Signature for another method.
+    let request = signed_request(public_key, Method::GET, now, 2, signature);
+    assert!(auth.authorize_at(&request, now).is_err());
+    // Signature for another nonce.
+    let request = signed_request(public_key, Method::POST, now, 3, signature);
+    assert!(auth.authorize_at(&request, now).is_err());
+    // Signature for another body.
+    let signature = ApiAuth::sign_request("POST", uri, b"[]", now, 4, &secret_key);
+    let request = signed_request(public_key, Method::POST, now, 4, signature);
+    assert!(auth.authorize_at(&request, now).is_err());
+    // Signature by an unknown key.
+    let (other_key, other_secret_key) = crypto::gen_keypair();
+    let signature = ApiAuth::sign_request("POST", uri, body, now, 5, &other_secret_key);
+    let request = signed_request(other_key, Method::POST, now, 5, signature);
+    assert!(auth.authorize_at(&request, now).is_err());
+
+    // Timestamps far from the node time must not overflow the difference computation.
+    for &(timestamp, now) in &[
+        (i64::min_value(), now),
+        (i64::max_value(), now),
+        (i64::min_value(), i64::max_value()),
+        (i64::max_value(), i64::min_value()),
+        (i64::max_value(), -1),
+    ] {
+        let signature = ApiAuth::sign_request("POST", uri, body, timestamp, 6, &secret_key);
+        let request = signed_request(public_key, Method::POST, timestamp, 6, signature);
+        assert!(auth.authorize_at(&request, now).is_err());
+    }
+    // An extreme timestamp is accepted when it matches the node time.
+    let timestamp = i64::max_value();
+    let signature = ApiAuth::sign_request("POST", uri, body, timestamp, 7, &secret_key);
+    let request = signed_request(public_key, Method::POST, timestamp, 7, signature);
+    assert!(auth.authorize_at(&request, timestamp).is_ok());
+}
+
+#[test]
+fn api_auth_rejects_replayed_requests() {
+    use actix_web::test::TestRequest;
+
+    let (public_key, secret_key) = crypto::gen_keypair();
+    let auth = ApiAuth {
+        admin_keys: vec![public_key],
+        signature_ttl: 10,
+        ..ApiAuth::default()
+    };
+    let uri = "/api/system/v1/shutdown";
+    let now = 1_000_000;
+    let signed_request = |timestamp: i64, nonce: u64| {
+        let signature = ApiAuth::sign_request("POST", uri, &[], timestamp, nonce, &secret_key);
+        TestRequest::with_header(ApiAuth::PUBLIC_KEY_HEADER, public_key.to_hex())
+            .header(ApiAuth::TIMESTAMP_HEADER, timestamp.to_string())
+            .header(ApiAuth::NONCE_HEADER, nonce.to_string())
+            .header(ApiAuth::CONTENT_HASH_HEADER, crypto::hash(&[]).to_hex())
+            .header(ApiAuth::SIGNATURE_HEADER, signature.to_hex())
+            .method(actix_web::http::Method::POST)
+            .uri(uri)
+            .finish()
+    };
+
+    let request = signed_request(now, 0);
+    assert!(auth.authorize_at(&request, now).is_ok());
+    assert!(auth.authorize_at(&request, now + 1).is_err());
+    // Clones of the configuration (e.g., used by different workers) share seen signatures.
+    assert!(auth.clone().authorize_at(&request, now + 1).is_err());
+    // The same request with another nonce is accepted.
+    assert!(auth.authorize_at(&signed_request(now, 1), now).is_ok());
+
+    // If too many signatures are remembered, new signed requests are rejected
+    // until the remembered signatures expire.
+    for nonce in 2..ApiAuth::MAX_SEEN_SIGNATURES as u64 {
+        assert!(auth.authorize_at(&signed_request(now, nonce), now).is_ok());
+    }
+    let request = signed_request(now, u64::max_value());
+    assert!(auth.authorize_at(&request, now).is_err());
+    let request = signed_request(now + 11, u64::max_value());
+    assert!(auth.authorize_at(&request, now + 11).is_ok());
+}
+
+#[test]
+fn api_auth_checks_signed_body() {
+    use actix_web::{http::Method, test::TestRequest};
+
+    let (public_key, secret_key) = crypto::gen_keypair();
+    let auth = ApiAuth::new(vec!["secret".to_owned()], vec![public_key]);
+    let uri = "/api/services/supervisor/propose-config";
+    let now = Utc::now().timestamp();
+    let signed_body = b"{\"value\":1}";
+    let content_hash = crypto::hash(signed_body);
+    let signed_request = |nonce: u64, body: &'static [u8]| {
+        let signature = ApiAuth::sign_request("POST", uri, signed_body, now, nonce, &secret_key);
+        TestRequest::with_header(ApiAuth::PUBLIC_KEY_HEADER, public_key.to_hex())
+            .header(ApiAuth::TIMESTAMP_HEADER, now.to_string())
+            .header(ApiAuth::NONCE_HEADER, nonce.to_string())
+            .header(ApiAuth::CONTENT_HASH_HEADER, content_hash.to_hex())
+            .header(ApiAuth::SIGNATURE_HEADER, signature.to_hex())
+            .method(Method::POST)
+            .uri(uri)
+            .set_payload(body)
+            .finish()
+    };
+
+    let request = signed_request(0, b"{\"value\":1}");
+    assert!(auth.authorize(&request).is_ok());
+    let value: serde_json::Value = read_json(&request).wait().unwrap();
+    assert_eq!(value, json!({ "value": 1 }));
+
+    let request = signed_request(1, b"{\"value\":2}");
+    assert!(auth.authorize(&request).is_ok());
+    let err = read_json::<serde_json::Value>(&request).wait().unwrap_err();
+    assert_eq!(
+        err.as_response_error().error_response().status(),
+        actix_web::http::StatusCode::UNAUTHORIZED
+    );
+
+    // Bodies of requests authorized with bearer tokens are not checked.
+    let request = TestRequest::with_header("Authorization", "Bearer secret")
+        .set_payload(&b"{\"value\":3}"[..])
+        .finish();
+    assert!(auth.authorize(&request).is_ok());
+    let value: serde_json::Value = read_json(&request).wait().unwrap();
+    assert_eq!(value, json!({ "value": 3 }));
+}
+
+#[test]
+fn tls_config_missing_files() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = TlsConfig {
+        certificate_path: dir.path().join("cert.pem"),
+        private_key_path: dir.path().join("key.pem"),
+        client_ca_path: None,
+    };
+    assert!(config.server_config().is_err());
+
+    std::fs::write(&config.certificate_path, "not a certificate").unwrap();
+    let err = config.server_config().unwrap_err();
+    assert!(err.to_string().contains("No certificates found"));
+}
@@ -0,0 +1,718 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-readable description of the API endpoints.
+//!
+//! `ApiScope` records request and response types of the endpoints added with
+//! the `endpoint` and `endpoint_mut` methods. For every mount point with at least
+//! one endpoint, the description is published as an [OpenAPI]-style document
+//! at the `openapi.json` endpoint, e.g., `api/explorer/openapi.json` or
+//! `api/services/{instance_name}/openapi.json`.
+//!
+//! The description lists the paths, methods and deprecation status of the endpoints
+//! together with the schemas of their query parameters, JSON bodies and responses.
+//! Schemas are provided by the [`ApiSchema`] trait, which must be implemented
+//! for the request and response types of the endpoints. Endpoints added as raw handlers
+//! are marked with the `x-raw-handler` extension field.
+//!
+//! # Examples
+//!
+//! ```
+//! use exonum::{api::ApiSchema, crypto::Hash, helpers::Height, impl_api_schema};
+//! use serde_derive::{Deserialize, Serialize};
+//! use serde_json::json;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! pub struct BlockHashQuery {
+//!     pub height: Height,
+//!     #[serde(default)]
+//!     pub with_proof: bool,
+//! }
+//!
+//! impl_api_schema!(BlockHashQuery {
+//!     height: Height,
+//!     #[default]
+//!     with_proof: bool,
+//! });
+//!
+//! let schema = BlockHashQuery::api_schema();
+//! assert_eq!(schema["type"], json!("object"));
+//! assert_eq!(schema["properties"]["with_proof"], json!({ "type": "boolean" }));
+//! assert_eq!(schema["required"], json!(["height"]));
+//! ```
+//!
+//! [OpenAPI]: https://swagger.io/specification/
+//! [`ApiSchema`]: trait.ApiSchema.html
+
+use actix_web::{http::Method, HttpResponse};
+use chrono::{DateTime, Utc};
+use exonum_merkledb::{ListProof, MapProof};
+use futures::future;
+use serde_json::{json, Map, Value};
+
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    sync::Arc,
+};
+
+use super::{
+    backends::actix::{FutureResponse, HttpRequest, RawHandler, RequestHandler},
+    ApiAccess, ApiScope, Deprecation,
+};
+use crate::{
+    blockchain::{
+        AdditionalHeaders, Block, BlockProof, ConsensusConfig, Equivocation, TxLocation,
+        ValidatorKeys,
+    },
+    crypto::{Hash, PublicKey, Signature, HASH_SIZE, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH},
+    explorer::{BlockWithTransactions, CommittedTransaction, StatusFilter, TransactionInfo},
+    helpers::{Height, Round, ValidatorId},
+    messages::{Precommit, SignedMessage, Verified},
+    node::ConnectInfo,
+    runtime::{error::ExecutionStatus, ArtifactId, CallInfo, InstanceSpec},
+};
+
+/// JSON schema of the values in the API description. Schemas follow the
+/// [OpenAPI] dialect of the JSON Schema, e.g., `null` values are denoted
+/// by the `nullable` keyword.
+///
+/// [OpenAPI]: https://swagger.io/specification/#schemaObject
+pub type Schema = Value;
+
+/// Type with the JSON schema published in the API description.
+///
+/// The trait must be implemented for the request and response types of the endpoints.
+/// The default implementation returns the empty schema, which matches any JSON value;
+/// it suits types with the complex serialization format, e.g., Merkle proofs.
+/// Schemas of structs are usually implemented with the [`impl_api_schema!`] macro.
+///
+/// [`impl_api_schema!`]: ../../macro.impl_api_schema.html
+pub trait ApiSchema {
+    /// Returns the schema of the JSON representation of the type.
+    fn api_schema() -> Schema {
+        json!({})
+    }
+}
+
+/// Implements `ApiSchema` for a struct with the named fields. The schema of the struct
+/// is an object with the schemas of the fields as its properties.
+///
+/// Fields with the nullable schemas (e.g., `Option`s) are not required; fields
+/// with the `serde(default)` attribute must be marked with `#[default]` so that they are
+/// not required either. See the [`description`] module for an example.
+///
+/// [`description`]: api/description/index.html
+#[macro_export]
+macro_rules! impl_api_schema {
+    (@default) => { false };
+    (@default default) => { true };
+    ($name:ty { $($(#[$default:ident])* $field:ident: $ty:ty),* $(,)? }) => {
+        impl $crate::api::ApiSchema for $name {
+            fn api_schema() -> $crate::api::Schema {
+                $crate::api::description::object_schema(vec![
+                    $((
+                        stringify!($field),
+                        <$ty as $crate::api::ApiSchema>::api_schema(),
+                        $crate::impl_api_schema!(@default $($default)*),
+                    )),*
+                ])
+            }
+        }
+    };
+}
+
+/// Returns the schema of an object with the given properties. Every property is specified
+/// by its name, schema and whether it has a default value. Properties without the default
+/// values and with non-nullable schemas are required.
+pub fn object_schema(properties: Vec<(&str, Schema, bool)>) -> Schema {
+    let mut required = Vec::new();
+    let mut schemas = Map::new();
+    for (name, schema, has_default) in properties {
+        if !has_default && schema.get("nullable") != Some(&json!(true)) {
+            required.push(json!(name));
+        }
+        schemas.insert(name.to_owned(), schema);
+    }
+
+    let mut schema = json!({ "type": "object", "properties": schemas });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+/// Returns the schema of a string enumeration, e.g., an enum with unit variants.
+pub fn enum_schema(values: &[&str]) -> Schema {
+    json!({ "type": "string", "enum": values })
+}
+
+/// Returns the schema of an internally tagged enum. Every variant is specified
+/// by its tag and the object schema of its content.
+pub fn tagged_schema(tag: &str, variants: Vec<(&str, Schema)>) -> Schema {
+    let variants = variants
+        .into_iter()
+        .map(|(name, content)| {
+            let mut schema = object_schema(vec![(tag, enum_schema(&[name]), false)]);
+            if let Some(properties) = content["properties"].as_object() {
+                for (key, value) in properties {
+                    schema["properties"][key] = value.clone();
+                }
+            }
+            if let Some(required) = content["required"].as_array() {
+                schema["required"]
+                    .as_array_mut()
+                    .expect("Tag is always required")
+                    .extend(required.iter().cloned());
+            }
+            schema
+        })
+        .collect::<Vec<_>>();
+    json!({ "oneOf": variants })
+}
+
+/// Returns the schema of a hex-encoded string, optionally, with the fixed number of bytes.
+pub fn hex_schema(len: Option<usize>) -> Schema {
+    let pattern = match len {
+        Some(len) => format!("^[0-9a-fA-F]{{{}}}$", len * 2),
+        None => "^([0-9a-fA-F]{2})*$".to_owned(),
+    };
+    json!({ "type": "string", "pattern": pattern })
+}
+
+impl ApiSchema for () {
+    fn api_schema() -> Schema {
+        json!({ "nullable": true, "enum": [null] })
+    }
+}
+
+impl ApiSchema for bool {
+    fn api_schema() -> Schema {
+        json!({ "type": "boolean" })
+    }
+}
+
+impl ApiSchema for String {
+    fn api_schema() -> Schema {
+        json!({ "type": "string" })
+    }
+}
+
+impl ApiSchema for Value {}
+
+macro_rules! impl_api_schema_for_integers {
+    ($($ty:ty => $format:expr, $minimum:expr;)*) => {
+        $(
+            impl ApiSchema for $ty {
+                fn api_schema() -> Schema {
+                    let mut schema = json!({ "type": "integer", "format": $format });
+                    if let Some(minimum) = $minimum {
+                        schema["minimum"] = json!(minimum);
+                    }
+                    schema
+                }
+            }
+        )*
+    };
+}
+
+impl_api_schema_for_integers! {
+    u8 => "int32", Some(0);
+    u16 => "int32", Some(0);
+    u32 => "int64", Some(0);
+    u64 => "int64", Some(0);
+    usize => "int64", Some(0);
+    i32 => "int32", None::<i32>;
+    i64 => "int64", None::<i32>;
+}
+
+impl<T: ApiSchema> ApiSchema for Option<T> {
+    fn api_schema() -> Schema {
+        let mut schema = T::api_schema();
+        schema["nullable"] = json!(true);
+        schema
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Vec<T> {
+    fn api_schema() -> Schema {
+        json!({ "type": "array", "items": T::api_schema() })
+    }
+}
+
+impl<K, V: ApiSchema, S> ApiSchema for HashMap<K, V, S> {
+    fn api_schema() -> Schema {
+        json!({ "type": "object", "additionalProperties": V::api_schema() })
+    }
+}
+
+impl<K, V: ApiSchema> ApiSchema for BTreeMap<K, V> {
+    fn api_schema() -> Schema {
+        json!({ "type": "object", "additionalProperties": V::api_schema() })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Range<T> {
+    fn api_schema() -> Schema {
+        object_schema(vec![
+            ("start", T::api_schema(), false),
+            ("end", T::api_schema(), false),
+        ])
+    }
+}
+
+impl ApiSchema for DateTime<Utc> {
+    fn api_schema() -> Schema {
+        json!({ "type": "string", "format": "date-time" })
+    }
+}
+
+impl ApiSchema for Hash {
+    fn api_schema() -> Schema {
+        hex_schema(Some(HASH_SIZE))
+    }
+}
+
+impl ApiSchema for PublicKey {
+    fn api_schema() -> Schema {
+        hex_schema(Some(PUBLIC_KEY_LENGTH))
+    }
+}
+
+impl ApiSchema for Signature {
+    fn api_schema() -> Schema {
+        hex_schema(Some(SIGNATURE_LENGTH))
+    }
+}
+
+impl ApiSchema for Height {
+    fn api_schema() -> Schema {
+        u64::api_schema()
+    }
+}
+
+impl ApiSchema for Round {
+    fn api_schema() -> Schema {
+        u32::api_schema()
+    }
+}
+
+impl ApiSchema for ValidatorId {
+    fn api_schema() -> Schema {
+        u16::api_schema()
+    }
+}
+
+impl ApiSchema for SignedMessage {
+    fn api_schema() -> Schema {
+        hex_schema(None)
+    }
+}
+
+impl<T> ApiSchema for Verified<T> {
+    fn api_schema() -> Schema {
+        hex_schema(None)
+    }
+}
+
+// Proofs have the complex recursive format, so their schemas are left unspecified.
+impl<V> ApiSchema for ListProof<V> {}
+
+impl<K, V, KeyMode> ApiSchema for MapProof<K, V, KeyMode> {}
+
+impl ApiSchema for AdditionalHeaders {
+    fn api_schema() -> Schema {
+        json!({ "type": "object", "additionalProperties": hex_schema(None) })
+    }
+}
+
+crate::impl_api_schema!(Block {
+    proposer_id: ValidatorId,
+    height: Height,
+    tx_count: u32,
+    prev_hash: Hash,
+    tx_hash: Hash,
+    state_hash: Hash,
+    timestamp: DateTime<Utc>,
+    #[default]
+    additional_headers: AdditionalHeaders,
+});
+
+crate::impl_api_schema!(BlockProof {
+    block: Block,
+    precommits: Vec<Verified<Precommit>>,
+});
+
+crate::impl_api_schema!(TxLocation {
+    block_height: Height,
+    position_in_block: u64,
+});
+
+crate::impl_api_schema!(ValidatorKeys {
+    consensus_key: PublicKey,
+    service_key: PublicKey,
+});
+
+crate::impl_api_schema!(ConsensusConfig {
+    #[default]
+    validator_keys: Vec<ValidatorKeys>,
+    first_round_timeout: u64,
+    status_timeout: u64,
+    peers_timeout: u64,
+    txs_block_limit: u32,
+    max_message_len: u32,
+    min_propose_timeout: u64,
+    max_propose_timeout: u64,
+    propose_timeout_threshold: u32,
+    #[default]
+    max_block_time_drift: u64,
+});
+
+crate::impl_api_schema!(ConnectInfo {
+    address: String,
+    public_key: PublicKey,
+});
+
+crate::impl_api_schema!(CallInfo {
+    instance_id: u32,
+    method_id: u32,
+});
+
+crate::impl_api_schema!(ArtifactId {
+    runtime_id: u32,
+    name: String,
+});
+
+crate::impl_api_schema!(InstanceSpec {
+    id: u32,
+    name: String,
+    artifact: ArtifactId,
+});
+
+impl ApiSchema for ExecutionStatus {
+    fn api_schema() -> Schema {
+        let error = || {
+            object_schema(vec![
+                ("description", String::api_schema(), false),
+                ("code", u8::api_schema(), false),
+            ])
+        };
+        tagged_schema(
+            "type",
+            vec![
+                ("success", object_schema(vec![])),
+                (
+                    "panic",
+                    object_schema(vec![("description", String::api_schema(), false)]),
+                ),
+                ("dispatcher_error", error()),
+                ("runtime_error", error()),
+                ("service_error", error()),
+            ],
+        )
+    }
+}
+
+impl ApiSchema for Equivocation {
+    fn api_schema() -> Schema {
+        let messages = || {
+            object_schema(vec![
+                ("first", hex_schema(None), false),
+                ("second", hex_schema(None), false),
+            ])
+        };
+        tagged_schema(
+            "type",
+            vec![("prevote", messages()), ("precommit", messages())],
+        )
+    }
+}
+
+impl ApiSchema for StatusFilter {
+    fn api_schema() -> Schema {
+        enum_schema(&["success", "failure"])
+    }
+}
+
+crate::impl_api_schema!(CommittedTransaction {
+    content: SignedMessage,
+    location: TxLocation,
+    location_proof: ListProof<Hash>,
+    status: ExecutionStatus,
+    time: DateTime<Utc>,
+});
+
+impl ApiSchema for TransactionInfo {
+    fn api_schema() -> Schema {
+        tagged_schema(
+            "type",
+            vec![
+                (
+                    "in-pool",
+                    object_schema(vec![("content", SignedMessage::api_schema(), false)]),
+                ),
+                ("committed", CommittedTransaction::api_schema()),
+            ],
+        )
+    }
+}
+
+crate::impl_api_schema!(BlockWithTransactions {
+    block: Block,
+    precommits: Vec<Verified<Precommit>>,
+    transactions: Vec<CommittedTransaction>,
+});
+
+/// Name of the endpoint with the API description.
+pub const DESCRIPTION_ENDPOINT: &str = "openapi.json";
+
+/// Description of the API endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndpointDescription {
+    /// Endpoint name relative to the mount point, e.g., `v1/blocks`.
+    pub name: String,
+    /// HTTP method of the endpoint.
+    pub method: String,
+    /// Whether the endpoint is a raw handler with an unknown request and response format.
+    pub raw: bool,
+    /// Whether the endpoint takes query parameters (for `GET` endpoints) or a JSON body
+    /// (for `POST` endpoints). Always `false` for raw handlers.
+    pub has_parameters: bool,
+    /// Schema of the query parameters or the JSON body of the endpoint. `None` for
+    /// raw handlers and endpoints without parameters.
+    pub request_schema: Option<Schema>,
+    /// Schema of the endpoint responses. `None` for raw handlers.
+    pub response_schema: Option<Schema>,
+    /// Deprecation of the endpoint, if any.
+    pub deprecation: Option<Deprecation>,
+}
+
+impl EndpointDescription {
+    /// Creates a description of the endpoint with the specified request and response types.
+    /// Endpoints with the `()` request type take no parameters.
+    pub(crate) fn typed<Q, I>(name: &str, method: &Method) -> Self
+    where
+        Q: ApiSchema + 'static,
+        I: ApiSchema,
+    {
+        let has_parameters = TypeId::of::<Q>() != TypeId::of::<()>();
+        Self {
+            name: name.to_owned(),
+            method: method.to_string(),
+            raw: false,
+            has_parameters,
+            request_schema: if has_parameters {
+                Some(Q::api_schema())
+            } else {
+                None
+            },
+            response_schema: Some(I::api_schema()),
+            deprecation: None,
+        }
+    }
+
+    /// Sets the deprecation of the endpoint.
+    pub(crate) fn with_deprecation(mut self, deprecation: Option<Deprecation>) -> Self {
+        self.deprecation = deprecation;
+        self
+    }
+
+    /// Creates a description of the raw handler.
+    pub(crate) fn raw(name: &str, method: &Method) -> Self {
+        Self {
+            name: name.to_owned(),
+            method: method.to_string(),
+            raw: true,
+            has_parameters: false,
+            request_schema: None,
+            response_schema: None,
+            deprecation: None,
+        }
+    }
+
+    fn operation(&self) -> Value {
+        if self.raw {
+            return json!({
+                "operationId": self.name,
+                "x-raw-handler": true,
+                "responses": {
+                    "default": { "description": "Response of the raw handler." }
+                },
+            });
+        }
+
+        let response_schema = self.response_schema.clone().unwrap_or_else(|| json!({}));
+        let mut operation = json!({
+            "operationId": self.name,
+            "responses": {
+                "200": {
+                    "description": "Successful response.",
+                    "content": {
+                        "application/json": {
+                            "schema": response_schema
+                        }
+                    }
+                }
+            },
+        });
+        if let Some(ref deprecation) = self.deprecation {
+            operation["deprecated"] = json!(true);
+            if let Some(removal_time) = deprecation.removal_time {
+                operation["x-removal-time"] = json!(removal_time);
+            }
+        }
+        let request_schema = self.request_schema.clone().unwrap_or_else(|| json!({}));
+        if self.has_parameters && self.method == Method::GET.as_str() {
+            operation["parameters"] = json!([{
+                "name": "query",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "schema": request_schema,
+            }]);
+        } else if self.has_parameters {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": request_schema } },
+            });
+        }
+        operation
+    }
+}
+
+/// Creates an OpenAPI-style document describing the endpoints of the given mount point.
+pub fn openapi_document(
+    mount_point: &str,
+    access: ApiAccess,
+    endpoints: &[EndpointDescription],
+) -> Value {
+    let mut paths = Map::new();
+    for endpoint in endpoints {
+        let path = format!("/api/{}/{}", mount_point, endpoint.name);
+        let item = paths.entry(path).or_insert_with(|| json!({}));
+        item[endpoint.method.to_lowercase()] = endpoint.operation();
+    }
+
+    json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": format!("{} {} API", mount_point, access),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "x-api-access": access.to_string(),
+        "paths": paths,
+    })
+}
+
+impl ApiScope {
+    /// Returns a copy of the scope with the additional endpoint publishing
+    /// the description of the scope. Empty scopes are returned as is.
+    pub(crate) fn with_description(&self, mount_point: &str, access: ApiAccess) -> Self {
+        let mut scope = self.clone();
+        let endpoints = self.endpoints();
+        if endpoints.is_empty() {
+            return scope;
+        }
+
+        let document = Arc::new(openapi_document(mount_point, access, &endpoints));
+        let index = move |_request: HttpRequest| -> FutureResponse {
+            Box::new(future::ok(HttpResponse::Ok().json(document.as_ref())))
+        };
+        scope.web_backend().raw_handler(RequestHandler {
+            name: DESCRIPTION_ENDPOINT.to_owned(),
+            method: Method::GET,
+            inner: Arc::from(index) as Arc<RawHandler>,
+        });
+        scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_for_typed_and_raw_endpoints() {
+        let endpoints = vec![
+            EndpointDescription::typed::<(), u64>("v1/count", &Method::GET),
+            EndpointDescription::typed::<u64, Hash>("v1/count", &Method::POST),
+            EndpointDescription::typed::<String, Option<String>>("v1/info", &Method::GET),
+            EndpointDescription::raw("v1/subscribe", &Method::GET),
+        ];
+        let document = openapi_document("services/counter", ApiAccess::Public, &endpoints);
+
+        let count = &document["paths"]["/api/services/counter/v1/count"];
+        assert!(count["get"].get("parameters").is_none());
+        assert!(count["get"].get("requestBody").is_none());
+        assert_eq!(
+            count["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+            u64::api_schema()
+        );
+        assert_eq!(count["post"]["requestBody"]["required"], json!(true));
+        assert_eq!(
+            count["post"]["requestBody"]["content"]["application/json"]["schema"],
+            u64::api_schema()
+        );
+        assert_eq!(
+            count["post"]["responses"]["200"]["content"]["application/json"]["schema"],
+            hex_schema(Some(HASH_SIZE))
+        );
+        assert!(count["post"].get("parameters").is_none());
+
+        let info = &document["paths"]["/api/services/counter/v1/info"]["get"];
+        assert_eq!(info["parameters"][0]["in"], json!("query"));
+        assert_eq!(info["parameters"][0]["schema"], json!({ "type": "string" }));
+        assert_eq!(
+            info["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "type": "string", "nullable": true })
+        );
+        assert!(info.get("requestBody").is_none());
+
+        let subscribe = &document["paths"]["/api/services/counter/v1/subscribe"]["get"];
+        assert_eq!(subscribe["x-raw-handler"], json!(true));
+        assert_eq!(document["x-api-access"], json!("public"));
+    }
+
+    #[test]
+    fn object_schemas() {
+        struct Query;
+
+        impl_api_schema!(Query {
+            height: Height,
+            author: Option<PublicKey>,
+            #[default]
+            verbose: bool,
+        });
+
+        let schema = Query::api_schema();
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(schema["properties"]["height"], Height::api_schema());
+        assert_eq!(schema["properties"]["author"]["nullable"], json!(true));
+        assert_eq!(schema["required"], json!(["height"]));
+
+        let schema = ExecutionStatus::api_schema();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 5);
+        assert_eq!(
+            variants[0]["properties"]["type"]["enum"],
+            json!(["success"])
+        );
+        assert_eq!(variants[0]["required"], json!(["type"]));
+        assert_eq!(
+            variants[2]["required"],
+            json!(["type", "description", "code"])
+        );
+    }
+}
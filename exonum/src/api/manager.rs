@@ -77,12 +77,23 @@ impl ApiManager {
         let listen_address = runtime_config.listen_address;
         info!("Starting {} web api on {}", access, listen_address);
 
+        let tls_config = match runtime_config.tls {
+            Some(ref tls) => Some(
+                tls.server_config()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?,
+            ),
+            None => None,
+        };
+
         let mut aggregator = self.runtime_config.api_aggregator.clone();
         aggregator.extend(self.user_endpoints.clone());
-        HttpServer::new(move || create_app(&aggregator, runtime_config.clone()))
-            .disable_signals()
-            .bind(listen_address)
-            .map(HttpServer::start)
+        let server = HttpServer::new(move || create_app(&aggregator, runtime_config.clone()))
+            .disable_signals();
+        let server = match tls_config {
+            Some(tls_config) => server.bind_rustls(listen_address, tls_config)?,
+            None => server.bind(listen_address)?,
+        };
+        Ok(server.start())
     }
 
     fn initiate_restart(&mut self, manager: Addr<Self>) {
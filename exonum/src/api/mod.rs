@@ -15,16 +15,21 @@
 //! API and corresponding utilities.
 
 pub use self::{
+    description::{ApiSchema, EndpointDescription, Schema},
     error::Error,
+    versioning::{Deprecation, VersionedEndpoint},
     with::{FutureResult, Immutable, Mutable, NamedWith, Result, With},
 };
 
 pub mod backends;
+pub mod description;
 pub mod error;
 pub mod manager;
 pub mod node;
+pub mod versioning;
 pub mod websocket;
 
+use actix_web::http::Method;
 use serde::{de::DeserializeOwned, Serialize};
 
 use std::{collections::BTreeMap, fmt};
@@ -90,6 +95,7 @@ pub trait ApiBackend: Sized {
 #[derive(Debug, Clone, Default)]
 pub struct ApiScope {
     pub(crate) actix_backend: actix::ApiBuilder,
+    descriptions: Vec<EndpointDescription>,
 }
 
 impl ApiScope {
@@ -106,15 +112,21 @@ impl ApiScope {
     /// - Query parameters should be decodable via `serde_urlencoded`, i.e. from the
     ///   "first_param=value1&second_param=value2" form.
     /// - Response items should be encodable via `serde_json` crate.
+    /// - Query parameters and response items should implement [`ApiSchema`], which
+    ///   provides their schemas for the [API description](description/index.html).
+    ///
+    /// [`ApiSchema`]: description/trait.ApiSchema.html
     pub fn endpoint<Q, I, R, F, E>(&mut self, name: &'static str, endpoint: E) -> &mut Self
     where
-        Q: DeserializeOwned + 'static,
-        I: Serialize + 'static,
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
         F: Fn(Q) -> R + 'static + Clone,
         E: Into<With<Q, I, R, F>>,
         actix::RequestHandler: From<NamedWith<Q, I, R, F, Immutable>>,
     {
         self.actix_backend.endpoint(name, endpoint);
+        self.descriptions
+            .push(EndpointDescription::typed::<Q, I>(name, &Method::GET));
         self
     }
 
@@ -125,15 +137,78 @@ impl ApiScope {
     ///
     /// - Query parameters should be decodable via `serde_json`.
     /// - Response items also should be encodable via `serde_json` crate.
+    /// - Query parameters and response items should implement [`ApiSchema`].
+    ///
+    /// [`ApiSchema`]: description/trait.ApiSchema.html
     pub fn endpoint_mut<Q, I, R, F, E>(&mut self, name: &'static str, endpoint: E) -> &mut Self
     where
-        Q: DeserializeOwned + 'static,
-        I: Serialize + 'static,
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
         F: Fn(Q) -> R + 'static + Clone,
         E: Into<With<Q, I, R, F>>,
         actix::RequestHandler: From<NamedWith<Q, I, R, F, Mutable>>,
     {
         self.actix_backend.endpoint_mut(name, endpoint);
+        self.descriptions
+            .push(EndpointDescription::typed::<Q, I>(name, &Method::POST));
+        self
+    }
+
+    /// Add the given endpoint handler with the explicit version to the API scope.
+    /// The endpoint is mounted at the `v{version}/{name}` path; if the endpoint is deprecated,
+    /// its responses contain the deprecation headers.
+    /// [Read more.](versioning/index.html)
+    ///
+    /// Otherwise, this method is equivalent to [`endpoint`](#method.endpoint).
+    pub fn versioned_endpoint<Q, I, R, F, E>(
+        &mut self,
+        endpoint: VersionedEndpoint,
+        handler: E,
+    ) -> &mut Self
+    where
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
+        F: Fn(Q) -> R + 'static + Clone,
+        E: Into<With<Q, I, R, F>>,
+        actix::RequestHandler: From<NamedWith<Q, I, R, F, Immutable>>,
+    {
+        let path = endpoint.path();
+        let named_with: NamedWith<Q, I, R, F, Immutable> = NamedWith::new(path.as_str(), handler);
+        let handler = endpoint.wrap_handler(actix::RequestHandler::from(named_with));
+        self.actix_backend.raw_handler(handler);
+        self.descriptions.push(
+            EndpointDescription::typed::<Q, I>(&path, &Method::GET)
+                .with_deprecation(endpoint.deprecation),
+        );
+        self
+    }
+
+    /// Add the given mutable endpoint handler with the explicit version to the API scope.
+    /// The endpoint is mounted at the `v{version}/{name}` path; if the endpoint is deprecated,
+    /// its responses contain the deprecation headers.
+    /// [Read more.](versioning/index.html)
+    ///
+    /// Otherwise, this method is equivalent to [`endpoint_mut`](#method.endpoint_mut).
+    pub fn versioned_endpoint_mut<Q, I, R, F, E>(
+        &mut self,
+        endpoint: VersionedEndpoint,
+        handler: E,
+    ) -> &mut Self
+    where
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
+        F: Fn(Q) -> R + 'static + Clone,
+        E: Into<With<Q, I, R, F>>,
+        actix::RequestHandler: From<NamedWith<Q, I, R, F, Mutable>>,
+    {
+        let path = endpoint.path();
+        let named_with: NamedWith<Q, I, R, F, Mutable> = NamedWith::new(path.as_str(), handler);
+        let handler = endpoint.wrap_handler(actix::RequestHandler::from(named_with));
+        self.actix_backend.raw_handler(handler);
+        self.descriptions.push(
+            EndpointDescription::typed::<Q, I>(&path, &Method::POST)
+                .with_deprecation(endpoint.deprecation),
+        );
         self
     }
 
@@ -141,6 +216,25 @@ impl ApiScope {
     pub fn web_backend(&mut self) -> &mut actix::ApiBuilder {
         &mut self.actix_backend
     }
+
+    /// Return descriptions of all endpoints in the scope. Endpoints added directly
+    /// to the web backend are described as raw handlers.
+    pub fn endpoints(&self) -> Vec<EndpointDescription> {
+        self.actix_backend
+            .handlers()
+            .iter()
+            .map(|handler| {
+                self.descriptions
+                    .iter()
+                    .find(|description| {
+                        description.name == handler.name
+                            && description.method == handler.method.as_str()
+                    })
+                    .cloned()
+                    .unwrap_or_else(|| EndpointDescription::raw(&handler.name, &handler.method))
+            })
+            .collect()
+    }
 }
 
 /// Exonum API builder, which is used to add endpoints to the node API.
@@ -229,14 +323,22 @@ impl ApiAggregator {
     }
 
     /// Extend the given API backend by handlers with the given access level.
+    ///
+    /// Each non-empty scope is extended by the `openapi.json` endpoint describing
+    /// the endpoints of the scope; see the [`description`](description/index.html) module.
     pub fn extend_backend<B: ExtendApiBackend>(&self, access: ApiAccess, backend: B) -> B {
-        let endpoints = self.endpoints.iter();
-        match access {
-            ApiAccess::Public => backend
-                .extend(endpoints.map(|(name, builder)| (name.as_str(), &builder.public_scope))),
-            ApiAccess::Private => backend
-                .extend(endpoints.map(|(name, builder)| (name.as_str(), &builder.private_scope))),
-        }
+        let scopes = self
+            .endpoints
+            .iter()
+            .map(|(name, builder)| {
+                let scope = match access {
+                    ApiAccess::Public => &builder.public_scope,
+                    ApiAccess::Private => &builder.private_scope,
+                };
+                (name.as_str(), scope.with_description(name, access))
+            })
+            .collect::<Vec<_>>();
+        backend.extend(scopes.iter().map(|(name, scope)| (*name, scope)))
     }
 
     fn explorer_api(blockchain: Blockchain, shared_node_state: SharedNodeState) -> ApiBuilder {
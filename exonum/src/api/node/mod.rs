@@ -14,22 +14,22 @@
 
 //! Exonum node API implementation.
 
-use actix::Addr;
+use actix::{Addr, Arbiter};
 
 use std::{
     collections::{HashMap, HashSet},
     fmt,
     net::SocketAddr,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use crate::{
     api::websocket,
-    blockchain::ValidatorKeys,
+    blockchain::{Blockchain, ValidatorKeys},
     crypto::Hash,
     events::network::ConnectedPeerAddr,
     helpers::Milliseconds,
-    node::{ConnectInfo, NodeRole, State},
+    node::{ConnectInfo, ConsensusEventBus, Metrics, NodeRole, State},
 };
 
 pub mod private;
@@ -80,6 +80,11 @@ impl ApiNodeState {
 #[derive(Clone, Debug)]
 pub struct SharedNodeState {
     node: Arc<RwLock<ApiNodeState>>,
+    metrics: Arc<Metrics>,
+    consensus_events: Arc<ConsensusEventBus>,
+    /// Serializes starts of the broadcast server, so that the node state is not locked
+    /// while the server is being started.
+    broadcast_server_start: Arc<Mutex<()>>,
     /// Timeout to update API state.
     pub state_update_timeout: Milliseconds,
 }
@@ -89,10 +94,23 @@ impl SharedNodeState {
     pub fn new(state_update_timeout: Milliseconds) -> Self {
         Self {
             node: Arc::new(RwLock::new(ApiNodeState::new())),
+            metrics: Arc::new(Metrics::new()),
+            consensus_events: Arc::new(ConsensusEventBus::new()),
+            broadcast_server_start: Arc::new(Mutex::new(())),
             state_update_timeout,
         }
     }
 
+    /// Returns metrics collected by the node.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Returns the bus of consensus events emitted by the node.
+    pub fn consensus_events(&self) -> &ConsensusEventBus {
+        &self.consensus_events
+    }
+
     /// Returns a list of connected addresses of other nodes.
     pub fn incoming_connections(&self) -> Vec<ConnectInfo> {
         self.node
@@ -230,9 +248,37 @@ impl SharedNodeState {
             .remove(addr)
     }
 
-    pub(crate) fn set_broadcast_server_address(&self, address: Addr<websocket::Server>) {
-        let mut node = self.node.write().expect("Expected write lock");
-        node.broadcast_server_address = Some(address);
+    /// Returns the address of the server broadcasting notifications about committed blocks,
+    /// starting the server on the first call.
+    pub(crate) fn broadcast_server(&self, blockchain: &Blockchain) -> Addr<websocket::Server> {
+        if let Some(address) = self.broadcast_server_address() {
+            return address;
+        }
+
+        // The server is started without holding the node state lock, since the start
+        // blocks until the server thread is spawned.
+        let _guard = self
+            .broadcast_server_start
+            .lock()
+            .expect("Expected mutex lock");
+        if let Some(address) = self.broadcast_server_address() {
+            return address;
+        }
+        let blockchain = blockchain.clone();
+        let address = Arbiter::start(|_| websocket::Server::new(blockchain));
+        self.node
+            .write()
+            .expect("Expected write lock")
+            .broadcast_server_address = Some(address.clone());
+        address
+    }
+
+    fn broadcast_server_address(&self) -> Option<Addr<websocket::Server>> {
+        self.node
+            .read()
+            .expect("Expected read lock")
+            .broadcast_server_address
+            .clone()
     }
 
     /// Broadcast message to all subscribers.
@@ -20,7 +20,10 @@
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use crate::{
-    api::{node::SharedNodeState, ApiBackend, ApiScope, Error as ApiError},
+    api::{
+        description, node::SharedNodeState, ApiBackend, ApiSchema, ApiScope, Error as ApiError,
+        Schema,
+    },
     crypto::PublicKey,
     node::{ApiSender, ConnectInfo, ExternalMessage},
     runtime::InstanceId,
@@ -91,6 +94,37 @@ struct ConsensusEnabledQuery {
     enabled: bool,
 }
 
+crate::impl_api_schema!(NodeInfo {
+    core_version: Option<String>,
+});
+
+impl ApiSchema for IncomingConnectionState {
+    fn api_schema() -> Schema {
+        description::tagged_schema(
+            "type",
+            vec![
+                ("Active", description::object_schema(vec![])),
+                (
+                    "Reconnect",
+                    description::object_schema(vec![("delay", u64::api_schema(), false)]),
+                ),
+            ],
+        )
+    }
+}
+
+crate::impl_api_schema!(IncomingConnection {
+    public_key: Option<PublicKey>,
+    state: IncomingConnectionState,
+});
+
+crate::impl_api_schema!(PeersInfo {
+    incoming_connections: Vec<ConnectInfo>,
+    outgoing_connections: HashMap<SocketAddr, IncomingConnection>,
+});
+
+crate::impl_api_schema!(ConsensusEnabledQuery { enabled: bool });
+
 /// Private system API.
 #[derive(Clone, Debug)]
 pub struct SystemApi {
@@ -116,6 +150,8 @@ impl SystemApi {
             .handle_network_info("v1/network", api_scope)
             .handle_is_consensus_enabled("v1/consensus_enabled", api_scope)
             .handle_set_consensus_enabled("v1/consensus_enabled", api_scope)
+            .handle_metrics("v1/metrics", api_scope)
+            .handle_consensus_events("v1/consensus_events/subscribe", api_scope)
             .handle_shutdown("v1/shutdown", api_scope);
         api_scope
     }
@@ -188,6 +224,59 @@ impl SystemApi {
         self_
     }
 
+    fn handle_metrics(self, name: &'static str, api_scope: &mut ApiScope) -> Self {
+        // Metrics are served in the Prometheus text format rather than in JSON,
+        // so we have to use the backend-dependent raw handler.
+        use crate::api::backends::actix::{FutureResponse, RawHandler, RequestHandler};
+        use actix_web::{HttpRequest, HttpResponse};
+        use futures::future;
+
+        let self_ = self.clone();
+
+        let index = move |_request: HttpRequest| -> FutureResponse {
+            let response = HttpResponse::Ok()
+                .content_type("text/plain; version=0.0.4")
+                .body(self.shared_api_state.metrics().to_prometheus());
+            Box::new(future::ok(response))
+        };
+
+        let handler = RequestHandler {
+            name: name.to_owned(),
+            method: actix_web::http::Method::GET,
+            inner: Arc::from(index) as Arc<RawHandler>,
+        };
+
+        api_scope.web_backend().raw_handler(handler);
+
+        self_
+    }
+
+    fn handle_consensus_events(self, name: &'static str, api_scope: &mut ApiScope) -> Self {
+        use crate::api::{
+            backends::actix::{FutureResponse, RawHandler, RequestHandler},
+            websocket::ConsensusEventsSession,
+        };
+        use actix_web::{ws, HttpRequest};
+        use futures::IntoFuture;
+
+        let self_ = self.clone();
+
+        let index = move |request: HttpRequest| -> FutureResponse {
+            let events = self.shared_api_state.consensus_events().subscribe();
+            Box::new(ws::start(&request, ConsensusEventsSession::new(events)).into_future())
+        };
+
+        let handler = RequestHandler {
+            name: name.to_owned(),
+            method: actix_web::http::Method::GET,
+            inner: Arc::from(index) as Arc<RawHandler>,
+        };
+
+        api_scope.web_backend().raw_handler(handler);
+
+        self_
+    }
+
     fn handle_shutdown(self, name: &'static str, api_scope: &mut ApiScope) -> Self {
         // These backend-dependent uses are needed to provide realization of the support of empty
         // request which is not easy in the generic approach, so it will be harder to misuse
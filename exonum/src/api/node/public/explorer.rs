@@ -14,16 +14,20 @@
 
 //! Exonum blockchain explorer API.
 
-use actix::Arbiter;
-use actix_web::{http, ws, AsyncResponder, Error as ActixError, FromRequest, Query};
+use actix_web::{
+    http, ws, AsyncResponder, Error as ActixError, FromRequest, HttpMessage, HttpResponse, Query,
+};
 use chrono::{DateTime, Utc};
-use exonum_merkledb::{ObjectHash, Snapshot};
-use futures::{Future, IntoFuture, Sink};
+use exonum_merkledb::{BinaryValue, ObjectHash, Snapshot};
+use exonum_proto::ProtobufConvert;
+use futures::{future, sync::oneshot, Future, IntoFuture, Sink};
 use hex::FromHex;
+use tokio::timer::Delay;
 
 use std::{
     ops::{Bound, Range},
-    sync::{Arc, Mutex},
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -31,23 +35,54 @@ use crate::{
         backends::actix::{
             self as actix_backend, FutureResponse, HttpRequest, RawHandler, RequestHandler,
         },
+        description,
         node::SharedNodeState,
-        websocket::{Server, Session, SubscriptionType, TransactionFilter},
-        ApiBackend, ApiScope, Error as ApiError, FutureResult,
+        websocket::{EventFilter, Session, SubscriptionType, TransactionFilter, WaitForCommit},
+        ApiBackend, ApiSchema, ApiScope, Error as ApiError, FutureResult, Schema,
+    },
+    blockchain::{Block, Blockchain, Equivocation, TxLocation},
+    crypto::{Hash, PublicKey},
+    explorer::{
+        self, median_precommits_time, BlockchainExplorer, CommittedTransaction,
+        CommittedTransactionFilter, StatusFilter, TransactionInfo,
     },
-    blockchain::{Block, Blockchain},
-    crypto::Hash,
-    explorer::{self, median_precommits_time, BlockchainExplorer, TransactionInfo},
-    helpers::Height,
-    messages::{Precommit, SignedMessage, Verified},
+    helpers::{Height, ValidatorId},
+    messages::{AnyTx, Precommit, SignedMessage, Verified},
     node::{ApiSender, ExternalMessage},
-    runtime::CallInfo,
+    proto::schema::consensus,
+    runtime::{CallInfo, InstanceId, MethodId},
 };
 
 /// The maximum number of blocks to return per blocks request, in this way
 /// the parameter limits the maximum execution time for such requests.
 pub const MAX_BLOCKS_PER_REQUEST: usize = 1000;
 
+/// The maximum number of transactions to return per transactions list request.
+pub const MAX_TRANSACTIONS_PER_REQUEST: usize = 1000;
+
+/// The maximum number of blocks scanned per transactions list request. If the limit
+/// is reached, the request returns a partial page with the cursor to continue the scan.
+pub const MAX_SCANNED_BLOCKS_PER_REQUEST: u64 = 1000;
+
+/// The maximum number of transactions in a single batch submission request. Signatures
+/// of the batch are verified by the API worker, so the limit bounds the request handling time.
+pub const MAX_TRANSACTIONS_PER_BATCH: usize = 1_000;
+
+/// The maximum size of the batch submission request body in bytes.
+const MAX_BATCH_BODY_SIZE: usize = 32 * 1024 * 1024;
+
+/// The default time to wait for the transaction commitment, in milliseconds.
+pub const DEFAULT_COMMIT_TIMEOUT: u64 = 10_000;
+
+/// The maximum time to wait for the transaction commitment, in milliseconds.
+pub const MAX_COMMIT_TIMEOUT: u64 = 30_000;
+
+/// The maximum number of requests simultaneously waiting for the transaction commitment.
+pub const MAX_COMMIT_WAITERS: usize = 10_000;
+
+/// Content types of the binary batch submission request body.
+const PROTOBUF_CONTENT_TYPES: &[&str] = &["application/x-protobuf", "application/octet-stream"];
+
 /// Information on blocks coupled with the corresponding range in the blockchain.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct BlocksRange {
@@ -131,6 +166,13 @@ impl BlockQuery {
     }
 }
 
+/// Equivocations query parameters.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub struct EquivocationsQuery {
+    /// If specified, only equivocations of the validator with the given ID are returned.
+    pub validator: Option<ValidatorId>,
+}
+
 /// Raw Transaction in hex representation.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TransactionHex {
@@ -145,6 +187,26 @@ pub struct TransactionResponse {
     pub tx_hash: Hash,
 }
 
+/// Batch of signed transactions in the binary format, which is accepted
+/// by the batch submission endpoint along with the JSON array of `TransactionHex`es.
+#[derive(Debug, Clone, PartialEq, ProtobufConvert, BinaryValue)]
+#[protobuf_convert(source = "consensus::TransactionsBatch")]
+pub struct TransactionsBatch {
+    /// Signed transactions.
+    pub transactions: Vec<SignedMessage>,
+}
+
+/// Result of the submission of a transaction from the batch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchTransactionResponse {
+    /// Hash of the transaction. `None` if the transaction cannot be decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<Hash>,
+    /// Verification error. `None` if the transaction has been sent to the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// Transaction query parameters.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct TransactionQuery {
@@ -152,6 +214,27 @@ pub struct TransactionQuery {
     pub hash: Hash,
 }
 
+/// Parameters of the request waiting for the transaction commitment.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WaitForCommitQuery {
+    /// The hash of the transaction.
+    pub hash: Hash,
+    /// The maximum time to wait for the transaction commitment, in milliseconds.
+    /// The default value is `DEFAULT_COMMIT_TIMEOUT`; values greater than `MAX_COMMIT_TIMEOUT`
+    /// are truncated.
+    pub timeout: Option<u64>,
+}
+
+impl WaitForCommitQuery {
+    /// Creates a new query with the given transaction hash and the default timeout.
+    pub fn new(hash: Hash) -> Self {
+        Self {
+            hash,
+            timeout: None,
+        }
+    }
+}
+
 impl TransactionQuery {
     /// Creates a new transaction query with the given height.
     pub fn new(hash: Hash) -> Self {
@@ -159,6 +242,86 @@ impl TransactionQuery {
     }
 }
 
+/// Committed transactions list parameters.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TransactionsQuery {
+    /// The number of transactions to return. Should not be greater than
+    /// `MAX_TRANSACTIONS_PER_REQUEST`.
+    pub count: usize,
+    /// If specified, only transactions addressed to the service instance
+    /// with the given ID are returned.
+    pub instance_id: Option<InstanceId>,
+    /// If specified, only transactions calling the method with the given ID are returned.
+    pub method_id: Option<MethodId>,
+    /// If specified, only transactions signed by the given key are returned.
+    pub author: Option<PublicKey>,
+    /// If specified, only transactions with the given execution result are returned.
+    pub status: Option<StatusFilter>,
+    /// Height of the block containing the pagination cursor. If specified, only transactions
+    /// committed before the cursor are returned.
+    pub before_height: Option<Height>,
+    /// Position of the pagination cursor in the block. The default value is 0, that is,
+    /// only transactions from blocks preceding `before_height` are returned.
+    pub before_position: Option<u64>,
+}
+
+impl TransactionsQuery {
+    fn filter(&self) -> CommittedTransactionFilter {
+        CommittedTransactionFilter {
+            instance_id: self.instance_id,
+            method_id: self.method_id,
+            author: self.author,
+            status: self.status,
+        }
+    }
+
+    fn cursor(&self) -> Option<TxLocation> {
+        self.before_height
+            .map(|height| TxLocation::new(height, self.before_position.unwrap_or_default()))
+    }
+}
+
+/// Parameters of the request for transactions signed by the specific key.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransactionsByAuthorQuery {
+    /// Author of the transactions.
+    pub author: PublicKey,
+    /// The number of transactions to return. Should not be greater than
+    /// `MAX_TRANSACTIONS_PER_REQUEST`.
+    pub count: usize,
+    /// Height of the block containing the pagination cursor. If specified, only transactions
+    /// committed before the cursor are returned.
+    pub before_height: Option<Height>,
+    /// Position of the pagination cursor in the block. The default value is 0.
+    pub before_position: Option<u64>,
+}
+
+impl From<TransactionsByAuthorQuery> for TransactionsQuery {
+    fn from(query: TransactionsByAuthorQuery) -> Self {
+        Self {
+            count: query.count,
+            author: Some(query.author),
+            before_height: query.before_height,
+            before_position: query.before_position,
+            ..Self::default()
+        }
+    }
+}
+
+/// Page of the committed transactions list.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionsList {
+    /// Committed transactions in the reverse order of their commitment.
+    pub transactions: Vec<CommittedTransaction>,
+    /// Location which should be passed as `before_height` and `before_position`
+    /// to get the next page. `None` if the returned page is the last one.
+    ///
+    /// A page may contain fewer transactions than requested (or none at all) even if
+    /// it is not the last one, since the number of blocks scanned per request is limited
+    /// by `MAX_SCANNED_BLOCKS_PER_REQUEST`. The next page may also be empty.
+    pub next: Option<TxLocation>,
+}
+
 impl AsRef<str> for TransactionHex {
     fn as_ref(&self) -> &str {
         self.tx_body.as_ref()
@@ -171,6 +334,90 @@ impl AsRef<[u8]> for TransactionHex {
     }
 }
 
+crate::impl_api_schema!(BlocksRange {
+    range: Range<Height>,
+    blocks: Vec<BlockInfo>,
+});
+
+crate::impl_api_schema!(TxInfo {
+    tx_hash: Hash,
+    call_info: CallInfo,
+});
+
+impl ApiSchema for BlockInfo {
+    fn api_schema() -> Schema {
+        // Fields of the block are flattened into the block info.
+        let mut schema = Block::api_schema();
+        let optional_fields = vec![
+            (
+                "precommits",
+                Option::<Vec<Verified<Precommit>>>::api_schema(),
+            ),
+            ("txs", Option::<Vec<TxInfo>>::api_schema()),
+            ("time", Option::<DateTime<Utc>>::api_schema()),
+        ];
+        for (name, field_schema) in optional_fields {
+            schema["properties"][name] = field_schema;
+        }
+        schema
+    }
+}
+
+crate::impl_api_schema!(BlocksQuery {
+    count: usize,
+    latest: Option<Height>,
+    earliest: Option<Height>,
+    #[default]
+    skip_empty_blocks: bool,
+    #[default]
+    add_blocks_time: bool,
+    #[default]
+    add_precommits: bool,
+});
+
+crate::impl_api_schema!(BlockQuery { height: Height });
+
+crate::impl_api_schema!(EquivocationsQuery {
+    validator: Option<ValidatorId>,
+});
+
+impl ApiSchema for TransactionHex {
+    fn api_schema() -> Schema {
+        description::object_schema(vec![("tx_body", description::hex_schema(None), false)])
+    }
+}
+
+crate::impl_api_schema!(TransactionResponse { tx_hash: Hash });
+
+crate::impl_api_schema!(TransactionQuery { hash: Hash });
+
+crate::impl_api_schema!(WaitForCommitQuery {
+    hash: Hash,
+    timeout: Option<u64>,
+});
+
+crate::impl_api_schema!(TransactionsQuery {
+    count: usize,
+    instance_id: Option<InstanceId>,
+    method_id: Option<MethodId>,
+    author: Option<PublicKey>,
+    status: Option<StatusFilter>,
+    before_height: Option<Height>,
+    before_position: Option<u64>,
+});
+
+crate::impl_api_schema!(TransactionsByAuthorQuery {
+    author: PublicKey,
+    count: usize,
+    before_height: Option<Height>,
+    before_position: Option<u64>,
+});
+
+crate::impl_api_schema!(TransactionsList {
+    transactions: Vec<CommittedTransaction>,
+    next: Option<TxLocation>,
+});
+
 /// Exonum blockchain explorer API.
 #[derive(Debug, Clone)]
 pub struct ExplorerApi {
@@ -276,6 +523,117 @@ impl ExplorerApi {
             })
     }
 
+    /// Wait until the transaction is committed or the timeout expires. Resolves to
+    /// the committed transaction with its execution status, location and location proof,
+    /// or to the transaction in the pool if the timeout has expired. Transactions
+    /// unknown to the node are waited for as well, since a just submitted transaction
+    /// may not have reached the pool yet.
+    ///
+    /// Waiting requests are woken up by the notifications about committed blocks
+    /// sent to the websocket server; at most `MAX_COMMIT_WAITERS` requests may wait
+    /// simultaneously. Only the node sends these notifications; blocks created
+    /// by other means, e.g., by the testkit, do not wake the waiting requests,
+    /// so such requests resolve once the timeout expires.
+    pub fn wait_for_commit(
+        blockchain: Blockchain,
+        shared_node_state: &SharedNodeState,
+        query: WaitForCommitQuery,
+    ) -> FutureResult<TransactionInfo> {
+        let timeout = query
+            .timeout
+            .unwrap_or(DEFAULT_COMMIT_TIMEOUT)
+            .min(MAX_COMMIT_TIMEOUT);
+        let deadline = Instant::now() + Duration::from_millis(timeout);
+        let query = TransactionQuery::new(query.hash);
+
+        let info = Self::transaction_info(blockchain.snapshot().as_ref(), query);
+        if let Ok(TransactionInfo::Committed(_)) = info {
+            return Box::new(future::result(info));
+        }
+
+        let (notify, committed) = oneshot::channel();
+        let task = shared_node_state
+            .broadcast_server(&blockchain)
+            .send(WaitForCommit {
+                tx_hash: query.hash,
+                notify,
+            })
+            .map_err(|e| ApiError::InternalError(format_err!("{}", e)))
+            .and_then(|is_accepted| {
+                if is_accepted {
+                    Ok(())
+                } else {
+                    Err(ApiError::BadRequest(format!(
+                        "Max number of requests waiting for commitment exceeded ({})",
+                        MAX_COMMIT_WAITERS
+                    )))
+                }
+            })
+            .and_then(move |()| {
+                // The notification is dropped if the websocket server is stopped,
+                // so the transaction status is checked in any case.
+                let committed = committed.then(|_| Ok::<_, ()>(()));
+                let timeout = Delay::new(deadline).then(|_| Ok::<_, ()>(()));
+                committed.select(timeout).then(|_| Ok(()))
+            })
+            .and_then(move |()| Self::transaction_info(blockchain.snapshot().as_ref(), query));
+        Box::new(task)
+    }
+
+    /// Return committed transactions matching the query, starting from the latest ones.
+    /// Transactions are looked up using the transaction indexes enabled in the node
+    /// configuration where possible; otherwise, blocks are scanned one by one, at most
+    /// `MAX_SCANNED_BLOCKS_PER_REQUEST` blocks per request.
+    pub fn transactions(
+        snapshot: &dyn Snapshot,
+        query: TransactionsQuery,
+    ) -> Result<TransactionsList, ApiError> {
+        if query.count > MAX_TRANSACTIONS_PER_REQUEST {
+            return Err(ApiError::BadRequest(format!(
+                "Max transaction count per request exceeded ({})",
+                MAX_TRANSACTIONS_PER_REQUEST
+            )));
+        }
+
+        let explorer = BlockchainExplorer::new(snapshot);
+        let mut iter = explorer
+            .committed_transactions(query.filter(), query.cursor().as_ref())
+            .scan_limit(MAX_SCANNED_BLOCKS_PER_REQUEST);
+        let transactions: Vec<_> = iter.by_ref().take(query.count).collect();
+        let next = if transactions.len() == query.count {
+            transactions.last().map(|tx| {
+                TxLocation::new(
+                    tx.location().block_height(),
+                    tx.location().position_in_block(),
+                )
+            })
+        } else {
+            // The scan has either reached the genesis block or the scan limit.
+            iter.cursor()
+        };
+        Ok(TransactionsList { transactions, next })
+    }
+
+    /// Return committed transactions signed by the specified key, starting from
+    /// the latest ones. Transactions are looked up using the `by_author` transaction index
+    /// if it is enabled in the node configuration. Otherwise, at most
+    /// `MAX_SCANNED_BLOCKS_PER_REQUEST` blocks are scanned per request, so the response
+    /// may contain a partial page together with the cursor to continue the scan.
+    pub fn transactions_by_author(
+        snapshot: &dyn Snapshot,
+        query: TransactionsByAuthorQuery,
+    ) -> Result<TransactionsList, ApiError> {
+        Self::transactions(snapshot, query.into())
+    }
+
+    /// Return the evidence of validator equivocations observed by the node.
+    pub fn equivocations(
+        snapshot: &dyn Snapshot,
+        query: EquivocationsQuery,
+    ) -> Result<Vec<Equivocation>, ApiError> {
+        Ok(BlockchainExplorer::new(snapshot).equivocations(query.validator))
+    }
+
     /// Add transaction into the pool of unconfirmed transactions, and broadcast transaction to other nodes.
     // TODO move this method to the public system API [ECR-3222]
     pub fn add_transaction(
@@ -307,6 +665,112 @@ impl ExplorerApi {
         )
     }
 
+    /// Verifies transactions from the batch and sends the valid ones to the node in a single
+    /// message. Returns the results of the submission in the order of transactions in the batch.
+    pub fn add_transactions(
+        sender: &ApiSender,
+        messages: Vec<Result<SignedMessage, failure::Error>>,
+    ) -> FutureResult<Vec<BatchTransactionResponse>> {
+        if messages.len() > MAX_TRANSACTIONS_PER_BATCH {
+            return Box::new(future::err(ApiError::BadRequest(format!(
+                "Max transaction count per batch exceeded ({})",
+                MAX_TRANSACTIONS_PER_BATCH
+            ))));
+        }
+
+        let mut verified_txs = Vec::with_capacity(messages.len());
+        let responses: Vec<_> = messages
+            .into_iter()
+            .map(|message| {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => {
+                        return BatchTransactionResponse {
+                            tx_hash: None,
+                            error: Some(e.to_string()),
+                        }
+                    }
+                };
+
+                let tx_hash = message.object_hash();
+                let error = match message.into_verified::<AnyTx>() {
+                    Ok(verified) => {
+                        verified_txs.push(verified);
+                        None
+                    }
+                    Err(e) => Some(e.to_string()),
+                };
+                BatchTransactionResponse {
+                    tx_hash: Some(tx_hash),
+                    error,
+                }
+            })
+            .collect();
+
+        if verified_txs.is_empty() {
+            return Box::new(future::ok(responses));
+        }
+        Box::new(
+            sender
+                .clone()
+                .0
+                .send(ExternalMessage::Transactions(verified_txs))
+                .map(move |_| responses)
+                .map_err(|e| ApiError::InternalError(e.into())),
+        )
+    }
+
+    /// Adds the endpoint for the batch transaction submission. The endpoint accepts either
+    /// a JSON array of `TransactionHex`es or a `TransactionsBatch` in the Protobuf format
+    /// if the request has the `application/x-protobuf` or `application/octet-stream`
+    /// content type.
+    pub fn handle_batch(
+        name: &'static str,
+        backend: &mut actix_backend::ApiBuilder,
+        sender: ApiSender,
+    ) {
+        let index = move |request: HttpRequest| -> FutureResponse {
+            let sender = sender.clone();
+            let is_protobuf = PROTOBUF_CONTENT_TYPES.contains(&request.content_type());
+            let messages: Box<
+                dyn Future<Item = Vec<Result<SignedMessage, failure::Error>>, Error = ActixError>,
+            > = if is_protobuf {
+                Box::new(
+                    request
+                        .body()
+                        .limit(MAX_BATCH_BODY_SIZE)
+                        .from_err()
+                        .and_then(|body| {
+                            TransactionsBatch::from_bytes(body.as_ref().into())
+                                .map(|batch| batch.transactions.into_iter().map(Ok).collect())
+                                .map_err(|e| ApiError::BadRequest(e.to_string()).into())
+                        }),
+                )
+            } else {
+                Box::new(request.json().limit(MAX_BATCH_BODY_SIZE).from_err().map(
+                    |txs: Vec<TransactionHex>| {
+                        txs.into_iter()
+                            .map(|tx| SignedMessage::from_hex(tx.tx_body))
+                            .collect()
+                    },
+                ))
+            };
+
+            messages
+                .and_then(move |messages| {
+                    Self::add_transactions(&sender, messages).map_err(From::from)
+                })
+                .map(|responses| HttpResponse::Ok().json(responses))
+                .responder()
+        };
+
+        backend.raw_handler(RequestHandler {
+            name: name.to_owned(),
+            method: http::Method::POST,
+            inner: Arc::from(index) as Arc<RawHandler>,
+        });
+    }
+
     /// Subscribes to events.
     pub fn handle_ws<Q>(
         name: &'static str,
@@ -317,19 +781,8 @@ impl ExplorerApi {
     ) where
         Q: Fn(&HttpRequest) -> Result<SubscriptionType, ActixError> + Send + Sync + 'static,
     {
-        let server = Arc::new(Mutex::new(None));
-
         let index = move |request: HttpRequest| -> FutureResponse {
-            let server = server.clone();
-            let blockchain = blockchain.clone();
-            let mut address = server.lock().expect("Expected mutex lock");
-            if address.is_none() {
-                *address = Some(Arbiter::start(|_| Server::new(blockchain)));
-
-                shared_node_state.set_broadcast_server_address(address.to_owned().unwrap());
-            }
-            let address = address.to_owned().unwrap();
-
+            let address = shared_node_state.broadcast_server(&blockchain);
             extract_query(&request)
                 .into_future()
                 .from_err()
@@ -352,6 +805,11 @@ impl ExplorerApi {
         api_scope: &mut ApiScope,
         shared_node_state: SharedNodeState,
     ) -> &mut ApiScope {
+        Self::handle_batch(
+            "v1/transactions/batch",
+            api_scope.web_backend(),
+            self.blockchain.sender().clone(),
+        );
         // Default subscription for blocks.
         Self::handle_ws(
             "v1/blocks/subscribe",
@@ -371,13 +829,33 @@ impl ExplorerApi {
                     return Ok(SubscriptionType::Transactions { filter: None });
                 }
 
-                Query::from_request(request, &Default::default())
-                    .map(|query: Query<TransactionFilter>| {
-                        Ok(SubscriptionType::Transactions {
-                            filter: Some(query.into_inner()),
-                        })
-                    })
-                    .unwrap_or(Ok(SubscriptionType::None))
+                // Malformed filters, e.g., containing unknown fields, are rejected
+                // with the `400 Bad Request` response.
+                Query::from_request(request, &Default::default()).map(
+                    |query: Query<TransactionFilter>| SubscriptionType::Transactions {
+                        filter: Some(query.into_inner()),
+                    },
+                )
+            },
+        );
+        // Default subscription for service events.
+        Self::handle_ws(
+            "v1/events/subscribe",
+            api_scope.web_backend(),
+            self.blockchain.clone(),
+            shared_node_state.clone(),
+            |request| {
+                if request.query().is_empty() {
+                    return Ok(SubscriptionType::Events { filter: None });
+                }
+
+                // Malformed filters, e.g., containing unknown fields, are rejected
+                // with the `400 Bad Request` response.
+                Query::from_request(request, &Default::default()).map(
+                    |query: Query<EventFilter>| SubscriptionType::Events {
+                        filter: Some(query.into_inner()),
+                    },
+                )
             },
         );
         // Default websocket connection.
@@ -385,7 +863,7 @@ impl ExplorerApi {
             "v1/ws",
             api_scope.web_backend(),
             self.blockchain.clone(),
-            shared_node_state,
+            shared_node_state.clone(),
             |_| Ok(SubscriptionType::None),
         );
         api_scope
@@ -401,6 +879,22 @@ impl ExplorerApi {
                 let blockchain = self.blockchain.clone();
                 move |query| Self::transaction_info(blockchain.snapshot().as_ref(), query)
             })
+            .endpoint("v1/transactions/wait", {
+                let blockchain = self.blockchain.clone();
+                move |query| Self::wait_for_commit(blockchain.clone(), &shared_node_state, query)
+            })
+            .endpoint("v1/transactions/list", {
+                let blockchain = self.blockchain.clone();
+                move |query| Self::transactions(blockchain.snapshot().as_ref(), query)
+            })
+            .endpoint("v1/transactions/by_author", {
+                let blockchain = self.blockchain.clone();
+                move |query| Self::transactions_by_author(blockchain.snapshot().as_ref(), query)
+            })
+            .endpoint("v1/equivocations", {
+                let blockchain = self.blockchain.clone();
+                move |query| Self::equivocations(blockchain.snapshot().as_ref(), query)
+            })
             .endpoint_mut("v1/transactions", {
                 let blockchain = self.blockchain.clone();
                 move |query| Self::add_transaction(blockchain.sender(), query)
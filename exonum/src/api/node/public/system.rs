@@ -17,7 +17,7 @@
 use exonum_merkledb::access::Access;
 
 use crate::{
-    api::{node::SharedNodeState, ApiScope},
+    api::{description, node::SharedNodeState, ApiSchema, ApiScope},
     blockchain::{Blockchain, Schema},
     helpers::user_agent,
     runtime::{ArtifactId, DispatcherSchema, InstanceSpec, SnapshotExt},
@@ -87,6 +87,28 @@ pub struct ProtoSourcesQuery {
     pub artifact: Option<String>,
 }
 
+crate::impl_api_schema!(StatsInfo {
+    tx_pool_size: u64,
+    tx_count: u64,
+    tx_cache_size: usize,
+});
+
+impl ApiSchema for ConsensusStatus {
+    fn api_schema() -> description::Schema {
+        description::enum_schema(&["Disabled", "Enabled", "Active"])
+    }
+}
+
+crate::impl_api_schema!(HealthCheckInfo {
+    consensus_status: ConsensusStatus,
+    connected_peers: usize,
+});
+
+crate::impl_api_schema!(DispatcherInfo {
+    artifacts: Vec<ArtifactId>,
+    services: Vec<InstanceSpec>,
+});
+
 /// Public system API.
 #[derive(Clone, Debug)]
 pub struct SystemApi {
@@ -0,0 +1,204 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioning and deprecation of the API endpoints.
+//!
+//! An endpoint declared with a `VersionedEndpoint` is mounted at the `v{version}/{name}`
+//! path, so that several versions of the same endpoint can be served side by side.
+//! Deprecated endpoints add the following headers to their responses:
+//!
+//! - `Deprecation: true`
+//! - `Sunset: {removal time}` ([RFC 8594]), if the removal time is set
+//! - `Link: <{link}>; rel="deprecation"`, if the link is set
+//!
+//! After the removal time, the endpoint responds with `410 Gone` to all requests.
+//!
+//! [RFC 8594]: https://tools.ietf.org/html/rfc8594
+
+use actix_web::{
+    http::header::{self, HeaderName, HeaderValue},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use futures::{future, Future};
+
+use std::sync::Arc;
+
+use super::backends::actix::{FutureResponse, HttpRequest, RawHandler, RequestHandler};
+
+/// Deprecation of the API endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deprecation {
+    /// Time after which the endpoint is removed.
+    pub removal_time: Option<DateTime<Utc>>,
+    /// Link to the documentation describing the replacement of the endpoint.
+    pub link: Option<String>,
+}
+
+impl Deprecation {
+    /// Creates a deprecation without the removal time and the link.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time after which the endpoint is removed.
+    pub fn with_removal_time(mut self, removal_time: DateTime<Utc>) -> Self {
+        self.removal_time = Some(removal_time);
+        self
+    }
+
+    /// Sets the link to the documentation describing the replacement of the endpoint.
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    fn is_removed_at(&self, time: DateTime<Utc>) -> bool {
+        self.removal_time
+            .map_or(false, |removal_time| time >= removal_time)
+    }
+
+    fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let mut headers = vec![(
+            HeaderName::from_static("deprecation"),
+            HeaderValue::from_static("true"),
+        )];
+        if let Some(removal_time) = self.removal_time {
+            let sunset = removal_time.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+            headers.push((
+                HeaderName::from_static("sunset"),
+                HeaderValue::from_str(&sunset).expect("HTTP date is a valid header value"),
+            ));
+        }
+        if let Some(ref link) = self.link {
+            match HeaderValue::from_str(&format!("<{}>; rel=\"deprecation\"", link)) {
+                Ok(value) => headers.push((header::LINK, value)),
+                Err(e) => warn!("Invalid deprecation link {}: {}", link, e),
+            }
+        }
+        headers
+    }
+
+    /// Wraps the handler so that it reports the deprecation in the response headers.
+    pub(crate) fn wrap_handler(&self, handler: RequestHandler) -> RequestHandler {
+        let deprecation = self.clone();
+        let headers = self.headers();
+        let inner = handler.inner;
+        let index = move |request: HttpRequest| -> FutureResponse {
+            if deprecation.is_removed_at(Utc::now()) {
+                let response = HttpResponse::Gone().body(format!(
+                    "The endpoint has been removed at {}",
+                    deprecation.removal_time.unwrap()
+                ));
+                return Box::new(future::ok(response));
+            }
+
+            let headers = headers.clone();
+            Box::new(inner(request).map(move |mut response| {
+                for (name, value) in headers {
+                    response.headers_mut().insert(name, value);
+                }
+                response
+            }))
+        };
+
+        RequestHandler {
+            name: handler.name,
+            method: handler.method,
+            inner: Arc::from(index) as Arc<RawHandler>,
+        }
+    }
+}
+
+/// Declaration of the API endpoint with the explicit version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionedEndpoint {
+    /// Version of the endpoint.
+    pub version: u16,
+    /// Name of the endpoint without the version prefix, e.g., `blocks`.
+    pub name: &'static str,
+    /// Deprecation of the endpoint, if any.
+    pub deprecation: Option<Deprecation>,
+}
+
+impl VersionedEndpoint {
+    /// Creates a declaration of the endpoint with the given version and name.
+    pub fn new(version: u16, name: &'static str) -> Self {
+        Self {
+            version,
+            name,
+            deprecation: None,
+        }
+    }
+
+    /// Marks the endpoint as deprecated.
+    pub fn deprecated(mut self, deprecation: Deprecation) -> Self {
+        self.deprecation = Some(deprecation);
+        self
+    }
+
+    /// Returns the path of the endpoint relative to the mount point, e.g., `v1/blocks`.
+    pub fn path(&self) -> String {
+        format!("v{}/{}", self.version, self.name)
+    }
+
+    pub(crate) fn wrap_handler(&self, handler: RequestHandler) -> RequestHandler {
+        match self.deprecation {
+            Some(ref deprecation) => deprecation.wrap_handler(handler),
+            None => handler,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn deprecation_headers() {
+        let deprecation = Deprecation::new()
+            .with_removal_time(Utc.ymd(2020, 3, 1).and_hms(12, 0, 0))
+            .with_link("https://example.com/migration");
+        let headers = deprecation
+            .headers()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            vec![
+                ("deprecation".to_owned(), "true".to_owned()),
+                (
+                    "sunset".to_owned(),
+                    "Sun, 01 Mar 2020 12:00:00 GMT".to_owned()
+                ),
+                (
+                    "link".to_owned(),
+                    "<https://example.com/migration>; rel=\"deprecation\"".to_owned()
+                ),
+            ]
+        );
+
+        assert!(!deprecation.is_removed_at(Utc.ymd(2020, 3, 1).and_hms(11, 59, 59)));
+        assert!(deprecation.is_removed_at(Utc.ymd(2020, 3, 1).and_hms(12, 0, 0)));
+        assert!(!Deprecation::new().is_removed_at(Utc::now()));
+    }
+
+    #[test]
+    fn versioned_endpoint_path() {
+        assert_eq!(VersionedEndpoint::new(2, "blocks").path(), "v2/blocks");
+    }
+}
@@ -20,22 +20,28 @@ use actix::*;
 use actix_web::ws;
 use chrono::{DateTime, Utc};
 use exonum_merkledb::{access::Access, ListProof, ObjectHash};
-use futures::Future;
+use futures::{
+    stream,
+    sync::{mpsc::Receiver, oneshot},
+    Future, Stream,
+};
 use hex::FromHex;
 use log::error;
 use rand::{rngs::ThreadRng, Rng};
 
-use std::{
-    cell::RefCell,
-    collections::{BTreeMap, HashMap},
-};
+use std::{cell::RefCell, collections::HashMap};
+
+pub use crate::explorer::StatusFilter;
 
 use crate::{
-    api::node::public::explorer::{TransactionHex, TransactionResponse},
-    blockchain::{Block, Blockchain, ExecutionStatus, Schema, TxLocation},
-    crypto::Hash,
+    api::node::public::explorer::{TransactionHex, TransactionResponse, MAX_COMMIT_WAITERS},
+    blockchain::{Block, Blockchain, ExecutionStatus, Schema, ServiceEvent, TxLocation},
+    crypto::{Hash, PublicKey},
     explorer::median_precommits_time,
+    helpers::Height,
     messages::SignedMessage,
+    node::RecordedEvent,
+    runtime::{InstanceId, MethodId},
 };
 
 /// Message, coming from websocket connection.
@@ -44,6 +50,10 @@ use crate::{
 enum IncomingMessage {
     /// Set subscription for websocket connection.
     SetSubscriptions(Vec<SubscriptionType>),
+    /// Add subscriptions to the current ones.
+    Subscribe(Vec<SubscriptionType>),
+    /// Remove subscriptions from the current ones.
+    Unsubscribe(Vec<SubscriptionType>),
     /// Send transaction to blockchain.
     Transaction(TransactionHex),
 }
@@ -61,24 +71,169 @@ pub enum SubscriptionType {
         /// Optional filter for subscription.
         filter: Option<TransactionFilter>,
     },
+    /// Subscription on events emitted by services.
+    Events {
+        /// Optional filter for subscription.
+        filter: Option<EventFilter>,
+    },
 }
 
-/// Describe filter for transactions by ID of service and (optionally)
-/// transaction type in service.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+impl SubscriptionType {
+    /// Checks if the notification should be sent to the subscriber.
+    fn matches(&self, notification: &Notification) -> bool {
+        match (self, notification) {
+            (SubscriptionType::Blocks, Notification::Block(_)) => true,
+            (SubscriptionType::Transactions { filter }, Notification::Transaction(summary)) => {
+                filter
+                    .as_ref()
+                    .map_or(true, |filter| filter.matches(summary))
+            }
+            (SubscriptionType::Events { filter }, Notification::Event(summary)) => filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(summary)),
+            _ => false,
+        }
+    }
+}
+
+/// Describes filter for committed transactions. Transaction matches the filter
+/// if it matches all specified conditions; unset conditions match any transaction.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+#[serde(deny_unknown_fields)]
 pub struct TransactionFilter {
-    /// ID of service.
-    pub service_id: u16,
-    /// Optional ID of transaction in service (if not set, all transaction of service will be sent).
-    pub message_id: Option<u16>,
+    /// ID of the service instance.
+    pub instance_id: Option<InstanceId>,
+    /// ID of the method in the service interface.
+    pub method_id: Option<MethodId>,
+    /// Author of the transaction.
+    pub author: Option<PublicKey>,
+    /// Result of the transaction execution.
+    pub status: Option<StatusFilter>,
+    /// Minimal height of the block containing the transaction (inclusive).
+    pub from_height: Option<Height>,
+    /// Maximal height of the block containing the transaction (inclusive).
+    pub to_height: Option<Height>,
 }
 
 impl TransactionFilter {
-    /// Create new transaction filter.
-    pub fn new(service_id: u16, message_id: Option<u16>) -> Self {
+    /// Create new transaction filter for the specified service instance and (optionally)
+    /// method.
+    pub fn new(instance_id: InstanceId, method_id: Option<MethodId>) -> Self {
+        Self {
+            instance_id: Some(instance_id),
+            method_id,
+            ..Self::default()
+        }
+    }
+
+    /// Restricts the filter to transactions signed by the specified key.
+    pub fn with_author(mut self, author: PublicKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Restricts the filter to transactions with the specified execution result.
+    pub fn with_status(mut self, status: StatusFilter) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Restricts the filter to transactions committed within the specified range of heights
+    /// (both bounds are inclusive).
+    pub fn with_heights(mut self, from_height: Option<Height>, to_height: Option<Height>) -> Self {
+        self.from_height = from_height;
+        self.to_height = to_height;
+        self
+    }
+
+    /// Checks if the transaction matches the filter.
+    pub fn matches(&self, summary: &CommittedTransactionSummary) -> bool {
+        fn check<T: PartialEq>(expected: &Option<T>, actual: &T) -> bool {
+            expected
+                .as_ref()
+                .map_or(true, |expected| expected == actual)
+        }
+
+        let status = if summary.status.0.is_ok() {
+            StatusFilter::Success
+        } else {
+            StatusFilter::Failure
+        };
+        let height = summary.location.block_height();
+
+        check(&self.instance_id, &summary.instance_id)
+            && check(&self.method_id, &summary.method_id)
+            && check(&self.author, &summary.author)
+            && check(&self.status, &status)
+            && self.from_height.map_or(true, |from| from <= height)
+            && self.to_height.map_or(true, |to| height <= to)
+    }
+}
+
+/// Describes filter for service events by the service instance and (optionally)
+/// the event name.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+#[serde(deny_unknown_fields)]
+pub struct EventFilter {
+    /// ID of the service instance.
+    pub instance_id: Option<InstanceId>,
+    /// Name of the event.
+    pub name: Option<String>,
+}
+
+impl EventFilter {
+    /// Create new event filter for the specified service instance and (optionally) event name.
+    pub fn new(instance_id: InstanceId, name: Option<String>) -> Self {
         Self {
-            service_id,
-            message_id,
+            instance_id: Some(instance_id),
+            name,
+        }
+    }
+
+    /// Checks if the event matches the filter.
+    pub fn matches(&self, summary: &ServiceEventSummary) -> bool {
+        self.instance_id
+            .map_or(true, |instance_id| instance_id == summary.instance_id)
+            && self
+                .name
+                .as_ref()
+                .map_or(true, |name| *name == summary.name)
+    }
+}
+
+/// Event emitted by a service in the committed block.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServiceEventSummary {
+    /// Height of the block.
+    pub height: Height,
+    /// Zero-based position of the event in the block.
+    pub position: u64,
+    /// ID of the service instance which has emitted the event.
+    pub instance_id: InstanceId,
+    /// Name of the event.
+    pub name: String,
+    /// Hex-encoded binary payload of the event. The payload should be decoded according
+    /// to the specification of the service which has emitted it.
+    pub payload: String,
+    /// Proof of existence of the event in the list of the block events. The root hash of the list
+    /// is stored in the `core.service_events` header of the block.
+    pub proof: ListProof<ServiceEvent>,
+}
+
+impl ServiceEventSummary {
+    fn new(
+        schema: &Schema<impl Access>,
+        height: Height,
+        position: u64,
+        event: ServiceEvent,
+    ) -> Self {
+        Self {
+            height,
+            position,
+            instance_id: event.instance_id,
+            name: event.name,
+            payload: hex::encode(&event.payload),
+            proof: schema.service_events(height).get_proof(position),
         }
     }
 }
@@ -88,10 +243,12 @@ impl TransactionFilter {
 pub struct CommittedTransactionSummary {
     /// Transaction identifier.
     pub tx_hash: Hash,
-    /// ID of service.
-    pub service_id: u16,
-    /// ID of transaction in service.
-    pub message_id: u16,
+    /// ID of the service instance.
+    pub instance_id: InstanceId,
+    /// ID of the method in the service interface.
+    pub method_id: MethodId,
+    /// Author of the transaction.
+    pub author: PublicKey,
     /// Result of transaction execution.
     pub status: ExecutionStatus,
     /// Transaction location in the blockchain.
@@ -105,9 +262,8 @@ pub struct CommittedTransactionSummary {
 impl CommittedTransactionSummary {
     fn new(schema: &Schema<impl Access>, tx_hash: &Hash) -> Option<Self> {
         let tx = schema.transactions().get(tx_hash)?;
-        let tx = tx.as_ref();
-        let service_id = tx.call_info.instance_id as u16;
-        let tx_id = tx.call_info.method_id as u16;
+        let author = tx.author();
+        let call_info = &tx.as_ref().call_info;
         let tx_result = schema.transaction_results().get(tx_hash)?;
         let location = schema.transactions_locations().get(tx_hash)?;
         let location_proof = schema
@@ -121,8 +277,9 @@ impl CommittedTransactionSummary {
         );
         Some(Self {
             tx_hash: *tx_hash,
-            service_id,
-            message_id: tx_id,
+            instance_id: call_info.instance_id,
+            method_id: call_info.method_id,
+            author,
             status: tx_result,
             location,
             location_proof,
@@ -140,6 +297,8 @@ pub enum Notification {
     Block(Block),
     /// Notification about new transaction.
     Transaction(CommittedTransactionSummary),
+    /// Notification about new service event.
+    Event(ServiceEventSummary),
 }
 
 /// WebSocket message for communication between clients(`Session`) and server(`Server`).
@@ -178,14 +337,30 @@ pub(crate) struct Broadcast {
     pub block_hash: Hash,
 }
 
+/// Registers a request waiting for the transaction commitment. The request is notified
+/// via `notify` once the transaction is committed. Returns `false` if there are too many
+/// waiting requests.
+#[derive(Message)]
+#[rtype(bool)]
+pub(crate) struct WaitForCommit {
+    pub tx_hash: Hash,
+    pub notify: oneshot::Sender<()>,
+}
+
 #[derive(Message)]
 #[rtype("Result<TransactionResponse, failure::Error>")]
 pub(crate) struct Transaction {
     tx: TransactionHex,
 }
 
+struct Subscriber {
+    address: Recipient<Message>,
+    subscriptions: Vec<SubscriptionType>,
+}
+
 pub(crate) struct Server {
-    pub subscribers: BTreeMap<SubscriptionType, HashMap<u64, Recipient<Message>>>,
+    subscribers: HashMap<u64, Subscriber>,
+    commit_waiters: HashMap<Hash, Vec<oneshot::Sender<()>>>,
     blockchain: Blockchain,
     rng: RefCell<ThreadRng>,
 }
@@ -193,40 +368,36 @@ pub(crate) struct Server {
 impl Server {
     pub fn new(blockchain: Blockchain) -> Self {
         Self {
-            subscribers: BTreeMap::new(),
+            subscribers: HashMap::new(),
+            commit_waiters: HashMap::new(),
             blockchain,
             rng: RefCell::new(rand::thread_rng()),
         }
     }
 
-    fn remove_subscriber(&mut self, id: u64) {
-        self.subscribers.iter_mut().for_each(|(_, v)| {
-            v.remove(&id);
-        });
+    fn commit_waiters_count(&self) -> usize {
+        self.commit_waiters.values().map(Vec::len).sum()
     }
 
-    fn set_subscriptions(
-        &mut self,
-        id: u64,
-        addr: Recipient<Message>,
-        subscriptions: Vec<SubscriptionType>,
-    ) {
-        subscriptions.into_iter().for_each(|sub_type| {
-            self.subscribers
-                .entry(sub_type)
-                .or_insert_with(HashMap::new)
-                .insert(id, addr.clone());
-        });
+    /// Removes the requests which have stopped waiting, e.g., because of the timeout.
+    fn remove_cancelled_waiters(&mut self) {
+        for waiters in self.commit_waiters.values_mut() {
+            waiters.retain(|notify| !notify.is_canceled());
+        }
+        self.commit_waiters.retain(|_, waiters| !waiters.is_empty());
+    }
+
+    fn notify_commit_waiters(&mut self, tx_hash: &Hash) {
+        for notify in self.commit_waiters.remove(tx_hash).unwrap_or_default() {
+            let _ = notify.send(());
+        }
     }
 
     fn disconnect_all(&mut self) {
-        for subscriber in self.subscribers.values_mut() {
-            for recipient in subscriber.values_mut() {
-                if let Err(err) = recipient.do_send(Message::Close) {
-                    warn!("Can't send Close message to a websocket client: {:?}", err);
-                }
+        for subscriber in self.subscribers.values() {
+            if let Err(err) = subscriber.address.do_send(Message::Close) {
+                warn!("Can't send Close message to a websocket client: {:?}", err);
             }
-            subscriber.clear();
         }
         self.subscribers.clear();
     }
@@ -253,7 +424,13 @@ impl Handler<Subscribe> for Server {
         _ctx: &mut Self::Context,
     ) -> u64 {
         let id = self.rng.borrow_mut().gen::<u64>();
-        self.set_subscriptions(id, address, subscriptions);
+        self.subscribers.insert(
+            id,
+            Subscriber {
+                address,
+                subscriptions,
+            },
+        );
 
         id
     }
@@ -263,7 +440,7 @@ impl Handler<Unsubscribe> for Server {
     type Result = ();
 
     fn handle(&mut self, Unsubscribe { id }: Unsubscribe, _ctx: &mut Self::Context) {
-        self.remove_subscriber(id);
+        self.subscribers.remove(&id);
     }
 }
 
@@ -275,19 +452,10 @@ impl Handler<UpdateSubscriptions> for Server {
         UpdateSubscriptions { id, subscriptions }: UpdateSubscriptions,
         _ctx: &mut Self::Context,
     ) {
-        // Find address of subscriber. If id not found, assume that subscriber doesn't exist and return.
-        let addr = if let Some(addr) = self
-            .subscribers
-            .values()
-            .flat_map(HashMap::iter)
-            .find_map(|(k, v)| if k == &id { Some(v.clone()) } else { None })
-        {
-            addr
-        } else {
-            return;
-        };
-        self.remove_subscriber(id);
-        self.set_subscriptions(id, addr, subscriptions);
+        // If id not found, assume that subscriber doesn't exist.
+        if let Some(subscriber) = self.subscribers.get_mut(&id) {
+            subscriber.subscriptions = subscriptions;
+        }
     }
 }
 
@@ -299,13 +467,15 @@ impl Handler<Broadcast> for Server {
         let schema = Schema::new(&snapshot);
         let block = schema.blocks().get(&block_hash).unwrap();
         let height = block.height();
-        let block_header = Notification::Block(block);
 
         // Notify about block
-        self.broadcast_message(SubscriptionType::Blocks, &block_header);
+        self.broadcast_message(&Notification::Block(block));
 
         // Get list of transactions in block and notify about each of them.
         let tx_hashes_table = schema.block_transactions(height);
+        for tx_hash in tx_hashes_table.iter() {
+            self.notify_commit_waiters(&tx_hash);
+        }
         tx_hashes_table
             .iter()
             .filter_map(|hash| {
@@ -320,24 +490,43 @@ impl Handler<Broadcast> for Server {
                 }
                 res
             })
-            .for_each(|tx_info| {
-                let service_id = tx_info.service_id;
-                let tx_id = tx_info.message_id;
-                let data = Notification::Transaction(tx_info);
-                self.broadcast_message(SubscriptionType::Transactions { filter: None }, &data);
-                self.broadcast_message(
-                    SubscriptionType::Transactions {
-                        filter: Some(TransactionFilter::new(service_id, None)),
-                    },
-                    &data,
-                );
-                self.broadcast_message(
-                    SubscriptionType::Transactions {
-                        filter: Some(TransactionFilter::new(service_id, Some(tx_id))),
-                    },
-                    &data,
-                );
-            });
+            .for_each(|tx_info| self.broadcast_message(&Notification::Transaction(tx_info)));
+
+        // Notify about events emitted by services in the block.
+        let events = schema.service_events(height);
+        for (position, event) in events.iter().enumerate() {
+            let summary = ServiceEventSummary::new(&schema, height, position as u64, event);
+            self.broadcast_message(&Notification::Event(summary));
+        }
+    }
+}
+
+impl Handler<WaitForCommit> for Server {
+    type Result = bool;
+
+    fn handle(
+        &mut self,
+        WaitForCommit { tx_hash, notify }: WaitForCommit,
+        _ctx: &mut Self::Context,
+    ) -> bool {
+        // The transaction may have been committed before the request was registered.
+        let snapshot = self.blockchain.snapshot();
+        if Schema::new(&snapshot)
+            .transactions_locations()
+            .contains(&tx_hash)
+        {
+            let _ = notify.send(());
+            return true;
+        }
+
+        if self.commit_waiters_count() >= MAX_COMMIT_WAITERS {
+            self.remove_cancelled_waiters();
+            if self.commit_waiters_count() >= MAX_COMMIT_WAITERS {
+                return false;
+            }
+        }
+        self.commit_waiters.entry(tx_hash).or_default().push(notify);
+        true
     }
 }
 
@@ -369,17 +558,16 @@ impl Handler<Terminate> for Server {
 }
 
 impl Server {
-    fn broadcast_message<T>(&mut self, sub_type: SubscriptionType, data: &T)
-    where
-        T: serde::Serialize,
-    {
+    /// Sends the notification to subscribers having at least one matching subscription.
+    fn broadcast_message(&self, data: &Notification) {
         let serialized = serde_json::to_string(data).unwrap();
         self.subscribers
-            .entry(sub_type)
-            .or_insert_with(HashMap::new)
-            .iter()
-            .for_each(|(_, addr)| {
-                let _ = addr.do_send(Message::Data(serialized.clone()));
+            .values()
+            .filter(|subscriber| subscriber.subscriptions.iter().any(|sub| sub.matches(data)))
+            .for_each(|subscriber| {
+                let _ = subscriber
+                    .address
+                    .do_send(Message::Data(serialized.clone()));
             });
     }
 }
@@ -402,6 +590,24 @@ impl Session {
     fn process_incoming_message(&mut self, msg: IncomingMessage) -> WsStatus {
         match msg {
             IncomingMessage::SetSubscriptions(subs) => self.set_subscriptions(subs),
+            IncomingMessage::Subscribe(subs) => {
+                let mut subscriptions = self.subscriptions.clone();
+                for sub in subs {
+                    if !subscriptions.contains(&sub) {
+                        subscriptions.push(sub);
+                    }
+                }
+                self.set_subscriptions(subscriptions)
+            }
+            IncomingMessage::Unsubscribe(subs) => {
+                let subscriptions = self
+                    .subscriptions
+                    .iter()
+                    .filter(|sub| !subs.contains(sub))
+                    .cloned()
+                    .collect();
+                self.set_subscriptions(subscriptions)
+            }
             IncomingMessage::Transaction(tx) => self.send_transaction(tx),
         }
     }
@@ -484,6 +690,63 @@ impl Handler<Message> for Session {
     }
 }
 
+/// Websocket session streaming consensus events of the node.
+pub(crate) struct ConsensusEventsSession {
+    events: Option<Receiver<RecordedEvent>>,
+}
+
+impl ConsensusEventsSession {
+    pub fn new(events: Receiver<RecordedEvent>) -> Self {
+        Self {
+            events: Some(events),
+        }
+    }
+}
+
+impl Actor for ConsensusEventsSession {
+    type Context = ws::WebsocketContext<Self, ()>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let events = self
+            .events
+            .take()
+            .expect("Consensus events session is started twice");
+        // The stream of events ends if the session lags behind, in which case
+        // the session is closed.
+        let messages = events
+            .map(|event| Message::Data(serde_json::to_string(&event).unwrap()))
+            .chain(stream::once(Ok(Message::Close)));
+        ctx.add_message_stream(messages);
+    }
+}
+
+impl Handler<Message> for ConsensusEventsSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: Message, ctx: &mut Self::Context) {
+        match msg {
+            Message::Data(x) => ctx.text(x),
+            Message::Close => {
+                ctx.close(Some(ws::CloseReason {
+                    code: ws::CloseCode::Again,
+                    description: Some("consensus events stream is closed".into()),
+                }));
+                ctx.stop();
+            }
+        }
+    }
+}
+
+impl StreamHandler<ws::Message, ws::ProtocolError> for ConsensusEventsSession {
+    fn handle(&mut self, msg: ws::Message, ctx: &mut Self::Context) {
+        match msg {
+            ws::Message::Ping(msg) => ctx.pong(&msg),
+            ws::Message::Close(_) => ctx.stop(),
+            _ => {}
+        }
+    }
+}
+
 #[serde(tag = "result", rename_all = "kebab-case")]
 #[derive(Debug, Serialize, Deserialize)]
 enum WsStatus {
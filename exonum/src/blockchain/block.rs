@@ -11,15 +11,137 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use chrono::{DateTime, Utc};
+use exonum_merkledb::BinaryValue;
 use exonum_proto::ProtobufConvert;
+use failure::Error;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use std::collections::BTreeMap;
 
 use crate::{
     crypto::Hash,
     helpers::{Height, ValidatorId},
     messages::{Precommit, Verified},
     proto,
+    runtime::InstanceId,
 };
 
+/// Additional headers of a block.
+///
+/// Headers allow to commit extra information into the block, such as markers of
+/// configuration changes, without changing the block structure. Headers are represented
+/// as a map from string keys to binary values, serialized in the order of keys.
+///
+/// Keys of the headers set by the core start with the `core.` prefix. Keys of the headers
+/// set by runtimes must not use this prefix; Rust services get their keys prefixed with
+/// the name of the service instance.
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Default)]
+pub struct AdditionalHeaders {
+    headers: BTreeMap<String, Vec<u8>>,
+}
+
+impl AdditionalHeaders {
+    /// Creates an empty set of headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a header with the given key. If the header with the same key is already
+    /// present, its value is replaced.
+    pub fn insert<V: BinaryValue>(&mut self, key: impl Into<String>, value: V) {
+        self.headers.insert(key.into(), value.into_bytes());
+    }
+
+    /// Returns the raw value of the header with the given key.
+    pub fn get_raw(&self, key: &str) -> Option<&[u8]> {
+        self.headers.get(key).map(Vec::as_slice)
+    }
+
+    /// Returns the deserialized value of the header with the given key.
+    pub fn get<V: BinaryValue>(&self, key: &str) -> Result<Option<V>, Error> {
+        self.get_raw(key)
+            .map(|bytes| V::from_bytes(bytes.into()))
+            .transpose()
+    }
+
+    /// Returns the number of headers.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Returns `true` if there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Iterates over headers in the order of their keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.headers
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_slice()))
+    }
+}
+
+impl ProtobufConvert for AdditionalHeaders {
+    type ProtoStruct = proto::AdditionalHeaders;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let entries: Vec<_> = self
+            .headers
+            .iter()
+            .map(|(key, value)| {
+                let mut entry = proto::AdditionalHeader::new();
+                entry.set_key(key.clone());
+                entry.set_value(value.clone());
+                entry
+            })
+            .collect();
+
+        let mut inner = Self::ProtoStruct::new();
+        inner.set_entries(entries.into());
+        inner
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, Error> {
+        let mut headers = BTreeMap::new();
+        let mut prev_key: Option<String> = None;
+        for mut entry in pb.take_entries().into_iter() {
+            let key = entry.take_key();
+            ensure!(
+                prev_key.as_ref().map_or(true, |prev_key| *prev_key < key),
+                "Additional headers are not sorted by key or contain duplicate keys"
+            );
+            prev_key = Some(key.clone());
+            headers.insert(key, entry.take_value());
+        }
+        Ok(Self { headers })
+    }
+}
+
+// Header values are represented in JSON as hex strings.
+impl Serialize for AdditionalHeaders {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let headers: BTreeMap<_, _> = self
+            .headers
+            .iter()
+            .map(|(key, value)| (key, hex::encode(value)))
+            .collect();
+        headers.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AdditionalHeaders {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let headers = BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| hex::decode(value).map(|value| (key, value)))
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom)?;
+        Ok(Self { headers })
+    }
+}
+
 /// Exonum block header data structure.
 ///
 /// A block is essentially a list of transactions, which is
@@ -56,10 +178,26 @@ pub struct Block {
     pub tx_hash: Hash,
     /// Hash of the blockchain state after applying transactions in the block.
     pub state_hash: Hash,
+    /// Time of the block as proposed by the leader node. Block times are monotonically
+    /// non-decreasing; the genesis block has the Unix epoch as its time.
+    pub timestamp: DateTime<Utc>,
+    /// Additional headers committed by the core and runtimes.
+    #[serde(default, skip_serializing_if = "AdditionalHeaders::is_empty")]
+    pub additional_headers: AdditionalHeaders,
 }
 
 impl Block {
-    /// Create new `Block`.
+    /// Key of the additional header containing the hash of the consensus configuration
+    /// set in this block. The header is present only in blocks which change the consensus
+    /// configuration.
+    pub const CONSENSUS_CONFIG_HEADER: &'static str = "core.consensus_config";
+
+    /// Key of the additional header containing the root hash of the list of events
+    /// emitted by services in this block. The header is present only in blocks
+    /// with at least one event.
+    pub const SERVICE_EVENTS_HEADER: &'static str = "core.service_events";
+
+    /// Create new `Block` without additional headers.
     pub fn new(
         proposer_id: ValidatorId,
         height: Height,
@@ -67,6 +205,7 @@ impl Block {
         prev_hash: Hash,
         tx_hash: Hash,
         state_hash: Hash,
+        timestamp: DateTime<Utc>,
     ) -> Self {
         Self {
             proposer_id,
@@ -75,6 +214,8 @@ impl Block {
             prev_hash,
             tx_hash,
             state_hash,
+            timestamp,
+            additional_headers: AdditionalHeaders::new(),
         }
     }
     /// Identifier of the leader node which has proposed the block.
@@ -102,6 +243,66 @@ impl Block {
     pub fn state_hash(&self) -> &Hash {
         &self.state_hash
     }
+    /// Time of the block as proposed by the leader node.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+    /// Additional headers committed by the core and runtimes.
+    pub fn additional_headers(&self) -> &AdditionalHeaders {
+        &self.additional_headers
+    }
+}
+
+/// Event emitted by a service during the block execution.
+///
+/// Events of each block are stored in a Merkelized list, the root hash of which
+/// is committed into the block as the `core.service_events` additional header,
+/// so that events can be proven to the light clients.
+#[derive(
+    Clone, PartialEq, Eq, Debug, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash,
+)]
+#[protobuf_convert(source = "proto::ServiceEvent")]
+pub struct ServiceEvent {
+    /// Identifier of the service instance which has emitted the event.
+    pub instance_id: InstanceId,
+    /// Name of the event.
+    pub name: String,
+    /// Binary payload of the event. The payload is represented in JSON as a hex string.
+    #[serde(with = "payload_hex")]
+    pub payload: Vec<u8>,
+}
+
+impl ServiceEvent {
+    /// Creates a new event with the binary serialized payload.
+    pub fn new(
+        instance_id: InstanceId,
+        name: impl Into<String>,
+        payload: impl BinaryValue,
+    ) -> Self {
+        Self {
+            instance_id,
+            name: name.into(),
+            payload: payload.into_bytes(),
+        }
+    }
+
+    /// Deserializes the payload of the event.
+    pub fn payload<T: BinaryValue>(&self) -> Result<T, Error> {
+        T::from_bytes(self.payload.as_slice().into())
+    }
+}
+
+mod payload_hex {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(payload: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(payload))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let payload = String::deserialize(deserializer)?;
+        hex::decode(payload).map_err(D::Error::custom)
+    }
 }
 
 /// Block with its `Precommit` messages.
@@ -121,6 +322,9 @@ pub struct BlockProof {
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+    use exonum_merkledb::ObjectHash;
+
     use super::*;
     use crate::crypto::hash;
 
@@ -133,6 +337,7 @@ mod tests {
         let tx_hash = hash(&txs);
         let tx_count = txs.len() as u32;
         let state_hash = hash(&[7, 8, 9]);
+        let time = Utc.timestamp(1_500_000_000, 42);
         let block = Block::new(
             proposer_id,
             height,
@@ -140,6 +345,7 @@ mod tests {
             prev_hash,
             tx_hash,
             state_hash,
+            time,
         );
 
         assert_eq!(block.proposer_id(), proposer_id);
@@ -148,8 +354,61 @@ mod tests {
         assert_eq!(block.prev_hash(), &prev_hash);
         assert_eq!(block.tx_hash(), &tx_hash);
         assert_eq!(block.state_hash(), &state_hash);
+        assert_eq!(block.timestamp(), time);
+        let json_str = ::serde_json::to_string(&block).unwrap();
+        let block1: Block = ::serde_json::from_str(&json_str).unwrap();
+        assert_eq!(block1, block);
+    }
+
+    #[test]
+    fn test_block_additional_headers() {
+        let mut block = Block::new(
+            ValidatorId(0),
+            Height(1),
+            0,
+            hash(&[1]),
+            hash(&[]),
+            hash(&[2]),
+            Utc.timestamp(0, 0),
+        );
+        let hash_without_headers = block.object_hash();
+
+        block.additional_headers.insert("foo.bar", 42_u64);
+        block
+            .additional_headers
+            .insert(Block::CONSENSUS_CONFIG_HEADER, hash(&[3]));
+        assert_ne!(block.object_hash(), hash_without_headers);
+        assert_eq!(block.additional_headers().len(), 2);
+        assert_eq!(
+            block.additional_headers().get::<u64>("foo.bar").unwrap(),
+            Some(42)
+        );
+        assert_eq!(
+            block
+                .additional_headers()
+                .get::<Hash>(Block::CONSENSUS_CONFIG_HEADER)
+                .unwrap(),
+            Some(hash(&[3]))
+        );
+        assert_eq!(block.additional_headers().get_raw("unknown"), None);
+
+        let block1 = Block::from_bytes(block.to_bytes().into()).unwrap();
+        assert_eq!(block1, block);
         let json_str = ::serde_json::to_string(&block).unwrap();
         let block1: Block = ::serde_json::from_str(&json_str).unwrap();
         assert_eq!(block1, block);
     }
+
+    #[test]
+    fn test_service_event() {
+        let event = ServiceEvent::new(5, "value", 42_u64);
+        assert_eq!(event.payload::<u64>().unwrap(), 42);
+
+        let event1 = ServiceEvent::from_bytes(event.to_bytes().into()).unwrap();
+        assert_eq!(event1, event);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["payload"], hex::encode(42_u64.into_bytes()));
+        let event1: ServiceEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(event1, event);
+    }
 }
@@ -18,7 +18,7 @@ use futures::sync::mpsc;
 
 use crate::{
     api::manager::UpdateEndpoints,
-    blockchain::{Blockchain, BlockchainMut, ConsensusConfig, Schema},
+    blockchain::{Blockchain, BlockchainMut, ConsensusConfig, Schema, TransactionIndexes},
     merkledb::BinaryValue,
     runtime::{
         rust::{RustRuntime, ServiceFactory},
@@ -42,6 +42,8 @@ pub struct BlockchainBuilder {
     /// List of the privileged services with the configuration parameters that are created directly
     /// in the genesis block.
     pub builtin_instances: Vec<InstanceConfig>,
+    /// Secondary indexes of committed transactions maintained by the blockchain.
+    pub transaction_indexes: TransactionIndexes,
 }
 
 impl BlockchainBuilder {
@@ -52,6 +54,7 @@ impl BlockchainBuilder {
             genesis_config,
             runtimes: vec![],
             builtin_instances: vec![],
+            transaction_indexes: TransactionIndexes::default(),
         }
     }
 
@@ -97,6 +100,12 @@ impl BlockchainBuilder {
         self
     }
 
+    /// Sets secondary indexes of committed transactions maintained by the blockchain.
+    pub fn with_transaction_indexes(mut self, transaction_indexes: TransactionIndexes) -> Self {
+        self.transaction_indexes = transaction_indexes;
+        self
+    }
+
     /// Returns blockchain instance, creates and commits the genesis block with the specified
     /// genesis configuration if the blockchain has not been initialized.
     /// Otherwise restores dispatcher state from database.
@@ -109,6 +118,7 @@ impl BlockchainBuilder {
         let mut blockchain = BlockchainMut {
             dispatcher: Dispatcher::new(&self.blockchain, self.runtimes),
             inner: self.blockchain,
+            transaction_indexes: self.transaction_indexes,
         };
 
         // If genesis block had been already created just restores dispatcher state from database
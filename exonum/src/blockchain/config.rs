@@ -121,6 +121,14 @@ pub struct ConsensusConfig {
     /// in a block if the transaction pool is almost empty, and create blocks faster when there are
     /// enough transactions in the pool.
     pub propose_timeout_threshold: u32,
+    /// Maximal difference (in milliseconds) by which the time of a proposal may exceed
+    /// the local time of a node. Proposals with the time further in the future are ignored.
+    /// Proposals with the time earlier than the time of the previous block are ignored
+    /// as well, so that block times are monotonically non-decreasing.
+    ///
+    /// If the parameter is not specified, `DEFAULT_MAX_BLOCK_TIME_DRIFT` is used.
+    #[serde(default = "ConsensusConfig::default_max_block_time_drift")]
+    pub max_block_time_drift: Milliseconds,
 }
 
 impl Default for ConsensusConfig {
@@ -135,6 +143,7 @@ impl Default for ConsensusConfig {
             min_propose_timeout: 10,
             max_propose_timeout: 200,
             propose_timeout_threshold: 500,
+            max_block_time_drift: Self::DEFAULT_MAX_BLOCK_TIME_DRIFT,
         }
     }
 }
@@ -144,6 +153,12 @@ impl ConsensusConfig {
     pub const DEFAULT_MAX_MESSAGE_LEN: u32 = 1024 * 1024; // 1 MB
     /// Time that will be added to round timeout for each next round in terms of percent of first_round_timeout.
     pub const TIMEOUT_LINEAR_INCREASE_PERCENT: u64 = 10; // 10%
+    /// Default value for max_block_time_drift.
+    pub const DEFAULT_MAX_BLOCK_TIME_DRIFT: Milliseconds = 30_000; // 30 seconds
+
+    fn default_max_block_time_drift() -> Milliseconds {
+        Self::DEFAULT_MAX_BLOCK_TIME_DRIFT
+    }
 
     /// Check that validator keys is correct. Configuration should have at least
     /// a single validator key. And each key should meet only once.
@@ -273,6 +288,10 @@ impl ValidateInput for ConsensusConfig {
             );
         }
 
+        if self.max_block_time_drift == 0 {
+            bail!("max_block_time_drift should not be equal to zero");
+        }
+
         // Check transactions limit.
         if self.txs_block_limit == 0 {
             bail!("txs_block_limit should not be equal to zero",);
@@ -428,6 +447,13 @@ mod tests {
                 },
                 "txs_block_limit should not be equal to zero",
             ),
+            (
+                ConsensusConfig {
+                    max_block_time_drift: 0,
+                    ..gen_consensus_config()
+                },
+                "max_block_time_drift should not be equal to zero",
+            ),
             (
                 ConsensusConfig {
                     max_message_len: 0,
@@ -441,4 +467,23 @@ mod tests {
             assert_err_contains(cfg.validate().unwrap_err(), expected_msg);
         }
     }
+
+    #[test]
+    fn max_block_time_drift_is_optional() {
+        let config = ConsensusConfig {
+            max_block_time_drift: 1000,
+            ..gen_consensus_config()
+        };
+        let mut value = serde_json::to_value(&config).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("max_block_time_drift");
+
+        let config: ConsensusConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            config.max_block_time_drift,
+            ConsensusConfig::DEFAULT_MAX_BLOCK_TIME_DRIFT
+        );
+    }
 }
@@ -0,0 +1,186 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_merkledb::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+use failure::Error;
+use protobuf::Message as ProtobufMessage;
+
+use std::borrow::Cow;
+
+use crate::{
+    crypto::{self, Hash},
+    helpers::{Height, Round, ValidatorId},
+    messages::{
+        Consensus as ConsensusMessage, Message, Precommit, Prevote, SignedMessage, Verified,
+    },
+    proto::schema::consensus,
+};
+
+/// Evidence of an equivocation, that is, of a validator signing two conflicting
+/// consensus messages for the same height and round.
+///
+/// The evidence is self-certifying: both messages carry the signature of the offending
+/// validator, so it can be checked without trusting the node which has detected it.
+/// Messages in the evidence are ordered by their hashes, so the same pair of messages
+/// always produces the same evidence regardless of the order in which they were received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Equivocation {
+    /// Two prevotes for different proposals.
+    Prevote {
+        /// First conflicting prevote.
+        first: Verified<Prevote>,
+        /// Second conflicting prevote.
+        second: Verified<Prevote>,
+    },
+    /// Two precommits for different proposals or blocks.
+    Precommit {
+        /// First conflicting precommit.
+        first: Verified<Precommit>,
+        /// Second conflicting precommit.
+        second: Verified<Precommit>,
+    },
+}
+
+impl Equivocation {
+    /// Creates an evidence from two prevotes. Returns `None` if the prevotes are not
+    /// conflicting, i.e., they are authored by different validators, relate to different
+    /// heights or rounds, or vote for the same proposal.
+    pub fn from_prevotes(first: Verified<Prevote>, second: Verified<Prevote>) -> Option<Self> {
+        let (a, b) = (first.payload(), second.payload());
+        let is_conflicting = a.validator == b.validator
+            && a.height == b.height
+            && a.round == b.round
+            && a.propose_hash != b.propose_hash;
+
+        if is_conflicting {
+            let (first, second) = ordered(first, second);
+            Some(Equivocation::Prevote { first, second })
+        } else {
+            None
+        }
+    }
+
+    /// Creates an evidence from two precommits. Returns `None` if the precommits are not
+    /// conflicting, i.e., they are authored by different validators, relate to different
+    /// heights or rounds, or vote for the same proposal and block.
+    pub fn from_precommits(
+        first: Verified<Precommit>,
+        second: Verified<Precommit>,
+    ) -> Option<Self> {
+        let (a, b) = (first.payload(), second.payload());
+        let is_conflicting = a.validator == b.validator
+            && a.height == b.height
+            && a.round == b.round
+            && (a.propose_hash != b.propose_hash || a.block_hash != b.block_hash);
+
+        if is_conflicting {
+            let (first, second) = ordered(first, second);
+            Some(Equivocation::Precommit { first, second })
+        } else {
+            None
+        }
+    }
+
+    /// Identifier of the validator which has signed the conflicting messages.
+    pub fn validator(&self) -> ValidatorId {
+        match self {
+            Equivocation::Prevote { first, .. } => first.payload().validator(),
+            Equivocation::Precommit { first, .. } => first.payload().validator(),
+        }
+    }
+
+    /// Height at which the equivocation took place.
+    pub fn height(&self) -> Height {
+        match self {
+            Equivocation::Prevote { first, .. } => first.payload().height(),
+            Equivocation::Precommit { first, .. } => first.payload().height(),
+        }
+    }
+
+    /// Round in which the equivocation took place.
+    pub fn round(&self) -> Round {
+        match self {
+            Equivocation::Prevote { first, .. } => first.payload().round(),
+            Equivocation::Precommit { first, .. } => first.payload().round(),
+        }
+    }
+
+    fn raw_messages(&self) -> (&SignedMessage, &SignedMessage) {
+        match self {
+            Equivocation::Prevote { first, second } => (first.as_raw(), second.as_raw()),
+            Equivocation::Precommit { first, second } => (first.as_raw(), second.as_raw()),
+        }
+    }
+}
+
+// Orders messages by their hashes.
+fn ordered<T>(first: Verified<T>, second: Verified<T>) -> (Verified<T>, Verified<T>) {
+    if first.as_raw().object_hash() <= second.as_raw().object_hash() {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+impl ProtobufConvert for Equivocation {
+    type ProtoStruct = consensus::Equivocation;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let (first, second) = self.raw_messages();
+        let mut inner = Self::ProtoStruct::new();
+        inner.set_first(first.to_pb());
+        inner.set_second(second.to_pb());
+        inner
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, Error> {
+        let first = Message::from_signed(SignedMessage::from_pb(pb.take_first())?)?;
+        let second = Message::from_signed(SignedMessage::from_pb(pb.take_second())?)?;
+
+        let evidence = match (first, second) {
+            (
+                Message::Consensus(ConsensusMessage::Prevote(first)),
+                Message::Consensus(ConsensusMessage::Prevote(second)),
+            ) => Self::from_prevotes(first, second),
+            (
+                Message::Consensus(ConsensusMessage::Precommit(first)),
+                Message::Consensus(ConsensusMessage::Precommit(second)),
+            ) => Self::from_precommits(first, second),
+            _ => None,
+        };
+        evidence.ok_or_else(|| format_err!("Messages do not constitute an equivocation"))
+    }
+}
+
+impl BinaryValue for Equivocation {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_pb()
+            .write_to_bytes()
+            .expect("Failed to serialize in BinaryValue for Equivocation")
+    }
+
+    fn from_bytes(value: Cow<'_, [u8]>) -> Result<Self, Error> {
+        let mut inner = <Self as ProtobufConvert>::ProtoStruct::new();
+        inner.merge_from_bytes(value.as_ref())?;
+        ProtobufConvert::from_pb(inner)
+    }
+}
+
+impl ObjectHash for Equivocation {
+    fn object_hash(&self) -> Hash {
+        crypto::hash(&self.to_bytes())
+    }
+}
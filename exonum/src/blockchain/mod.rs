@@ -22,14 +22,16 @@ pub use crate::runtime::{
 };
 
 pub use self::{
-    block::{Block, BlockProof},
+    block::{AdditionalHeaders, Block, BlockProof, ServiceEvent},
     builder::{BlockchainBuilder, InstanceCollection, InstanceConfig},
     config::{ConsensusConfig, ValidatorKeys},
-    schema::{IndexCoordinates, Schema, SchemaOrigin, TxLocation},
+    equivocation::Equivocation,
+    schema::{IndexCoordinates, Schema, SchemaOrigin, TransactionIndexes, TxLocation},
 };
 
 pub mod config;
 
+use chrono::{DateTime, TimeZone, Utc};
 use exonum_crypto::gen_keypair;
 use exonum_merkledb::{
     access::RawAccess, Database, Fork, MapIndex, ObjectHash, Patch, Result as StorageResult,
@@ -53,6 +55,7 @@ use crate::{
 
 mod block;
 mod builder;
+mod equivocation;
 mod schema;
 #[cfg(test)]
 pub mod tests;
@@ -178,6 +181,7 @@ impl Blockchain {
 pub struct BlockchainMut {
     inner: Blockchain,
     dispatcher: Dispatcher,
+    transaction_indexes: TransactionIndexes,
 }
 
 impl AsRef<Blockchain> for BlockchainMut {
@@ -227,8 +231,12 @@ impl BlockchainMut {
         initial_services: Vec<InstanceConfig>,
     ) -> Result<(), Error> {
         config.validate()?;
+        // The genesis block has a fixed time, so that its hash only depends on the configuration.
+        let genesis_time = Utc.timestamp(0, 0);
         let mut fork = self.fork();
         Schema::new(&fork).consensus_config_entry().set(config);
+        // Services may access the block time in their constructors.
+        Schema::new(&fork).pending_block_time().set(genesis_time);
 
         // Add service instances.
         for instance_config in initial_services {
@@ -248,6 +256,7 @@ impl BlockchainMut {
         let (_, patch) = self.create_patch(
             ValidatorId::zero(),
             Height::zero(),
+            genesis_time,
             &[],
             &mut BTreeMap::new(),
         );
@@ -267,24 +276,37 @@ impl BlockchainMut {
 
     /// Executes the given transactions from the pool.
     /// Then collects the resulting changes from the current storage state and returns them
-    /// with the hash of the resulting block.
+    /// with the hash of the resulting block. The block gets the specified `time`, which
+    /// is available to services during the block execution.
     pub fn create_patch(
         &self,
         proposer_id: ValidatorId,
         height: Height,
+        time: DateTime<Utc>,
         tx_hashes: &[Hash],
         tx_cache: &mut BTreeMap<Hash, Verified<AnyTx>>,
     ) -> (Hash, Patch) {
         // Create fork
         let mut fork = self.fork();
+        // Make the block time available to the executed transactions and hooks.
+        Schema::new(&fork).pending_block_time().set(time);
+        let transaction_indexes =
+            Schema::new(&fork).update_transaction_indexes(height, self.transaction_indexes);
         // Get last hash.
         let last_hash = self.inner.last_hash();
         // Save & execute transactions.
         for (index, hash) in tx_hashes.iter().enumerate() {
-            self.execute_transaction(*hash, height, index, &mut fork, tx_cache)
-                // Execution could fail if the transaction
-                // cannot be deserialized or it isn't in the pool.
-                .expect("Transaction execution error");
+            // Execution could fail if the transaction
+            // cannot be deserialized or it isn't in the pool.
+            self.execute_transaction(
+                *hash,
+                height,
+                index,
+                transaction_indexes,
+                &mut fork,
+                tx_cache,
+            )
+            .expect("Transaction execution error");
         }
 
         // Skip execution for genesis block.
@@ -292,6 +314,9 @@ impl BlockchainMut {
             self.dispatcher.before_commit(&mut fork);
         }
 
+        let additional_headers = self.take_additional_headers(&fork, height);
+        Schema::new(&fork).pending_block_time().remove();
+
         // Get tx & state hash.
         let schema = Schema::new(&fork);
         let state_hash = {
@@ -317,14 +342,16 @@ impl BlockchainMut {
         let tx_hash = schema.block_transactions(height).object_hash();
 
         // Create block.
-        let block = Block::new(
+        let block = Block {
             proposer_id,
             height,
-            tx_hashes.len() as u32,
-            last_hash,
+            tx_count: tx_hashes.len() as u32,
+            prev_hash: last_hash,
             tx_hash,
             state_hash,
-        );
+            timestamp: time,
+            additional_headers,
+        };
         trace!("execute block = {:?}", block);
 
         // Calculate block hash.
@@ -337,11 +364,39 @@ impl BlockchainMut {
         (block_hash, fork.into_patch())
     }
 
+    /// Collects additional headers set by the runtimes during the block execution
+    /// and complements them with the headers set by the core. The genesis block
+    /// always marks the initial consensus configuration.
+    fn take_additional_headers(&self, fork: &Fork, height: Height) -> AdditionalHeaders {
+        let schema = Schema::new(fork);
+        let mut pending_headers = schema.pending_block_headers();
+        let mut headers = AdditionalHeaders::new();
+        for (key, value) in pending_headers.iter() {
+            headers.insert(key, value);
+        }
+        pending_headers.clear();
+
+        let config_hash = schema.consensus_config_entry().object_hash();
+        let prev_config_hash = Schema::new(&self.snapshot())
+            .consensus_config_entry()
+            .object_hash();
+        if height == Height::zero() || config_hash != prev_config_hash {
+            headers.insert(Block::CONSENSUS_CONFIG_HEADER, config_hash);
+        }
+
+        let events = schema.service_events(height);
+        if !events.is_empty() {
+            headers.insert(Block::SERVICE_EVENTS_HEADER, events.object_hash());
+        }
+        headers
+    }
+
     fn execute_transaction(
         &self,
         tx_hash: Hash,
         height: Height,
         index: usize,
+        transaction_indexes: TransactionIndexes,
         fork: &mut Fork,
         tx_cache: &mut BTreeMap<Hash, Verified<AnyTx>>,
     ) -> Result<(), Error> {
@@ -371,9 +426,10 @@ impl BlockchainMut {
         schema
             .transaction_results()
             .put(&tx_hash, ExecutionStatus(tx_result));
+        let location = TxLocation::new(height, index as u64);
+        schema.index_transaction(location, &transaction, transaction_indexes);
         schema.commit_transaction(&tx_hash, height, transaction);
         tx_cache.remove(&tx_hash);
-        let location = TxLocation::new(height, index as u64);
         schema.transactions_locations().put(&tx_hash, location);
         fork.flush();
         Ok(())
@@ -481,6 +537,16 @@ impl BlockchainMut {
             .expect("Unable to save peer to the peers cache");
     }
 
+    /// Saves the evidence of a validator equivocation.
+    pub(crate) fn save_equivocation(&mut self, equivocation: Equivocation) {
+        let fork = self.fork();
+        Schema::new(&fork)
+            .equivocations()
+            .put(&equivocation.object_hash(), equivocation);
+        self.merge(fork.into_patch())
+            .expect("Unable to save equivocation evidence");
+    }
+
     /// Removes from the cache the `Connect` message from a peer.
     pub fn remove_peer_with_pubkey(&mut self, key: &PublicKey) {
         let fork = self.fork();
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
 use exonum_merkledb::{
     access::{Access, AccessExt, RawAccessMut},
     BinaryKey, Entry, KeySetIndex, ListIndex, MapIndex, ObjectHash, ProofListIndex, ProofMapIndex,
@@ -21,7 +22,7 @@ use exonum_proto::ProtobufConvert;
 
 use std::mem;
 
-use super::{Block, BlockProof, ConsensusConfig, ExecutionStatus};
+use super::{Block, BlockProof, ConsensusConfig, Equivocation, ExecutionStatus, ServiceEvent};
 use crate::{
     crypto::{self, Hash, PublicKey},
     helpers::{Height, Round, ValidatorId},
@@ -57,12 +58,23 @@ define_names!(
     CONSENSUS_MESSAGES_CACHE => "consensus_messages_cache";
     CONSENSUS_ROUND => "consensus_round";
     CONSENSUS_CONFIG => "consensus.config";
+    EQUIVOCATIONS => "equivocations";
+    PENDING_BLOCK_HEADERS => "pending_block_headers";
+    PENDING_BLOCK_TIME => "pending_block_time";
+    SERVICE_EVENTS => "service_events";
+    TRANSACTION_INDEXES => "transaction_indexes";
+    TRANSACTIONS_BY_INSTANCE => "transactions_by_instance";
+    TRANSACTIONS_BY_AUTHOR => "transactions_by_author";
+    TRANSACTIONS_BY_INSTANCE_KEYS => "transactions_by_instance_keys";
+    TRANSACTIONS_BY_AUTHOR_KEYS => "transactions_by_author_keys";
 );
 
 /// Transaction location in a block.
 /// The given entity defines the block where the transaction was
 /// included and the position of this transaction in that block.
-#[derive(Debug, Serialize, Deserialize, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ProtobufConvert, BinaryValue, ObjectHash,
+)]
 #[protobuf_convert(source = "proto::TxLocation")]
 pub struct TxLocation {
     /// Height of the block where the transaction was included.
@@ -90,6 +102,53 @@ impl TxLocation {
     }
 }
 
+/// Optional secondary indexes of committed transactions maintained by the node.
+///
+/// The indexes are not a part of the blockchain state, so they can be enabled
+/// or disabled on each node independently. An index covers transactions committed
+/// since the height at which it has been enabled for the last time; see
+/// [`Schema::transaction_index_start`](struct.Schema.html#method.transaction_index_start).
+///
+/// The content of an index is removed once the index is disabled. The removal is
+/// incremental: at most 1,000 lists of the index (e.g., lists of transactions
+/// of different authors) are removed per block. A re-enabled index is maintained again only after its previous
+/// content is removed completely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionIndexes {
+    /// Index of transactions by the service instance they are addressed to.
+    #[serde(default)]
+    pub by_instance: bool,
+    /// Index of transactions by their author.
+    #[serde(default)]
+    pub by_author: bool,
+}
+
+impl TransactionIndexes {
+    /// Name of the index of transactions by the service instance.
+    pub const BY_INSTANCE: &'static str = "by_instance";
+    /// Name of the index of transactions by the author.
+    pub const BY_AUTHOR: &'static str = "by_author";
+
+    fn iter(self) -> impl Iterator<Item = (&'static str, bool)> {
+        vec![
+            (Self::BY_INSTANCE, self.by_instance),
+            (Self::BY_AUTHOR, self.by_author),
+        ]
+        .into_iter()
+    }
+
+    fn enable(&mut self, index_name: &str) {
+        match index_name {
+            Self::BY_INSTANCE => self.by_instance = true,
+            Self::BY_AUTHOR => self.by_author = true,
+            _ => unreachable!("Unknown transaction index {}", index_name),
+        }
+    }
+}
+
+/// Maximum number of the lists of disabled transaction indexes removed per block.
+pub(crate) const MAX_CLEARED_INDEX_LISTS: usize = 1_000;
+
 /// Information schema for indices maintained by the Exonum core logic.
 ///
 /// Indices defined by this schema are present in the blockchain regardless of
@@ -176,11 +235,98 @@ impl<T: Access> Schema<T> {
             .get_proof_list((BLOCK_TRANSACTIONS, &height))
     }
 
+    /// Returns a table that keeps a list of events emitted by services in the block
+    /// with the given height.
+    pub fn service_events(&self, height: Height) -> ProofListIndex<T::Base, ServiceEvent> {
+        let height: u64 = height.into();
+        self.access
+            .clone()
+            .get_proof_list((SERVICE_EVENTS, &height))
+    }
+
+    /// Returns a table that keeps locations of committed transactions addressed to the given
+    /// service instance in the order of their commitment.
+    ///
+    /// The table is maintained only if the `by_instance` transaction index is enabled
+    /// in the node configuration.
+    pub fn transactions_by_instance(
+        &self,
+        instance_id: InstanceId,
+    ) -> ListIndex<T::Base, TxLocation> {
+        self.access
+            .clone()
+            .get_list((TRANSACTIONS_BY_INSTANCE, &instance_id))
+    }
+
+    /// Returns a table that keeps locations of committed transactions signed by the given key
+    /// in the order of their commitment. Transactions can be obtained by their locations
+    /// from the `block_transactions` table.
+    ///
+    /// The table is maintained only if the `by_author` transaction index is enabled
+    /// in the node configuration.
+    pub fn transactions_by_author(&self, author: &PublicKey) -> ListIndex<T::Base, TxLocation> {
+        self.access
+            .clone()
+            .get_list((TRANSACTIONS_BY_AUTHOR, author))
+    }
+
+    /// Returns a set of service instances with non-empty `transactions_by_instance` lists.
+    fn transactions_by_instance_keys(&self) -> KeySetIndex<T::Base, InstanceId> {
+        self.access
+            .clone()
+            .get_key_set(TRANSACTIONS_BY_INSTANCE_KEYS)
+    }
+
+    /// Returns a set of authors with non-empty `transactions_by_author` lists.
+    fn transactions_by_author_keys(&self) -> KeySetIndex<T::Base, PublicKey> {
+        self.access.clone().get_key_set(TRANSACTIONS_BY_AUTHOR_KEYS)
+    }
+
+    /// Returns a table that keeps the heights since which the enabled transaction indexes
+    /// are maintained.
+    pub(crate) fn transaction_indexes(&self) -> MapIndex<T::Base, String, u64> {
+        self.access.clone().get_map(TRANSACTION_INDEXES)
+    }
+
+    /// Returns the height since which the transaction index with the given name is maintained,
+    /// or `None` if the index is disabled. Transactions committed before this height
+    /// are not present in the index.
+    pub fn transaction_index_start(&self, index_name: &str) -> Option<Height> {
+        self.transaction_indexes().get(index_name).map(Height)
+    }
+
     /// Returns a table that keeps a list of precommits for the block with the given hash.
     pub fn precommits(&self, hash: &Hash) -> ListIndex<T::Base, Verified<Precommit>> {
         self.access.clone().get_list((PRECOMMITS, hash))
     }
 
+    /// Returns a table that keeps evidence of equivocations observed by this node,
+    /// indexed by the hash of the evidence.
+    ///
+    /// The evidence is recorded by the node locally as soon as it receives conflicting
+    /// consensus messages and is not a part of the blockchain state, so different nodes
+    /// may have different content of this index. Services can read the index via
+    /// `BlockchainData::for_core` from the snapshot of committed data, e.g., in `after_commit`
+    /// or in the HTTP API handlers, and submit the evidence to the blockchain within
+    /// a transaction, since it is self-certifying. The index must not be read during
+    /// transaction execution or in the `before_commit` hook, as this would make
+    /// the execution results differ among nodes.
+    pub fn equivocations(&self) -> MapIndex<T::Base, Hash, Equivocation> {
+        self.access.clone().get_map(EQUIVOCATIONS)
+    }
+
+    /// Returns a table with additional headers set by runtimes for the block being created.
+    /// The table is cleared after the headers are committed into the block.
+    pub(crate) fn pending_block_headers(&self) -> MapIndex<T::Base, String, Vec<u8>> {
+        self.access.clone().get_map(PENDING_BLOCK_HEADERS)
+    }
+
+    /// Returns an entry with the time of the block being created.
+    /// The entry is cleared after the block is created.
+    pub(crate) fn pending_block_time(&self) -> Entry<T::Base, DateTime<Utc>> {
+        self.access.clone().get_entry(PENDING_BLOCK_TIME)
+    }
+
     /// Returns an actual consensus configuration entry.
     pub fn consensus_config_entry(&self) -> Entry<T::Base, ConsensusConfig> {
         self.access.clone().get_entry(CONSENSUS_CONFIG)
@@ -326,6 +472,83 @@ where
         self.block_transactions(height).push(*hash);
     }
 
+    /// Records the height since which the enabled transaction indexes are maintained
+    /// and forgets disabled indexes. Returns the indexes which should be maintained
+    /// in the block at the given height.
+    ///
+    /// The content of indexes which are not maintained is removed incrementally, so that
+    /// a single block does not remove an unbounded number of entries. An enabled index
+    /// is maintained only after the entries from the previous period in which it was
+    /// maintained are removed.
+    pub(crate) fn update_transaction_indexes(
+        &mut self,
+        height: Height,
+        indexes: TransactionIndexes,
+    ) -> TransactionIndexes {
+        let mut starts = self.transaction_indexes();
+        let mut maintained = TransactionIndexes::default();
+        for (name, enabled) in indexes.iter() {
+            if enabled && starts.contains(name) {
+                maintained.enable(name);
+                continue;
+            }
+            if !enabled {
+                starts.remove(name);
+            }
+
+            let is_cleared = self.clear_transaction_index(name, MAX_CLEARED_INDEX_LISTS);
+            if enabled && is_cleared {
+                starts.put(&name.to_owned(), height.0);
+                maintained.enable(name);
+            }
+        }
+        maintained
+    }
+
+    /// Removes at most `limit` lists of the transaction index. Returns `true` if the index
+    /// is empty afterwards.
+    fn clear_transaction_index(&mut self, index_name: &str, limit: usize) -> bool {
+        match index_name {
+            TransactionIndexes::BY_INSTANCE => {
+                let mut keys = self.transactions_by_instance_keys();
+                for instance_id in keys.iter().take(limit).collect::<Vec<_>>() {
+                    self.transactions_by_instance(instance_id).clear();
+                    keys.remove(&instance_id);
+                }
+                keys.iter().next().is_none()
+            }
+            TransactionIndexes::BY_AUTHOR => {
+                let mut keys = self.transactions_by_author_keys();
+                for author in keys.iter().take(limit).collect::<Vec<_>>() {
+                    self.transactions_by_author(&author).clear();
+                    keys.remove(&author);
+                }
+                keys.iter().next().is_none()
+            }
+            _ => unreachable!("Unknown transaction index {}", index_name),
+        }
+    }
+
+    /// Adds the committed transaction with the given location into the enabled
+    /// transaction indexes.
+    pub(crate) fn index_transaction(
+        &mut self,
+        location: TxLocation,
+        tx: &Verified<AnyTx>,
+        indexes: TransactionIndexes,
+    ) {
+        if indexes.by_instance {
+            let instance_id = tx.payload().call_info.instance_id;
+            self.transactions_by_instance(instance_id).push(location);
+            self.transactions_by_instance_keys().insert(instance_id);
+        }
+        if indexes.by_author {
+            let author = tx.author();
+            self.transactions_by_author(&author).push(location);
+            self.transactions_by_author_keys().insert(author);
+        }
+    }
+
     /// Updates transaction count of the blockchain.
     pub(crate) fn update_transaction_count(&mut self, count: u64) {
         let mut len_index = self.transactions_len_index();
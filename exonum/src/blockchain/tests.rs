@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, TimeZone, Utc};
 use exonum_crypto::{self as crypto, Hash};
 use exonum_merkledb::{
-    access::AccessExt, BinaryValue, Database, Error as StorageError, ObjectHash, Snapshot,
+    access::AccessExt, BinaryValue, Database, Error as StorageError, Fork, ObjectHash, Snapshot,
     TemporaryDB,
 };
 use exonum_proto::ProtobufConvert;
@@ -24,7 +25,8 @@ use std::{collections::BTreeMap, panic, sync::Mutex};
 
 use crate::{
     blockchain::{
-        Blockchain, BlockchainMut, ExecutionErrorKind, ExecutionStatus, InstanceCollection, Schema,
+        schema::MAX_CLEARED_INDEX_LISTS, Block, Blockchain, BlockchainMut, ExecutionErrorKind,
+        ExecutionStatus, InstanceCollection, Schema, TransactionIndexes, TxLocation,
     },
     helpers::{generate_testnet_config, Height, ValidatorId},
     messages::Verified,
@@ -197,9 +199,19 @@ impl Service for ServiceGoodImpl {
         vec![]
     }
 
-    fn before_commit(&self, context: CallContext<'_>) {
-        let mut index = context.service_data().get_list("val");
-        index.push(1);
+    fn before_commit(&self, mut context: CallContext<'_>) {
+        let len = {
+            let mut index = context.service_data().get_list("val");
+            index.push(1);
+            index.len()
+        };
+        context.set_block_header("len", len).unwrap();
+        // Dots in keys are rejected to avoid collisions with keys of other services.
+        context.set_block_header("nested.len", len).unwrap_err();
+        context
+            .service_data()
+            .get_entry("block_time")
+            .set(context.block_time().unwrap());
     }
 }
 
@@ -294,9 +306,55 @@ impl Service for TxResultCheckService {
     }
 }
 
+const SERVICE_EVENTS_ID: InstanceId = 6;
+
+#[protobuf_convert(source = "TestServiceTx")]
+#[derive(Serialize, Deserialize, Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+struct EventValue {
+    value: u64,
+}
+
+#[exonum_interface(crate = "crate")]
+trait ServiceEventsInterface {
+    fn emit(&self, context: CallContext<'_>, arg: EventValue) -> Result<(), ExecutionError>;
+}
+
+#[derive(Debug, ServiceDispatcher, ServiceFactory)]
+#[service_dispatcher(crate = "crate", implements("ServiceEventsInterface"))]
+#[service_factory(
+    crate = "crate",
+    artifact_name = "service_events",
+    artifact_version = "1.0.0",
+    proto_sources = "crate::proto::schema"
+)]
+struct ServiceEventsImpl;
+
+impl ServiceEventsInterface for ServiceEventsImpl {
+    fn emit(&self, mut context: CallContext<'_>, arg: EventValue) -> Result<(), ExecutionError> {
+        context.emit_event("value", arg.value);
+        // Events of the failed transactions should be discarded.
+        if arg.value % 2 == 1 {
+            Err(ExecutionError::new(ErrorKind::service(0), "Odd value"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Service for ServiceEventsImpl {
+    fn state_hash(&self, _data: BlockchainData<&dyn Snapshot>) -> Vec<Hash> {
+        vec![]
+    }
+}
+
 fn assert_service_execute(blockchain: &mut BlockchainMut) {
-    let (_, patch) =
-        blockchain.create_patch(ValidatorId::zero(), Height(1), &[], &mut BTreeMap::new());
+    let (_, patch) = blockchain.create_patch(
+        ValidatorId::zero(),
+        Height(1),
+        Utc::now(),
+        &[],
+        &mut BTreeMap::new(),
+    );
     blockchain.merge(patch).unwrap();
     let snapshot = blockchain.snapshot();
     let index = snapshot.get_list("service_good.val");
@@ -305,8 +363,13 @@ fn assert_service_execute(blockchain: &mut BlockchainMut) {
 }
 
 fn assert_service_execute_panic(blockchain: &mut BlockchainMut) {
-    let (_, patch) =
-        blockchain.create_patch(ValidatorId::zero(), Height(1), &[], &mut BTreeMap::new());
+    let (_, patch) = blockchain.create_patch(
+        ValidatorId::zero(),
+        Height(1),
+        Utc::now(),
+        &[],
+        &mut BTreeMap::new(),
+    );
     blockchain.merge(patch).unwrap();
     let snapshot = blockchain.snapshot();
     assert!(snapshot
@@ -329,6 +392,7 @@ fn execute_transaction(blockchain: &mut BlockchainMut, tx: Verified<AnyTx>) -> E
     let (block_hash, patch) = blockchain.create_patch(
         ValidatorId::zero(),
         Height::zero(),
+        Utc::now(),
         &[tx.object_hash()],
         &mut BTreeMap::new(),
     );
@@ -377,6 +441,7 @@ fn handling_tx_panic_error() {
     let (_, patch) = blockchain.create_patch(
         ValidatorId::zero(),
         Height::zero(),
+        Utc::now(),
         &[
             tx_ok1.object_hash(),
             tx_failed.object_hash(),
@@ -432,6 +497,7 @@ fn handling_tx_panic_storage_error() {
     blockchain.create_patch(
         ValidatorId::zero(),
         Height::zero(),
+        Utc::now(),
         &[
             tx_ok1.object_hash(),
             tx_storage_error.object_hash(),
@@ -449,6 +515,224 @@ fn service_execute_good() {
     assert_service_execute(&mut blockchain);
 }
 
+#[test]
+fn block_additional_headers() {
+    let mut blockchain = create_blockchain(vec![
+        InstanceCollection::new(ServiceGoodImpl).with_instance(3, "service_good", ())
+    ]);
+
+    // The genesis block marks the initial consensus configuration.
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    let config_hash = schema.consensus_config_entry().object_hash();
+    let genesis_block = schema.last_block();
+    let headers = genesis_block.additional_headers();
+    assert_eq!(headers.len(), 1);
+    assert_eq!(
+        headers.get::<Hash>(Block::CONSENSUS_CONFIG_HEADER).unwrap(),
+        Some(config_hash)
+    );
+
+    let (block_hash, patch) = blockchain.create_patch(
+        ValidatorId::zero(),
+        Height(1),
+        Utc::now(),
+        &[],
+        &mut BTreeMap::new(),
+    );
+    blockchain.merge(patch).unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    let block = schema.blocks().get(&block_hash).unwrap();
+    let headers = block.additional_headers();
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers.get::<u64>("service_good.len").unwrap(), Some(1));
+    assert!(headers.get_raw(Block::CONSENSUS_CONFIG_HEADER).is_none());
+    assert!(schema.pending_block_headers().keys().next().is_none());
+}
+
+#[test]
+fn block_time() {
+    let mut blockchain = create_blockchain(vec![
+        InstanceCollection::new(ServiceGoodImpl).with_instance(3, "service_good", ())
+    ]);
+    assert_eq!(
+        blockchain.as_ref().last_block().timestamp(),
+        Utc.timestamp(0, 0)
+    );
+
+    let time = Utc.timestamp(1_500_000_000, 0);
+    let (block_hash, patch) = blockchain.create_patch(
+        ValidatorId::zero(),
+        Height(1),
+        time,
+        &[],
+        &mut BTreeMap::new(),
+    );
+    blockchain.merge(patch).unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(schema.blocks().get(&block_hash).unwrap().timestamp(), time);
+    assert_eq!(
+        snapshot
+            .get_entry::<_, DateTime<Utc>>("service_good.block_time")
+            .get(),
+        Some(time)
+    );
+    assert!(!schema.pending_block_time().exists());
+}
+
+#[test]
+fn service_events() {
+    let mut blockchain = create_blockchain(vec![InstanceCollection::new(ServiceEventsImpl)
+        .with_instance(SERVICE_EVENTS_ID, "service_events", ())]);
+
+    let (pk, sec_key) = crypto::gen_keypair();
+    let tx_hashes = [2, 3, 4]
+        .iter()
+        .map(|&value| {
+            let transaction = EventValue { value }.sign(SERVICE_EVENTS_ID, pk, &sec_key);
+            let hash = transaction.object_hash();
+            let fork = blockchain.fork();
+            Schema::new(&fork).add_transaction_into_pool(transaction);
+            blockchain.merge(fork.into_patch()).unwrap();
+            hash
+        })
+        .collect::<Vec<_>>();
+
+    let (block_hash, patch) = blockchain.create_patch(
+        ValidatorId::zero(),
+        Height(1),
+        Utc::now(),
+        &tx_hashes,
+        &mut BTreeMap::new(),
+    );
+    blockchain.merge(patch).unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    let events = schema.service_events(Height(1));
+    let values = events
+        .iter()
+        .map(|event| {
+            assert_eq!(event.instance_id, SERVICE_EVENTS_ID);
+            assert_eq!(event.name, "value");
+            event.payload::<u64>().unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![2, 4]);
+
+    let block = schema.blocks().get(&block_hash).unwrap();
+    assert_eq!(
+        block
+            .additional_headers()
+            .get::<Hash>(Block::SERVICE_EVENTS_HEADER)
+            .unwrap(),
+        Some(events.object_hash())
+    );
+    // The genesis block contains no events.
+    assert!(schema.service_events(Height(0)).is_empty());
+}
+
+#[test]
+fn transaction_indexes_are_cleared_when_reenabled() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut schema = Schema::new(&fork);
+    let (pk, sec_key) = crypto::gen_keypair();
+    let tx = TestExecute { value: 1 }.sign(TEST_SERVICE_ID, pk, &sec_key);
+    let all_indexes = TransactionIndexes {
+        by_instance: true,
+        by_author: true,
+    };
+
+    schema.update_transaction_indexes(Height(1), all_indexes);
+    schema.index_transaction(TxLocation::new(Height(1), 0), &tx, all_indexes);
+    assert_eq!(schema.transactions_by_instance(TEST_SERVICE_ID).len(), 1);
+    assert_eq!(schema.transactions_by_author(&pk).len(), 1);
+
+    // Disable the index by author.
+    let by_instance = TransactionIndexes {
+        by_instance: true,
+        by_author: false,
+    };
+    schema.update_transaction_indexes(Height(2), by_instance);
+    assert_eq!(
+        schema.transaction_index_start(TransactionIndexes::BY_AUTHOR),
+        None
+    );
+    assert!(schema.transactions_by_author(&pk).is_empty());
+    assert_eq!(schema.transactions_by_instance(TEST_SERVICE_ID).len(), 1);
+
+    // Re-enable the index. It should not contain entries from the previous period.
+    schema.index_transaction(TxLocation::new(Height(2), 0), &tx, by_instance);
+    schema.update_transaction_indexes(Height(3), all_indexes);
+    assert_eq!(
+        schema.transaction_index_start(TransactionIndexes::BY_AUTHOR),
+        Some(Height(3))
+    );
+    assert_eq!(
+        schema.transaction_index_start(TransactionIndexes::BY_INSTANCE),
+        Some(Height(1))
+    );
+    assert!(schema.transactions_by_author(&pk).is_empty());
+    assert_eq!(schema.transactions_by_instance(TEST_SERVICE_ID).len(), 2);
+}
+
+#[test]
+fn transaction_indexes_are_cleared_incrementally() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut schema = Schema::new(&fork);
+    let (pk, sec_key) = crypto::gen_keypair();
+    let by_instance = TransactionIndexes {
+        by_instance: true,
+        by_author: false,
+    };
+    let no_indexes = TransactionIndexes::default();
+
+    let list_count = 2 * MAX_CLEARED_INDEX_LISTS + 1;
+    assert_eq!(
+        schema.update_transaction_indexes(Height(1), by_instance),
+        by_instance
+    );
+    for instance_id in 0..list_count as InstanceId {
+        let tx = TestExecute { value: 1 }.sign(instance_id, pk, &sec_key);
+        schema.index_transaction(TxLocation::new(Height(1), 0), &tx, by_instance);
+    }
+    let non_empty_lists = |schema: &Schema<&Fork>| {
+        (0..list_count as InstanceId)
+            .filter(|&instance_id| !schema.transactions_by_instance(instance_id).is_empty())
+            .count()
+    };
+
+    schema.update_transaction_indexes(Height(2), no_indexes);
+    assert_eq!(non_empty_lists(&schema), MAX_CLEARED_INDEX_LISTS + 1);
+
+    // The re-enabled index is not maintained until its previous content is removed.
+    assert_eq!(
+        schema.update_transaction_indexes(Height(3), by_instance),
+        no_indexes
+    );
+    assert_eq!(non_empty_lists(&schema), 1);
+    assert_eq!(
+        schema.transaction_index_start(TransactionIndexes::BY_INSTANCE),
+        None
+    );
+
+    assert_eq!(
+        schema.update_transaction_indexes(Height(4), by_instance),
+        by_instance
+    );
+    assert_eq!(non_empty_lists(&schema), 0);
+    assert_eq!(
+        schema.transaction_index_start(TransactionIndexes::BY_INSTANCE),
+        Some(Height(4))
+    );
+}
+
 #[test]
 fn service_execute_panic() {
     let mut blockchain = create_blockchain(vec![
@@ -495,6 +779,7 @@ fn error_discards_transaction_changes() {
         let (_, patch) = blockchain.create_patch(
             ValidatorId::zero(),
             Height(index),
+            Utc::now(),
             &[hash],
             &mut BTreeMap::new(),
         );
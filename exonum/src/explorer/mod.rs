@@ -18,11 +18,12 @@
 //! See the `explorer` example in the crate for examples of usage.
 
 use chrono::{DateTime, Utc};
-use exonum_merkledb::{ListProof, ObjectHash, Snapshot};
+use exonum_merkledb::{ListIndex, ListProof, ObjectHash, Snapshot};
 use serde::{Serialize, Serializer};
 
 use std::{
     cell::{Ref, RefCell},
+    cmp,
     collections::Bound,
     fmt,
     ops::{Index, RangeBounds},
@@ -31,10 +32,14 @@ use std::{
 };
 
 use crate::{
-    blockchain::{Block, ExecutionError, ExecutionStatus, Schema, TxLocation},
-    crypto::Hash,
-    helpers::Height,
+    blockchain::{
+        Block, Equivocation, ExecutionError, ExecutionStatus, Schema, TransactionIndexes,
+        TxLocation,
+    },
+    crypto::{Hash, PublicKey},
+    helpers::{Height, ValidatorId},
     messages::{AnyTx, Precommit, Verified},
+    runtime::{InstanceId, MethodId},
 };
 
 /// Ending height of the range (exclusive), given the a priori max height.
@@ -615,6 +620,19 @@ impl<'a> BlockchainExplorer<'a> {
         })
     }
 
+    /// Returns equivocations observed by the node, optionally filtered by the validator
+    /// which has committed them. Equivocations are sorted by height and round.
+    pub fn equivocations(&self, validator: Option<ValidatorId>) -> Vec<Equivocation> {
+        let mut equivocations: Vec<_> = self
+            .schema
+            .equivocations()
+            .values()
+            .filter(|equivocation| validator.map_or(true, |id| equivocation.validator() == id))
+            .collect();
+        equivocations.sort_by_key(|equivocation| (equivocation.height(), equivocation.round()));
+        equivocations
+    }
+
     /// Iterates over blocks in the blockchain.
     pub fn blocks<R: RangeBounds<Height>>(&self, heights: R) -> Blocks<'_> {
         use std::cmp::max;
@@ -631,6 +649,118 @@ impl<'a> BlockchainExplorer<'a> {
             back: max(ptr, end_height(heights.end_bound(), max_height)),
         }
     }
+
+    /// Iterates over committed transactions matching the filter, starting from the latest
+    /// transaction and going back in time. If `before` is specified, only transactions
+    /// committed before the specified location are returned, which can be used to paginate
+    /// the list of transactions. Locations beyond the end of the blockchain are ignored.
+    ///
+    /// If the filter specifies an author or a service instance and the corresponding
+    /// transaction index (`by_author` or `by_instance`) is enabled, the index is used
+    /// to look up transactions committed since the index was enabled. Otherwise, blocks
+    /// are scanned one by one; the number of scanned blocks can be limited with
+    /// [`CommittedTransactions::scan_limit`].
+    ///
+    /// [`CommittedTransactions::scan_limit`]: struct.CommittedTransactions.html#method.scan_limit
+    pub fn committed_transactions(
+        &self,
+        filter: CommittedTransactionFilter,
+        before: Option<&TxLocation>,
+    ) -> CommittedTransactions<'a> {
+        let height = self.height();
+        let scan_ptr = match before {
+            Some(location) if location.block_height() <= height => {
+                let height = location.block_height();
+                let len = self.schema.block_transactions(height).len();
+                (height, cmp::min(location.position_in_block(), len))
+            }
+            _ => (height, self.schema.block_transactions(height).len()),
+        };
+
+        let indexed = self.select_index(&filter).map(|(locations, start)| {
+            let remaining = count_locations_before(&locations, scan_ptr);
+            IndexedTransactions {
+                locations,
+                remaining,
+                start,
+                ptr: scan_ptr,
+            }
+        });
+        let scan_ptr = match indexed {
+            Some(ref indexed) => cmp::min(scan_ptr, (indexed.start, 0)),
+            None => scan_ptr,
+        };
+
+        CommittedTransactions {
+            explorer: *self,
+            filter,
+            indexed,
+            scan_ptr: Some(scan_ptr),
+            scan_limit: None,
+            scanned_blocks: 0,
+        }
+    }
+
+    /// Iterates over transactions signed by the specified key, starting from the latest
+    /// transaction and going back in time. If `before` is specified, only transactions
+    /// committed before the specified location are returned.
+    ///
+    /// If the `by_author` transaction index is enabled, the index is used to look up
+    /// transactions committed since the index was enabled. Otherwise, blocks are scanned
+    /// one by one, which may be slow for long blockchains; use
+    /// [`CommittedTransactions::scan_limit`] to bound the scan.
+    ///
+    /// [`CommittedTransactions::scan_limit`]: struct.CommittedTransactions.html#method.scan_limit
+    pub fn transactions_by_author(
+        &self,
+        author: PublicKey,
+        before: Option<&TxLocation>,
+    ) -> CommittedTransactions<'a> {
+        let filter = CommittedTransactionFilter::new().with_author(author);
+        self.committed_transactions(filter, before)
+    }
+
+    /// Selects the transaction index to look up transactions matching the filter together
+    /// with the height since which the index is maintained. The index by author is preferred
+    /// as more selective.
+    fn select_index(
+        &self,
+        filter: &CommittedTransactionFilter,
+    ) -> Option<(ListIndex<&'a dyn Snapshot, TxLocation>, Height)> {
+        if let Some(ref author) = filter.author {
+            if let Some(start) = self
+                .schema
+                .transaction_index_start(TransactionIndexes::BY_AUTHOR)
+            {
+                return Some((self.schema.transactions_by_author(author), start));
+            }
+        }
+
+        let instance_id = filter.instance_id?;
+        let start = self
+            .schema
+            .transaction_index_start(TransactionIndexes::BY_INSTANCE)?;
+        Some((self.schema.transactions_by_instance(instance_id), start))
+    }
+}
+
+/// Returns the number of locations in the list preceding the specified location.
+/// Locations in the list are ordered, so binary search is used.
+fn count_locations_before(
+    locations: &ListIndex<&dyn Snapshot, TxLocation>,
+    location: (Height, u64),
+) -> u64 {
+    let (mut low, mut high) = (0, locations.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let entry = locations.get(mid).unwrap();
+        if (entry.block_height(), entry.position_in_block()) < location {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
 }
 
 /// Iterator over blocks in the blockchain.
@@ -696,6 +826,208 @@ impl<'a> DoubleEndedIterator for Blocks<'a> {
     }
 }
 
+/// Expected result of the transaction execution.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusFilter {
+    /// Transaction has been executed successfully.
+    Success,
+    /// Transaction execution has failed.
+    Failure,
+}
+
+impl StatusFilter {
+    fn matches(self, status: &ExecutionStatus) -> bool {
+        match self {
+            StatusFilter::Success => status.0.is_ok(),
+            StatusFilter::Failure => status.0.is_err(),
+        }
+    }
+}
+
+/// Filter for committed transactions returned by
+/// [`BlockchainExplorer::committed_transactions`]. Transaction matches the filter
+/// if it matches all specified conditions; unset conditions match any transaction.
+///
+/// [`BlockchainExplorer::committed_transactions`]: struct.BlockchainExplorer.html#method.committed_transactions
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct CommittedTransactionFilter {
+    /// ID of the service instance.
+    pub instance_id: Option<InstanceId>,
+    /// ID of the method in the service interface.
+    pub method_id: Option<MethodId>,
+    /// Author of the transaction.
+    pub author: Option<PublicKey>,
+    /// Result of the transaction execution.
+    pub status: Option<StatusFilter>,
+}
+
+impl CommittedTransactionFilter {
+    /// Creates a filter matching any transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the filter to transactions addressed to the specified service instance
+    /// and (optionally) method.
+    pub fn with_instance(mut self, instance_id: InstanceId, method_id: Option<MethodId>) -> Self {
+        self.instance_id = Some(instance_id);
+        self.method_id = method_id;
+        self
+    }
+
+    /// Restricts the filter to transactions signed by the specified key.
+    pub fn with_author(mut self, author: PublicKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Restricts the filter to transactions with the specified execution result.
+    pub fn with_status(mut self, status: StatusFilter) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Checks if the transaction with the specified execution status matches the filter.
+    pub fn matches(&self, transaction: &Verified<AnyTx>, status: &ExecutionStatus) -> bool {
+        let call_info = &transaction.as_ref().call_info;
+        self.instance_id
+            .map_or(true, |instance_id| instance_id == call_info.instance_id)
+            && self
+                .method_id
+                .map_or(true, |method_id| method_id == call_info.method_id)
+            && self
+                .author
+                .map_or(true, |author| author == transaction.author())
+            && self
+                .status
+                .map_or(true, |expected| expected.matches(status))
+    }
+}
+
+/// Transactions read from a transaction index.
+struct IndexedTransactions<'a> {
+    locations: ListIndex<&'a dyn Snapshot, TxLocation>,
+    /// Number of transactions in the index which are yet to be returned.
+    remaining: u64,
+    /// Height since which the index is maintained.
+    start: Height,
+    /// Location of the last transaction read from the index (exclusive).
+    ptr: (Height, u64),
+}
+
+/// Iterator over committed transactions in the reverse order of their commitment.
+///
+/// See [`BlockchainExplorer::committed_transactions`] for details.
+///
+/// [`BlockchainExplorer::committed_transactions`]: struct.BlockchainExplorer.html#method.committed_transactions
+pub struct CommittedTransactions<'a> {
+    explorer: BlockchainExplorer<'a>,
+    filter: CommittedTransactionFilter,
+    indexed: Option<IndexedTransactions<'a>>,
+    /// Location of the block scan (exclusive), or `None` if the scan has reached
+    /// the genesis block.
+    scan_ptr: Option<(Height, u64)>,
+    /// Maximum number of blocks the scan may move through.
+    scan_limit: Option<u64>,
+    /// Number of blocks the scan has moved through.
+    scanned_blocks: u64,
+}
+
+impl<'a> fmt::Debug for CommittedTransactions<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        formatter
+            .debug_struct("CommittedTransactions")
+            .field("filter", &self.filter)
+            .field("scan_ptr", &self.scan_ptr)
+            .field("scan_limit", &self.scan_limit)
+            .finish()
+    }
+}
+
+impl<'a> CommittedTransactions<'a> {
+    /// Limits the number of blocks the iterator moves through while scanning blocks
+    /// (the block containing the initial location is not counted). Once the limit
+    /// is reached, the iterator stops, and the scan can be resumed from
+    /// the [`cursor`](#method.cursor).
+    ///
+    /// Transactions looked up with the transaction indexes do not count towards the limit.
+    pub fn scan_limit(mut self, max_blocks: u64) -> Self {
+        self.scan_limit = Some(max_blocks);
+        self
+    }
+
+    /// Returns the location before which the transactions are yet to be examined,
+    /// or `None` if all transactions have been examined. The location can be passed
+    /// as `before` to [`BlockchainExplorer::committed_transactions`] to continue iteration.
+    ///
+    /// [`BlockchainExplorer::committed_transactions`]: struct.BlockchainExplorer.html#method.committed_transactions
+    pub fn cursor(&self) -> Option<TxLocation> {
+        let ptr = match self.indexed {
+            Some(ref indexed) => Some(indexed.ptr),
+            None => self.scan_ptr,
+        };
+        ptr.map(|(height, position)| TxLocation::new(height, position))
+    }
+
+    fn next_hash(&mut self) -> Option<Hash> {
+        let schema = self.explorer.schema;
+        if let Some(indexed) = self.indexed.as_mut() {
+            if indexed.remaining > 0 {
+                indexed.remaining -= 1;
+                let location = indexed.locations.get(indexed.remaining).unwrap();
+                let (height, position) = (location.block_height(), location.position_in_block());
+                if height >= indexed.start {
+                    indexed.ptr = (height, position);
+                    return schema.block_transactions(height).get(position);
+                }
+            }
+            // Transactions committed before the index was enabled are found by the block scan.
+            self.indexed = None;
+        }
+
+        while let Some((height, position)) = self.scan_ptr {
+            if position > 0 {
+                self.scan_ptr = Some((height, position - 1));
+                return schema.block_transactions(height).get(position - 1);
+            }
+
+            if height == Height(0) {
+                self.scan_ptr = None;
+            } else if self
+                .scan_limit
+                .map_or(false, |limit| self.scanned_blocks >= limit)
+            {
+                return None;
+            } else {
+                self.scanned_blocks += 1;
+                let height = height.previous();
+                self.scan_ptr = Some((height, schema.block_transactions(height).len()));
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for CommittedTransactions<'a> {
+    type Item = CommittedTransaction;
+
+    fn next(&mut self) -> Option<CommittedTransaction> {
+        loop {
+            let tx_hash = self.next_hash()?;
+            let schema = self.explorer.schema;
+            let content = schema
+                .transactions()
+                .get(&tx_hash)
+                .expect("BUG: Cannot find transaction in database");
+            let status = schema.transaction_results().get(&tx_hash).unwrap();
+            if self.filter.matches(&content, &status) {
+                return Some(self.explorer.committed_transaction(&tx_hash, Some(content)));
+            }
+        }
+    }
+}
+
 /// Calculates a median time from precommits.
 pub fn median_precommits_time(precommits: &[Verified<Precommit>]) -> DateTime<Utc> {
     if precommits.is_empty() {
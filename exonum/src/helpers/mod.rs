@@ -75,6 +75,8 @@ pub fn generate_testnet_config(count: u16, start_port: u16) -> Vec<NodeConfig> {
             services_configs: Default::default(),
             database: Default::default(),
             thread_pool_size: Default::default(),
+            consensus_events_log: None,
+            transaction_indexes: Default::default(),
             master_key_path: "master.key.toml".into(),
             keys,
         })
@@ -154,6 +154,8 @@ impl Status {
 /// The message is ignored if it
 ///     * contains incorrect `prev_hash`
 ///     * is sent by non-leader
+///     * contains `time` earlier than the time of the previous block or too far
+///       in the future according to `ConsensusConfig::max_block_time_drift`
 ///     * contains already committed transactions
 ///     * is already known
 ///
@@ -178,6 +180,8 @@ pub struct Propose {
     pub prev_hash: Hash,
     /// The list of transactions to include in the next block.
     pub transactions: Vec<Hash>,
+    /// Time of the proposed block according to the local clock of the leader.
+    pub time: DateTime<Utc>,
 }
 
 impl Propose {
@@ -188,6 +192,7 @@ impl Propose {
         round: Round,
         prev_hash: Hash,
         transactions: impl IntoIterator<Item = Hash>,
+        time: DateTime<Utc>,
     ) -> Self {
         Self {
             validator,
@@ -195,6 +200,7 @@ impl Propose {
             round,
             prev_hash,
             transactions: transactions.into_iter().collect(),
+            time,
         }
     }
 
@@ -218,6 +224,11 @@ impl Propose {
     pub fn transactions(&self) -> &[Hash] {
         &self.transactions
     }
+
+    /// Time of the proposed block according to the local clock of the leader.
+    pub fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
 }
 
 /// Pre-vote for a new block.
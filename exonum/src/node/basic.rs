@@ -15,6 +15,7 @@
 use rand::Rng;
 
 use super::{NodeHandler, NodeRole, RequestData};
+use crate::blockchain::Schema;
 use crate::crypto::PublicKey;
 use crate::events::error::LogError;
 use crate::events::network::ConnectedPeerAddr;
@@ -231,6 +232,9 @@ impl NodeHandler {
     /// Node update internal `ApiState` and `NodeRole`.
     pub fn handle_update_api_state_timeout(&mut self) {
         self.api_state.update_node_state(&self.state);
+        let pool_len = Schema::new(&self.blockchain.snapshot()).transactions_pool_len();
+        let mempool_size = pool_len as usize + self.state.tx_cache_len();
+        self.metrics().mempool_size.set(mempool_size as i64);
         // FIXME Add special event to update state [ECR-3222]
         self.node_role = NodeRole::new(self.state.validator_id());
         self.add_update_api_state_timeout();
@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Duration, Utc};
 use exonum_merkledb::{BinaryValue, ObjectHash, Patch};
 
-use std::{collections::HashSet, convert::TryFrom};
+use std::{collections::HashSet, convert::TryFrom, time::Instant};
 
 use crate::{
-    blockchain::{contains_transaction, Schema},
+    blockchain::{contains_transaction, Block, Schema},
     crypto::{Hash, PublicKey},
     events::InternalRequest,
     helpers::{Height, Round, ValidatorId},
@@ -26,7 +27,7 @@ use crate::{
         Precommit, Prevote, PrevotesRequest, Propose, ProposeRequest, SignedMessage,
         TransactionsRequest, TransactionsResponse, Verified,
     },
-    node::{NodeHandler, RequestData},
+    node::{ConsensusEvent, NodeHandler, RequestData},
 };
 
 // Shortcut to get verified messages from bytes.
@@ -125,10 +126,17 @@ impl NodeHandler {
             return;
         }
 
-        trace!("Handle propose");
-
         let snapshot = self.blockchain.snapshot();
         let schema = Schema::new(&snapshot);
+
+        // Check time
+        if let Err(err) = self.validate_propose_time(msg.payload(), &schema.last_block()) {
+            error!("{}, msg={:?}", err, msg);
+            return;
+        }
+
+        trace!("Handle propose");
+
         let has_unknown_txs = match self.state.add_propose(
             msg.clone(),
             &schema.transactions(),
@@ -141,7 +149,16 @@ impl NodeHandler {
             }
         };
 
+        let elapsed = self.height_elapsed();
+        self.metrics().propose_latency.observe_duration(elapsed);
+
         let hash = msg.object_hash();
+        self.emit_event(ConsensusEvent::ProposeReceived {
+            height: self.state.height(),
+            round: msg.payload().round(),
+            validator: msg.payload().validator,
+            propose_hash: hash,
+        });
         let known_nodes = self.remove_request(&RequestData::Propose(hash));
 
         if has_unknown_txs {
@@ -155,6 +172,34 @@ impl NodeHandler {
         }
     }
 
+    /// Checks that the time of the propose is not earlier than the time of the previous block
+    /// and is not too far in the future according to the local clock.
+    fn validate_propose_time(
+        &self,
+        propose: &Propose,
+        last_block: &Block,
+    ) -> Result<(), failure::Error> {
+        if propose.time() < last_block.timestamp() {
+            bail!(
+                "Received propose with time earlier than the previous block, \
+                 time={}, previous_block_time={}",
+                propose.time(),
+                last_block.timestamp()
+            );
+        }
+
+        let local_time = DateTime::<Utc>::from(self.system_state.current_time());
+        let max_drift = Duration::milliseconds(self.max_block_time_drift() as i64);
+        if propose.time() > local_time + max_drift {
+            bail!(
+                "Received propose with time too far in the future, time={}, local_time={}",
+                propose.time(),
+                local_time
+            );
+        }
+        Ok(())
+    }
+
     fn validate_block_response(&self, msg: &Verified<BlockResponse>) -> Result<(), failure::Error> {
         if msg.payload().to != self.state.consensus_public_key() {
             bail!(
@@ -240,6 +285,12 @@ impl NodeHandler {
 
     /// Executes and commits block. This function is called when node has full propose information.
     pub fn handle_full_propose(&mut self, hash: Hash, propose_round: Round) {
+        self.emit_event(ConsensusEvent::ProposeAccepted {
+            height: self.state.height(),
+            round: propose_round,
+            propose_hash: hash,
+        });
+
         // Send prevote
         if self.state.locked_round() == Round::zero() {
             if self.state.is_validator() && !self.state.have_prevote(propose_round) {
@@ -291,6 +342,7 @@ impl NodeHandler {
             let (computed_block_hash, patch) = self.create_block(
                 block.proposer_id(),
                 block.height(),
+                block.timestamp(),
                 msg.payload().transactions(),
             );
             // Verify block_hash.
@@ -324,7 +376,8 @@ impl NodeHandler {
         );
 
         // Add prevote
-        let has_consensus = self.state.add_prevote(msg.clone());
+        let has_consensus = self.add_prevote(msg.clone());
+        self.save_equivocations();
 
         // Request propose or transactions
         let has_propose_with_txs = self.request_propose_or_txs(msg.payload().propose_hash, from);
@@ -420,6 +473,11 @@ impl NodeHandler {
                 self.blockchain.save_messages(round, raw_messages);
 
                 self.state.lock(round, propose_hash);
+                self.emit_event(ConsensusEvent::LockAcquired {
+                    height: self.state.height(),
+                    round,
+                    propose_hash,
+                });
                 // Send precommit
                 if self.state.is_validator() && !self.state.have_incompatible_prevotes() {
                     // Execute block and get state hash
@@ -448,6 +506,7 @@ impl NodeHandler {
 
         // Add precommit
         let has_consensus = self.state.add_precommit(msg.clone());
+        self.save_equivocations();
 
         // Request propose
         if self.state.propose(msg.payload().propose_hash()).is_none() {
@@ -483,13 +542,21 @@ impl NodeHandler {
     ) {
         trace!("COMMIT {:?}", block_hash);
 
+        if let Some(round) = round {
+            let elapsed = self.height_elapsed();
+            let metrics = self.metrics();
+            metrics.precommit_latency.observe_duration(elapsed);
+            metrics.rounds_per_height.observe(f64::from(round.0));
+        }
+
         // Merge changes into storage
         let (committed_txs, proposer) = {
-            let (committed_txs, proposer) = {
+            let (committed_txs, proposer, merge_duration) = {
                 let block_state = self.state.block_mut(&block_hash).unwrap();
                 let committed_txs = block_state.txs().len();
                 let proposer = block_state.proposer_id();
 
+                let merge_start = Instant::now();
                 self.blockchain
                     .commit(
                         block_state.patch(),
@@ -499,8 +566,11 @@ impl NodeHandler {
                     )
                     .unwrap();
 
-                (committed_txs, proposer)
+                (committed_txs, proposer, merge_start.elapsed())
             };
+            self.metrics()
+                .db_merge_duration
+                .observe_duration(merge_duration);
             // Update node state.
             self.state
                 .update_config(Schema::new(&self.blockchain.snapshot()).consensus_config());
@@ -518,6 +588,20 @@ impl NodeHandler {
         let pool_len = schema.transactions_pool_len();
 
         let height = self.state.height();
+        self.metrics().height.set(height.0 as i64);
+        self.metrics().round.set(i64::from(self.state.round().0));
+        self.emit_event(ConsensusEvent::BlockCommitted {
+            height: height.previous(),
+            round,
+            block_hash,
+            tx_count: committed_txs,
+        });
+        self.emit_event(ConsensusEvent::RoundStarted {
+            height,
+            round: self.state.round(),
+        });
+        let mempool_size = pool_len as usize + self.state.tx_cache_len();
+        self.metrics().mempool_size.set(mempool_size as i64);
         info!(
             "COMMIT ====== height={}, proposer={}, round={}, committed={}, pool={}, hash={}",
             height,
@@ -640,6 +724,11 @@ impl NodeHandler {
 
     // Try to process consensus messages from the future round.
     fn process_new_round(&mut self) {
+        self.metrics().round.set(i64::from(self.state.round().0));
+        self.emit_event(ConsensusEvent::RoundStarted {
+            height: self.state.height(),
+            round: self.state.round(),
+        });
         if self.state.is_validator() {
             // Send prevote if we are locked or propose if we are leader
             if let Some(hash) = self.state.locked_propose() {
@@ -699,6 +788,9 @@ impl NodeHandler {
             let round = self.state.round();
 
             let txs = self.get_txs_for_propose();
+            // Block times must not decrease even if the local clock lags behind.
+            let last_block_time = self.blockchain.as_ref().last_block().timestamp();
+            let time = DateTime::<Utc>::from(self.system_state.current_time()).max(last_block_time);
 
             let propose = self.sign_message(Propose::new(
                 validator_id,
@@ -706,6 +798,7 @@ impl NodeHandler {
                 round,
                 self.state.last_hash(),
                 txs,
+                time,
             ));
             // Put our propose to the consensus messages cache
             self.blockchain.save_message(round, propose.clone());
@@ -715,8 +808,16 @@ impl NodeHandler {
 
             self.allow_expedited_propose = true;
 
+            let elapsed = self.height_elapsed();
+            self.metrics().propose_latency.observe_duration(elapsed);
+
             // Save our propose into state
             let hash = self.state.add_self_propose(propose);
+            self.emit_event(ConsensusEvent::ProposeAccepted {
+                height: self.state.height(),
+                round,
+                propose_hash: hash,
+            });
 
             // Send prevote
             let has_majority_prevotes = self.broadcast_prevote(round, hash);
@@ -802,6 +903,16 @@ impl NodeHandler {
                     self.sign_message(BlockRequest::new(peer, height)).into()
                 }
             };
+            let kind = match *data {
+                RequestData::Propose(..) => "propose",
+                RequestData::PoolTransactions => "pool_transactions",
+                RequestData::ProposeTransactions(..) | RequestData::BlockTransactions => {
+                    "transactions"
+                }
+                RequestData::Prevotes(..) => "prevotes",
+                RequestData::Block(..) => "block",
+            };
+            self.metrics().requests_sent.inc(kind);
             trace!("Send request {:?} to peer {:?}", data, peer);
             self.send_to_peer(peer, message);
         }
@@ -812,11 +923,13 @@ impl NodeHandler {
         &mut self,
         proposer_id: ValidatorId,
         height: Height,
+        time: DateTime<Utc>,
         tx_hashes: &[Hash],
     ) -> (Hash, Patch) {
         self.blockchain.create_patch(
             proposer_id,
             height,
+            time,
             tx_hashes,
             &mut self.state.tx_cache_mut(),
         )
@@ -840,6 +953,7 @@ impl NodeHandler {
         let (block_hash, patch) = self.create_block(
             propose.validator,
             propose.height,
+            propose.time,
             propose.transactions.as_slice(),
         );
         // Save patch
@@ -905,6 +1019,26 @@ impl NodeHandler {
         self.state.remove_request(data)
     }
 
+    /// Adds the prevote to the node state. Emits the `MajorityPrevotes` event and records
+    /// the prevote latency once the majority of prevotes for the proposal is collected.
+    /// Returns `true` if there is the majority of prevotes.
+    fn add_prevote(&mut self, prevote: Verified<Prevote>) -> bool {
+        let round = prevote.payload().round;
+        let propose_hash = prevote.payload().propose_hash;
+        let had_majority_prevotes = self.state.has_majority_prevotes(round, propose_hash);
+        let has_majority_prevotes = self.state.add_prevote(prevote);
+        if has_majority_prevotes && !had_majority_prevotes {
+            let elapsed = self.height_elapsed();
+            self.metrics().prevote_latency.observe_duration(elapsed);
+            self.emit_event(ConsensusEvent::MajorityPrevotes {
+                height: self.state.height(),
+                round,
+                propose_hash,
+            });
+        }
+        has_majority_prevotes
+    }
+
     /// Broadcasts the `Prevote` message to all peers.
     pub fn broadcast_prevote(&mut self, round: Round, propose_hash: Hash) -> bool {
         let validator_id = self
@@ -919,7 +1053,7 @@ impl NodeHandler {
             propose_hash,
             locked_round,
         ));
-        let has_majority_prevotes = self.state.add_prevote(prevote.clone());
+        let has_majority_prevotes = self.add_prevote(prevote.clone());
 
         // save outgoing Prevote to the consensus messages cache before broadcast
         self.check_propose_saved(round, &propose_hash);
@@ -1029,6 +1163,13 @@ impl NodeHandler {
         Ok(())
     }
 
+    /// Saves equivocations detected by the node state to the storage.
+    fn save_equivocations(&mut self) {
+        for equivocation in self.state.take_equivocations() {
+            self.blockchain.save_equivocation(equivocation);
+        }
+    }
+
     /// Checks whether Propose is saved to the consensus cache and saves it otherwise
     fn check_propose_saved(&mut self, round: Round, propose_hash: &Hash) {
         if let Some(propose_state) = self.state.propose_mut(propose_hash) {
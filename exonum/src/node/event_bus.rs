@@ -0,0 +1,412 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured events of the consensus process.
+//!
+//! The node emits a `ConsensusEvent` at each significant step of the consensus algorithm.
+//! Events can be streamed via the `v1/consensus_events/subscribe` websocket endpoint
+//! of the private API, or recorded to a file (one JSON object per line) specified
+//! in the `consensus_events_log` field of the node configuration. Recorded files
+//! can be read back with `ConsensusEventBus::read_log` to analyze them or to replay
+//! the messages received by the node against the sandbox.
+//!
+//! Emitting events never blocks the consensus: events are written to the file
+//! by a dedicated thread, and subscribers which do not keep up with the events
+//! are dropped.
+
+use chrono::{DateTime, Utc};
+use futures::sync::mpsc;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    mem,
+    path::Path,
+    sync::{mpsc as std_mpsc, Mutex},
+    thread,
+};
+
+use crate::{
+    crypto::Hash,
+    helpers::{Height, Round, ValidatorId},
+    messages::SignedMessage,
+};
+
+/// Maximum number of events waiting to be delivered to a subscriber. Subscribers
+/// lagging behind by more events are dropped.
+pub const SUBSCRIBER_QUEUE_SIZE: usize = 1_024;
+/// Maximum number of events waiting to be written to the log file. Events which do not
+/// fit into the queue are not recorded.
+const LOG_QUEUE_SIZE: usize = 4_096;
+
+/// Event of the consensus process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ConsensusEvent {
+    /// The node has started a new round.
+    RoundStarted {
+        /// Current height.
+        height: Height,
+        /// Started round.
+        round: Round,
+    },
+    /// The node has received a valid proposal from the leader.
+    ProposeReceived {
+        /// Current height.
+        height: Height,
+        /// Round of the proposal.
+        round: Round,
+        /// Leader of the round.
+        validator: ValidatorId,
+        /// Hash of the proposal.
+        propose_hash: Hash,
+    },
+    /// All transactions of the proposal are known to the node, or the proposal
+    /// has been created by the node itself.
+    ProposeAccepted {
+        /// Current height.
+        height: Height,
+        /// Round of the proposal.
+        round: Round,
+        /// Hash of the proposal.
+        propose_hash: Hash,
+    },
+    /// The node has collected the majority of prevotes for the proposal. The proposal
+    /// itself may be still unknown to the node.
+    MajorityPrevotes {
+        /// Current height.
+        height: Height,
+        /// Round of the prevotes.
+        round: Round,
+        /// Hash of the proposal.
+        propose_hash: Hash,
+    },
+    /// The node has locked on the proposal.
+    LockAcquired {
+        /// Current height.
+        height: Height,
+        /// Round of the lock.
+        round: Round,
+        /// Hash of the proposal.
+        propose_hash: Hash,
+    },
+    /// The node has committed a block.
+    BlockCommitted {
+        /// Height of the committed block.
+        height: Height,
+        /// Round in which the block has been accepted, if it is known.
+        round: Option<Round>,
+        /// Hash of the committed block.
+        block_hash: Hash,
+        /// Number of transactions in the block.
+        tx_count: usize,
+    },
+    /// A timeout of the node has fired.
+    TimeoutFired {
+        /// Current height.
+        height: Height,
+        /// Current round.
+        round: Round,
+        /// Kind of the timeout, e.g., `round` or `propose`.
+        kind: String,
+    },
+    /// The node has received a consensus message (`Propose`, `Prevote`, `Precommit`
+    /// or `Status`) from a peer. Together with the fired timeouts, received messages allow
+    /// to replay the behavior of the node. Transactions, requests and responses are not
+    /// recorded.
+    MessageReceived {
+        /// Received message.
+        message: SignedMessage,
+    },
+}
+
+/// Consensus event together with the node time at which it has been emitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Node time at which the event has been emitted.
+    pub time: DateTime<Utc>,
+    /// Emitted event.
+    #[serde(flatten)]
+    pub event: ConsensusEvent,
+}
+
+#[derive(Debug, Default)]
+struct EventBusInner {
+    subscribers: Vec<mpsc::Sender<RecordedEvent>>,
+    log: Option<LogWriter>,
+}
+
+/// Handle to the thread writing events to the log file.
+#[derive(Debug)]
+struct LogWriter {
+    events: std_mpsc::SyncSender<RecordedEvent>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl LogWriter {
+    fn new(file: File) -> io::Result<Self> {
+        let (events, receiver) = std_mpsc::sync_channel(LOG_QUEUE_SIZE);
+        let thread = thread::Builder::new()
+            .name("consensus-events-log".to_owned())
+            .spawn(move || write_log(file, &receiver))?;
+        Ok(Self { events, thread })
+    }
+
+    /// Waits until all the queued events are written to the file.
+    fn stop(self) {
+        drop(self.events);
+        if self.thread.join().is_err() {
+            error!("Consensus events log writer has panicked");
+        }
+    }
+}
+
+fn write_log(file: File, events: &std_mpsc::Receiver<RecordedEvent>) {
+    let mut writer = BufWriter::new(file);
+    while let Ok(event) = events.recv() {
+        if let Err(e) = write_queued_events(&mut writer, &event, events) {
+            error!("Cannot record consensus event: {}", e);
+            return;
+        }
+    }
+}
+
+/// Writes the event and all the events queued after it, then flushes the file.
+fn write_queued_events(
+    writer: &mut impl Write,
+    first: &RecordedEvent,
+    events: &std_mpsc::Receiver<RecordedEvent>,
+) -> io::Result<()> {
+    write_event(writer, first)?;
+    for event in events.try_iter() {
+        write_event(writer, &event)?;
+    }
+    writer.flush()
+}
+
+fn write_event(writer: &mut impl Write, event: &RecordedEvent) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, event)?;
+    writer.write_all(b"\n")
+}
+
+/// Bus delivering consensus events to subscribers and to the log file.
+#[derive(Debug, Default)]
+pub struct ConsensusEventBus {
+    inner: Mutex<EventBusInner>,
+}
+
+impl ConsensusEventBus {
+    /// Creates a new bus without subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes to the events emitted after this call. The subscription is cancelled
+    /// when the returned receiver is dropped. If the receiver lags behind by more than
+    /// `SUBSCRIBER_QUEUE_SIZE` events, the subscription is cancelled by the bus,
+    /// and the receiver stream ends.
+    pub fn subscribe(&self) -> mpsc::Receiver<RecordedEvent> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_QUEUE_SIZE);
+        self.inner.lock().unwrap().subscribers.push(sender);
+        receiver
+    }
+
+    /// Starts recording events to the specified file. Events are appended to the file
+    /// if it already exists.
+    pub fn record_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let log = LogWriter::new(file)?;
+        let previous_log = self.inner.lock().unwrap().log.replace(log);
+        if let Some(previous_log) = previous_log {
+            previous_log.stop();
+        }
+        Ok(())
+    }
+
+    /// Stops recording events to the file, waiting until all the emitted events
+    /// are written.
+    pub fn stop_recording(&self) {
+        let log = self.inner.lock().unwrap().log.take();
+        if let Some(log) = log {
+            log.stop();
+        }
+    }
+
+    /// Reads events recorded by the `record_to_file` method.
+    pub fn read_log(path: impl AsRef<Path>) -> Result<Vec<RecordedEvent>, failure::Error> {
+        let reader = BufReader::new(File::open(path)?);
+        reader
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect()
+    }
+
+    /// Checks whether the bus has subscribers or records events to a file. Events
+    /// which are expensive to build should only be emitted if this method returns `true`.
+    pub(crate) fn is_listened(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        !inner.subscribers.is_empty() || inner.log.is_some()
+    }
+
+    /// Emits the event to all subscribers. This method does not block.
+    pub(crate) fn emit(&self, time: DateTime<Utc>, event: ConsensusEvent) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.subscribers.is_empty() && inner.log.is_none() {
+            return;
+        }
+
+        let event = RecordedEvent { time, event };
+        let subscribers = mem::replace(&mut inner.subscribers, Vec::new());
+        inner.subscribers = subscribers
+            .into_iter()
+            .filter_map(|mut subscriber| match subscriber.try_send(event.clone()) {
+                Ok(()) => Some(subscriber),
+                Err(ref e) if e.is_full() => {
+                    warn!("Dropping consensus events subscriber lagging behind");
+                    None
+                }
+                Err(_) => None,
+            })
+            .collect();
+
+        if let Some(log) = inner.log.as_ref() {
+            match log.events.try_send(event) {
+                Ok(()) => {}
+                Err(std_mpsc::TrySendError::Full(_)) => {
+                    warn!("Consensus events log is lagging behind, the event is not recorded");
+                }
+                Err(std_mpsc::TrySendError::Disconnected(_)) => {
+                    inner.log = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{Future, Stream};
+
+    use super::*;
+    use crate::crypto::hash;
+
+    #[test]
+    fn subscribers_receive_events() {
+        let bus = ConsensusEventBus::new();
+        let events = bus.subscribe();
+        let event = ConsensusEvent::LockAcquired {
+            height: Height(1),
+            round: Round(2),
+            propose_hash: hash(&[1]),
+        };
+        let time = Utc::now();
+        bus.emit(time, event.clone());
+        drop(bus);
+
+        let received = events.collect().wait().unwrap();
+        assert_eq!(received, vec![RecordedEvent { time, event }]);
+    }
+
+    #[test]
+    fn bus_is_listened_with_subscribers() {
+        let bus = ConsensusEventBus::new();
+        assert!(!bus.is_listened());
+        let events = bus.subscribe();
+        assert!(bus.is_listened());
+        drop(events);
+        bus.emit(
+            Utc::now(),
+            ConsensusEvent::RoundStarted {
+                height: Height(1),
+                round: Round(1),
+            },
+        );
+        assert!(!bus.is_listened());
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed() {
+        let bus = ConsensusEventBus::new();
+        drop(bus.subscribe());
+        bus.emit(
+            Utc::now(),
+            ConsensusEvent::RoundStarted {
+                height: Height(1),
+                round: Round(1),
+            },
+        );
+        assert!(bus.inner.lock().unwrap().subscribers.is_empty());
+    }
+
+    #[test]
+    fn lagging_subscribers_are_dropped() {
+        let bus = ConsensusEventBus::new();
+        let events = bus.subscribe();
+        let total = SUBSCRIBER_QUEUE_SIZE + 2;
+        for round in 0..total {
+            bus.emit(
+                Utc::now(),
+                ConsensusEvent::RoundStarted {
+                    height: Height(1),
+                    round: Round(round as u32),
+                },
+            );
+        }
+        assert!(bus.inner.lock().unwrap().subscribers.is_empty());
+
+        // The subscriber receives the queued events, after which the stream ends.
+        let received = events.collect().wait().unwrap();
+        assert!(!received.is_empty() && received.len() < total);
+        assert_eq!(
+            received[0].event,
+            ConsensusEvent::RoundStarted {
+                height: Height(1),
+                round: Round(0),
+            }
+        );
+    }
+
+    #[test]
+    fn events_are_recorded_to_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let bus = ConsensusEventBus::new();
+        bus.record_to_file(file.path()).unwrap();
+
+        let events = vec![
+            ConsensusEvent::TimeoutFired {
+                height: Height(1),
+                round: Round(1),
+                kind: "round".to_owned(),
+            },
+            ConsensusEvent::BlockCommitted {
+                height: Height(1),
+                round: None,
+                block_hash: hash(&[2]),
+                tx_count: 3,
+            },
+        ];
+        let time = Utc::now();
+        for event in &events {
+            bus.emit(time, event.clone());
+        }
+
+        bus.stop_recording();
+        let recorded = ConsensusEventBus::read_log(file.path()).unwrap();
+        let expected = events
+            .into_iter()
+            .map(|event| RecordedEvent { time, event })
+            .collect::<Vec<_>>();
+        assert_eq!(recorded, expected);
+    }
+}
@@ -12,11 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{ConnectListConfig, ExternalMessage, NodeHandler, NodeTimeout};
+use super::{ConnectListConfig, ConsensusEvent, ExternalMessage, NodeHandler, NodeTimeout};
 
 use crate::{
     blockchain::Schema,
     events::{error::LogError, Event, EventHandler, InternalEvent, InternalRequest, NetworkEvent},
+    messages::{Message, Service},
 };
 
 impl EventHandler for NodeHandler {
@@ -35,7 +36,14 @@ impl NodeHandler {
             InternalEvent::Timeout(timeout) => self.handle_timeout(timeout),
             InternalEvent::JumpToRound(height, round) => self.handle_new_round(height, round),
             InternalEvent::Shutdown => panic!("Shutdown should be processed in the event loop"),
-            InternalEvent::MessageVerified(msg) => self.handle_message(*msg),
+            InternalEvent::MessageVerified(msg) => {
+                if is_consensus_message(&msg) && self.api_state.consensus_events().is_listened() {
+                    self.emit_event(ConsensusEvent::MessageReceived {
+                        message: msg.as_raw().clone(),
+                    });
+                }
+                self.handle_message(*msg)
+            }
             InternalEvent::RestartApi => unreachable!(),
         }
     }
@@ -56,6 +64,11 @@ impl NodeHandler {
             ExternalMessage::Transaction(tx) => {
                 self.handle_incoming_tx(tx);
             }
+            ExternalMessage::Transactions(txs) => {
+                for tx in txs {
+                    self.handle_incoming_tx(tx);
+                }
+            }
             ExternalMessage::PeerAdd(info) => {
                 info!("Send Connect message to {}", info);
                 self.state.add_peer_to_connect_list(info.clone());
@@ -97,6 +110,13 @@ impl NodeHandler {
             );
             return;
         }
+        let kind = timeout.kind();
+        self.metrics().timeouts.inc(kind);
+        self.emit_event(ConsensusEvent::TimeoutFired {
+            height: self.state.height(),
+            round: self.state.round(),
+            kind: kind.to_owned(),
+        });
         match timeout {
             NodeTimeout::Round(height, round) => self.handle_round_timeout(height, round),
             NodeTimeout::Request(data, peer) => self.handle_request_timeout(&data, peer),
@@ -148,3 +168,11 @@ impl NodeHandler {
         }
     }
 }
+
+/// Checks whether the message is recorded as the `MessageReceived` consensus event.
+fn is_consensus_message(msg: &Message) -> bool {
+    match msg {
+        Message::Consensus(_) | Message::Service(Service::Status(_)) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics of the node.
+//!
+//! Metrics are collected by the node during the consensus process and can be exported
+//! in the [Prometheus text format] via the `v1/metrics` endpoint of the private API.
+//!
+//! [Prometheus text format]: https://prometheus.io/docs/instrumenting/exposition_formats/
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Buckets for the consensus latencies, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+/// Buckets for the number of rounds per height.
+const ROUND_BUCKETS: &[f64] = &[1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0];
+/// Buckets for the storage operations, in seconds.
+const STORAGE_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Value which can arbitrarily go up and down.
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    /// Sets the value of the gauge.
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of the gauge.
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Family of counters distinguished by the value of the `kind` label.
+#[derive(Debug, Default)]
+pub struct CounterVec(Mutex<BTreeMap<&'static str, u64>>);
+
+impl CounterVec {
+    /// Increments the counter with the specified label value by one.
+    pub fn inc(&self, kind: &'static str) {
+        *self.0.lock().unwrap().entry(kind).or_insert(0) += 1;
+    }
+
+    /// Returns the current value of the counter with the specified label value.
+    pub fn get(&self, kind: &str) -> u64 {
+        self.0.lock().unwrap().get(kind).cloned().unwrap_or(0)
+    }
+}
+
+#[derive(Debug)]
+struct HistogramState {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// Histogram of the observed values.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    state: Mutex<HistogramState>,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            state: Mutex::new(HistogramState {
+                buckets: vec![0; bounds.len()],
+                sum: 0.0,
+                count: 0,
+            }),
+        }
+    }
+
+    /// Records the observed value.
+    pub fn observe(&self, value: f64) {
+        let mut state = self.state.lock().unwrap();
+        for (bucket, bound) in state.buckets.iter_mut().zip(self.bounds) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        state.sum += value;
+        state.count += 1;
+    }
+
+    /// Records the observed duration in seconds.
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    /// Returns the number of observed values.
+    pub fn count(&self) -> u64 {
+        self.state.lock().unwrap().count
+    }
+
+    /// Returns the sum of observed values.
+    pub fn sum(&self) -> f64 {
+        self.state.lock().unwrap().sum
+    }
+}
+
+/// Registry of the node metrics.
+///
+/// The registry is shared between the node and its API via `SharedNodeState`.
+#[derive(Debug)]
+pub struct Metrics {
+    /// Current height of the consensus.
+    pub height: Gauge,
+    /// Current round of the consensus.
+    pub round: Gauge,
+    /// Number of rounds it has taken to commit a block.
+    pub rounds_per_height: Histogram,
+    /// Time from the start of the height to receiving or creating a proposal.
+    ///
+    /// The latency is recorded for the proposal of each round rather than only for the first
+    /// one, so a height with several rounds contributes several samples with increasing values.
+    pub propose_latency: Histogram,
+    /// Time from the start of the height to collecting the majority of prevotes.
+    ///
+    /// The latency is recorded when the majority of prevotes for a proposal is first collected,
+    /// regardless of whether the node locks on the proposal.
+    pub prevote_latency: Histogram,
+    /// Time from the start of the height to collecting the majority of precommits.
+    pub precommit_latency: Histogram,
+    /// Fired timeouts by the `NodeTimeout` variant.
+    pub timeouts: CounterVec,
+    /// Requests sent to other nodes by the request type.
+    pub requests_sent: CounterVec,
+    /// Requests received from other nodes by the request type.
+    pub requests_received: CounterVec,
+    /// Duration of merging the block changes into the database.
+    pub db_merge_duration: Histogram,
+    /// Number of uncommitted transactions known to the node.
+    pub mempool_size: Gauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Creates a new registry with all metrics set to zero.
+    pub fn new() -> Self {
+        Self {
+            height: Gauge::default(),
+            round: Gauge::default(),
+            rounds_per_height: Histogram::new(ROUND_BUCKETS),
+            propose_latency: Histogram::new(LATENCY_BUCKETS),
+            prevote_latency: Histogram::new(LATENCY_BUCKETS),
+            precommit_latency: Histogram::new(LATENCY_BUCKETS),
+            timeouts: CounterVec::default(),
+            requests_sent: CounterVec::default(),
+            requests_received: CounterVec::default(),
+            db_merge_duration: Histogram::new(STORAGE_BUCKETS),
+            mempool_size: Gauge::default(),
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text format.
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+        self.write_prometheus(&mut output)
+            .expect("Writing into a string never fails");
+        output
+    }
+
+    fn write_prometheus(&self, out: &mut String) -> fmt::Result {
+        write_gauge(
+            out,
+            "exonum_consensus_height",
+            "Current height of the consensus.",
+            &self.height,
+        )?;
+        write_gauge(
+            out,
+            "exonum_consensus_round",
+            "Current round of the consensus.",
+            &self.round,
+        )?;
+        write_histogram(
+            out,
+            "exonum_consensus_rounds_per_height",
+            "Number of rounds it has taken to commit a block.",
+            &self.rounds_per_height,
+        )?;
+        write_histogram(
+            out,
+            "exonum_consensus_propose_latency_seconds",
+            "Time from the start of the height to receiving a proposal in each round.",
+            &self.propose_latency,
+        )?;
+        write_histogram(
+            out,
+            "exonum_consensus_prevote_latency_seconds",
+            "Time from the start of the height to collecting the majority of prevotes.",
+            &self.prevote_latency,
+        )?;
+        write_histogram(
+            out,
+            "exonum_consensus_precommit_latency_seconds",
+            "Time from the start of the height to collecting the majority of precommits.",
+            &self.precommit_latency,
+        )?;
+        write_counter_vec(
+            out,
+            "exonum_node_timeouts_total",
+            "Number of fired timeouts.",
+            &self.timeouts,
+        )?;
+        write_counter_vec(
+            out,
+            "exonum_node_requests_sent_total",
+            "Number of requests sent to other nodes.",
+            &self.requests_sent,
+        )?;
+        write_counter_vec(
+            out,
+            "exonum_node_requests_received_total",
+            "Number of requests received from other nodes.",
+            &self.requests_received,
+        )?;
+        write_histogram(
+            out,
+            "exonum_storage_merge_duration_seconds",
+            "Duration of merging the block changes into the database.",
+            &self.db_merge_duration,
+        )?;
+        write_gauge(
+            out,
+            "exonum_mempool_size",
+            "Number of uncommitted transactions known to the node.",
+            &self.mempool_size,
+        )
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) -> fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} {}", name, kind)
+}
+
+fn write_gauge(out: &mut String, name: &str, help: &str, gauge: &Gauge) -> fmt::Result {
+    write_header(out, name, help, "gauge")?;
+    writeln!(out, "{} {}", name, gauge.get())
+}
+
+fn write_counter_vec(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    counters: &CounterVec,
+) -> fmt::Result {
+    write_header(out, name, help, "counter")?;
+    for (kind, value) in counters.0.lock().unwrap().iter() {
+        writeln!(out, "{}{{kind=\"{}\"}} {}", name, kind, value)?;
+    }
+    Ok(())
+}
+
+fn write_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) -> fmt::Result {
+    write_header(out, name, help, "histogram")?;
+    let state = histogram.state.lock().unwrap();
+    for (bound, count) in histogram.bounds.iter().zip(&state.buckets) {
+        writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count)?;
+    }
+    writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, state.count)?;
+    writeln!(out, "{}_sum {}", name, state.sum)?;
+    writeln!(out, "{}_count {}", name, state.count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new(&[1.0, 2.0, 5.0]);
+        histogram.observe(0.5);
+        histogram.observe(2.0);
+        histogram.observe(10.0);
+
+        let mut output = String::new();
+        write_histogram(&mut output, "test", "Test histogram.", &histogram).unwrap();
+        let expected = "# HELP test Test histogram.\n\
+                        # TYPE test histogram\n\
+                        test_bucket{le=\"1\"} 1\n\
+                        test_bucket{le=\"2\"} 2\n\
+                        test_bucket{le=\"5\"} 2\n\
+                        test_bucket{le=\"+Inf\"} 3\n\
+                        test_sum 12.5\n\
+                        test_count 3\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn metrics_in_prometheus_format() {
+        let metrics = Metrics::new();
+        metrics.height.set(5);
+        metrics.timeouts.inc("round");
+        metrics.timeouts.inc("round");
+        metrics.timeouts.inc("propose");
+
+        let output = metrics.to_prometheus();
+        assert!(
+            output.contains("# TYPE exonum_consensus_height gauge\nexonum_consensus_height 5\n")
+        );
+        assert!(output.contains("exonum_node_timeouts_total{kind=\"propose\"} 1\n"));
+        assert!(output.contains("exonum_node_timeouts_total{kind=\"round\"} 2\n"));
+        assert!(output.contains("exonum_consensus_rounds_per_height_count 0\n"));
+    }
+}
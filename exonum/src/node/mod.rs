@@ -19,9 +19,13 @@
 
 pub use self::{
     connect_list::{ConnectList, PeerAddress},
+    event_bus::{ConsensusEvent, ConsensusEventBus, RecordedEvent},
+    metrics::Metrics,
     state::{RequestData, State, ValidatorState},
 };
 
+pub mod event_bus;
+pub mod metrics;
 // TODO: Temporary solution to get access to WAIT constants. (ECR-167)
 pub mod state;
 
@@ -47,7 +51,8 @@ use std::{
 use crate::{
     api::{
         backends::actix::{
-            AllowOrigin, ApiRuntimeConfig, App, AppConfig, Cors, SystemRuntimeConfig,
+            AllowOrigin, ApiAuth, ApiRuntimeConfig, App, AppConfig, Cors, SystemRuntimeConfig,
+            TlsConfig,
         },
         manager::UpdateEndpoints,
         node::SharedNodeState,
@@ -55,7 +60,7 @@ use crate::{
     },
     blockchain::{
         Blockchain, BlockchainBuilder, BlockchainMut, ConsensusConfig, InstanceCollection, Schema,
-        ValidatorKeys,
+        TransactionIndexes, ValidatorKeys,
     },
     crypto::{self, Hash, PublicKey, SecretKey},
     events::{
@@ -86,6 +91,8 @@ pub enum ExternalMessage {
     PeerAdd(ConnectInfo),
     /// Transaction that implements the `Transaction` trait.
     Transaction(Verified<AnyTx>),
+    /// Batch of transactions submitted together.
+    Transactions(Vec<Verified<AnyTx>>),
     /// Enable or disable the node.
     Enable(bool),
     /// Shutdown the node.
@@ -109,6 +116,20 @@ pub enum NodeTimeout {
     PeerExchange,
 }
 
+impl NodeTimeout {
+    /// Returns the kind of the timeout used in metrics and consensus events.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            NodeTimeout::Status(..) => "status",
+            NodeTimeout::Round(..) => "round",
+            NodeTimeout::Request(..) => "request",
+            NodeTimeout::Propose(..) => "propose",
+            NodeTimeout::UpdateApiState => "update_api_state",
+            NodeTimeout::PeerExchange => "peer_exchange",
+        }
+    }
+}
+
 /// A helper trait that provides the node with information about the state of the system such
 /// as current time or listen address.
 pub trait SystemStateProvider: std::fmt::Debug + Send + 'static {
@@ -183,6 +204,18 @@ pub struct NodeApiConfig {
     ///
     /// [cors]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS
     pub private_allow_origin: Option<AllowOrigin>,
+    /// Authentication of requests to private API handlers. If not set, private API
+    /// is accessible to anyone who can connect to `private_api_address`.
+    #[serde(default)]
+    pub private_api_auth: Option<ApiAuth>,
+    /// TLS settings of the public API server. If not set, public API is served
+    /// over plain HTTP.
+    #[serde(default)]
+    pub public_api_tls: Option<TlsConfig>,
+    /// TLS settings of the private API server. If not set, private API is served
+    /// over plain HTTP. The settings may require clients to present a certificate.
+    #[serde(default)]
+    pub private_api_tls: Option<TlsConfig>,
 }
 
 impl Default for NodeApiConfig {
@@ -193,6 +226,9 @@ impl Default for NodeApiConfig {
             private_api_address: None,
             public_allow_origin: None,
             private_allow_origin: None,
+            private_api_auth: None,
+            public_api_tls: None,
+            private_api_tls: None,
         }
     }
 }
@@ -262,6 +298,12 @@ pub struct NodeConfig {
     pub connect_list: ConnectListConfig,
     /// Transaction Verification Thread Pool size.
     pub thread_pool_size: Option<u8>,
+    /// Path to the file to record consensus events to, if any.
+    #[serde(default)]
+    pub consensus_events_log: Option<PathBuf>,
+    /// Optional transaction indexes maintained by the node.
+    #[serde(default)]
+    pub transaction_indexes: TransactionIndexes,
     /// Path to the master key file.
     pub master_key_path: PathBuf,
     /// Validator keys.
@@ -297,6 +339,8 @@ impl NodeConfig {
             database: self.database,
             connect_list: self.connect_list,
             thread_pool_size: self.thread_pool_size,
+            consensus_events_log: self.consensus_events_log,
+            transaction_indexes: self.transaction_indexes,
             master_key_path: self.master_key_path,
             keys,
         }
@@ -546,6 +590,25 @@ impl NodeHandler {
         &self.api_state
     }
 
+    /// Returns metrics collected by the node.
+    pub fn metrics(&self) -> &Metrics {
+        self.api_state.metrics()
+    }
+
+    /// Emits the consensus event to the subscribers of the node event bus.
+    pub(crate) fn emit_event(&self, event: ConsensusEvent) {
+        let time = self.system_state.current_time().into();
+        self.api_state.consensus_events().emit(time, event);
+    }
+
+    /// Returns the time elapsed since the start of the current height.
+    fn height_elapsed(&self) -> Duration {
+        self.system_state
+            .current_time()
+            .duration_since(self.state.height_start_time())
+            .unwrap_or_default()
+    }
+
     /// Returns value of the `first_round_timeout` field from the current `ConsensusConfig`.
     pub fn first_round_timeout(&self) -> Milliseconds {
         self.state().consensus_config().first_round_timeout
@@ -588,6 +651,11 @@ impl NodeHandler {
         self.state().consensus_config().propose_timeout_threshold
     }
 
+    /// Returns the maximal difference between the time of a proposal and the local time.
+    pub fn max_block_time_drift(&self) -> Milliseconds {
+        self.state().consensus_config().max_block_time_drift
+    }
+
     /// Returns `State` of the node.
     pub fn state(&self) -> &State {
         &self.state
@@ -954,6 +1022,7 @@ impl Node {
         let blockchain = BlockchainBuilder::new(blockchain, node_cfg.consensus.clone())
             .with_rust_runtime(channel.endpoints.0.clone(), services)
             .with_external_runtimes(external_runtimes)
+            .with_transaction_indexes(node_cfg.transaction_indexes)
             .build()
             .expect("Cannot create dispatcher");
         Self::with_blockchain(blockchain, channel, node_cfg, config_file_path)
@@ -985,6 +1054,12 @@ impl Node {
         };
 
         let api_state = SharedNodeState::new(node_cfg.api.state_update_timeout as u64);
+        if let Some(ref path) = node_cfg.consensus_events_log {
+            api_state
+                .consensus_events()
+                .record_to_file(path)
+                .expect("Could not open consensus events log file");
+        }
         let system_state = Box::new(DefaultSystemState(node_cfg.listen_address));
         let network_config = config.network;
 
@@ -1005,6 +1080,8 @@ impl Node {
                         listen_address,
                         access: ApiAccess::Public,
                         app_config: api_cfg.public_allow_origin.clone().map(into_app_config),
+                        auth: None,
+                        tls: api_cfg.public_api_tls.clone(),
                     })
                     .into_iter();
                 let private_api_handler = api_cfg
@@ -1013,6 +1090,8 @@ impl Node {
                         listen_address,
                         access: ApiAccess::Private,
                         app_config: api_cfg.private_allow_origin.clone().map(into_app_config),
+                        auth: api_cfg.private_api_auth.clone(),
+                        tls: api_cfg.private_api_tls.clone(),
                     })
                     .into_iter();
                 // Collects API handlers.
@@ -46,6 +46,16 @@ impl NodeHandler {
             return;
         }
 
+        let kind = match msg {
+            Requests::ProposeRequest(..) => "propose",
+            Requests::TransactionsRequest(..) => "transactions",
+            Requests::PrevotesRequest(..) => "prevotes",
+            Requests::PeersRequest(..) => "peers",
+            Requests::BlockRequest(..) => "block",
+            Requests::PoolTransactionsRequest(..) => "pool_transactions",
+        };
+        self.metrics().requests_received.inc(kind);
+
         match msg {
             Requests::ProposeRequest(ref msg) => self.handle_request_propose(msg),
             Requests::TransactionsRequest(ref msg) => self.handle_request_txs(msg),
@@ -24,7 +24,7 @@ use std::{
 };
 
 use crate::{
-    blockchain::{contains_transaction, ConsensusConfig, ValidatorKeys},
+    blockchain::{contains_transaction, ConsensusConfig, Equivocation, ValidatorKeys},
     crypto::{Hash, PublicKey, SecretKey},
     events::network::ConnectedPeerAddr,
     helpers::{byzantine_quorum, Height, Milliseconds, Round, ValidatorId},
@@ -92,6 +92,8 @@ pub struct State {
 
     // Cache that stores transactions before adding to persistent pool.
     tx_cache: BTreeMap<Hash, Verified<AnyTx>>,
+    // Detected equivocations which are not yet saved to the storage.
+    equivocations: Vec<Equivocation>,
     keys: Keys,
 }
 
@@ -252,6 +254,18 @@ where
     pub fn messages(&self) -> &Vec<T> {
         &self.messages
     }
+
+    /// Returns the message of the specified validator, if any.
+    pub fn message_of(&self, validator: ValidatorId) -> Option<&T> {
+        let voter: usize = validator.into();
+        if self.validators.get(voter).unwrap_or(false) {
+            self.messages
+                .iter()
+                .find(|msg| msg.validator() == validator)
+        } else {
+            None
+        }
+    }
 }
 
 impl RequestData {
@@ -481,6 +495,7 @@ impl State {
             incomplete_block: None,
 
             tx_cache: BTreeMap::new(),
+            equivocations: Vec::new(),
 
             keys,
         }
@@ -1038,6 +1053,17 @@ impl State {
             }
         }
 
+        let conflicting = self
+            .prevotes
+            .iter()
+            .filter(|((round, hash), _)| {
+                *round == msg.payload().round && *hash != msg.payload().propose_hash
+            })
+            .filter_map(|(_, votes)| votes.message_of(msg.validator()))
+            .filter_map(|other| Equivocation::from_prevotes(other.clone(), msg.clone()))
+            .collect::<Vec<_>>();
+        self.add_equivocations(conflicting);
+
         let key = (msg.payload().round, msg.payload().propose_hash);
         let validators_len = self.validators().len();
         let votes = self
@@ -1096,6 +1122,17 @@ impl State {
             }
         }
 
+        let conflicting = self
+            .precommits
+            .iter()
+            // Precommits for the same block may still conflict if they refer to different
+            // proposals, so the conflict is determined by `Equivocation::from_precommits`.
+            .filter(|((round, _), _)| *round == msg.payload().round)
+            .filter_map(|(_, votes)| votes.message_of(msg.validator()))
+            .filter_map(|other| Equivocation::from_precommits(other.clone(), msg.clone()))
+            .collect::<Vec<_>>();
+        self.add_equivocations(conflicting);
+
         let key = (msg.payload().round, msg.payload().block_hash);
         let validators_len = self.validators().len();
         let votes = self
@@ -1106,6 +1143,23 @@ impl State {
         votes.count() >= majority_count
     }
 
+    fn add_equivocations(&mut self, equivocations: Vec<Equivocation>) {
+        for equivocation in equivocations {
+            warn!(
+                "Validator {} has equivocated at height {}, round {}",
+                equivocation.validator(),
+                equivocation.height(),
+                equivocation.round()
+            );
+            self.equivocations.push(equivocation);
+        }
+    }
+
+    /// Takes equivocations detected since the previous call of this method.
+    pub fn take_equivocations(&mut self) -> Vec<Equivocation> {
+        std::mem::replace(&mut self.equivocations, Vec::new())
+    }
+
     /// Adds unknown (for this node) propose.
     pub fn add_unknown_propose_with_precommits(
         &mut self,
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use byteorder::{ByteOrder, LittleEndian};
+use chrono::{TimeZone, Utc};
 use exonum_crypto::{gen_keypair, Hash};
 use exonum_merkledb::{Database, Fork, ObjectHash, Snapshot, TemporaryDB};
 use futures::{future, sync::mpsc, Future, IntoFuture};
@@ -57,6 +58,7 @@ fn create_genesis_block(dispatcher: &mut Dispatcher, fork: &mut Fork) {
         Hash::zero(),
         Hash::zero(),
         Hash::zero(),
+        Utc.timestamp(0, 0),
     );
     let block_hash = block.object_hash();
     let schema = CoreSchema::new(&*fork);
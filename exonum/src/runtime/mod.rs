@@ -147,12 +147,13 @@ use futures::Future;
 
 use std::fmt;
 
+use chrono::{DateTime, Utc};
 use exonum_merkledb::{BinaryValue, Fork, Snapshot};
 
 use crate::{
-    blockchain::Blockchain,
+    blockchain::{Blockchain, Schema as CoreSchema, ServiceEvent},
     crypto::{Hash, PublicKey},
-    helpers::ValidateInput,
+    helpers::{Height, ValidateInput},
 };
 
 mod blockchain_data;
@@ -605,6 +606,56 @@ impl<'a> ExecutionContext<'a> {
             .map_err(From::from)
     }
 
+    /// Sets an additional header of the block being created. Headers set by runtimes
+    /// are committed into the block together with the headers set by the core; see
+    /// [`Block::additional_headers`].
+    ///
+    /// Headers can only be set within the `before_commit` hook, i.e., if the caller
+    /// is `Caller::Blockchain`. Keys starting with `core.` are reserved for the core.
+    ///
+    /// [`Block::additional_headers`]: ../blockchain/struct.Block.html#structfield.additional_headers
+    pub fn set_block_header(
+        &mut self,
+        key: impl Into<String>,
+        value: impl BinaryValue,
+    ) -> Result<(), ExecutionError> {
+        if self.caller != Caller::Blockchain {
+            return Err(DispatcherError::unauthorized_caller(
+                "Block headers can only be set within the `before_commit` hook",
+            ));
+        }
+        let key = key.into();
+        if key.starts_with("core.") {
+            return Err(DispatcherError::unauthorized_caller(format!(
+                "Block header key `{}` is reserved for the core",
+                key
+            )));
+        }
+
+        CoreSchema::new(&*self.fork)
+            .pending_block_headers()
+            .put(&key, value.into_bytes());
+        Ok(())
+    }
+
+    /// Appends the event to the list of events emitted by services in the block being created.
+    ///
+    /// Events emitted by a transaction are discarded if the transaction execution fails.
+    pub fn emit_event(&mut self, event: ServiceEvent) {
+        let schema = CoreSchema::new(&*self.fork);
+        let height = Height(schema.block_hashes_by_height().len());
+        schema.service_events(height).push(event);
+    }
+
+    /// Returns the time of the block being created, as proposed by the leader node.
+    /// Unlike the local time of the node, the block time is the same on all nodes
+    /// and thus can be used in transactions and `before_commit` hooks.
+    ///
+    /// Returns `None` if called outside of the block creation.
+    pub fn block_time(&self) -> Option<DateTime<Utc>> {
+        CoreSchema::new(&*self.fork).pending_block_time().get()
+    }
+
     fn reborrow(&mut self) -> ExecutionContext<'_> {
         self.reborrow_with_interface(self.interface_name)
     }
@@ -22,7 +22,7 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use super::Broadcaster;
 use crate::{
-    api::{ApiBuilder, ApiScope},
+    api::{ApiBuilder, ApiSchema, ApiScope, VersionedEndpoint},
     blockchain::{Blockchain, Schema as CoreSchema},
     runtime::{BlockchainData, InstanceDescriptor, InstanceId},
 };
@@ -104,25 +104,13 @@ impl ServiceApiScope {
     /// [Read more.](../../../api/struct.ApiScope.html#endpoint)
     pub fn endpoint<Q, I, F, R>(&mut self, name: &'static str, handler: F) -> &mut Self
     where
-        Q: DeserializeOwned + 'static,
-        I: Serialize + 'static,
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
         F: Fn(&ServiceApiState<'_>, Q) -> R + 'static + Clone + Send + Sync,
         R: IntoFuture<Item = I, Error = crate::api::Error> + 'static,
     {
-        let blockchain = self.blockchain.clone();
-        let descriptor = self.descriptor.clone();
-        self.inner
-            .endpoint(name, move |query: Q| -> crate::api::FutureResult<I> {
-                let state = ServiceApiState::from_api_context(
-                    &blockchain,
-                    InstanceDescriptor {
-                        id: descriptor.0,
-                        name: descriptor.1.as_ref(),
-                    },
-                );
-                let result = handler(&state, query);
-                Box::new(result.into_future())
-            });
+        let handler = self.wrap_handler(handler);
+        self.inner.endpoint(name, handler);
         self
     }
 
@@ -131,6 +119,62 @@ impl ServiceApiScope {
     /// In HTTP backends this type of endpoint corresponds to `POST` requests.
     /// [Read more.](../../../api/struct.ApiScope.html#endpoint_mut)
     pub fn endpoint_mut<Q, I, F, R>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
+        F: Fn(&ServiceApiState<'_>, Q) -> R + 'static + Clone + Send + Sync,
+        R: IntoFuture<Item = I, Error = crate::api::Error> + 'static,
+    {
+        let handler = self.wrap_handler(handler);
+        self.inner.endpoint_mut(name, handler);
+        self
+    }
+
+    /// Add a readonly endpoint handler with the explicit version to the service API scope.
+    ///
+    /// In HTTP backends this type of endpoint corresponds to `GET` requests.
+    /// [Read more.](../../../api/struct.ApiScope.html#versioned_endpoint)
+    pub fn versioned_endpoint<Q, I, F, R>(
+        &mut self,
+        endpoint: VersionedEndpoint,
+        handler: F,
+    ) -> &mut Self
+    where
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
+        F: Fn(&ServiceApiState<'_>, Q) -> R + 'static + Clone + Send + Sync,
+        R: IntoFuture<Item = I, Error = crate::api::Error> + 'static,
+    {
+        let handler = self.wrap_handler(handler);
+        self.inner.versioned_endpoint(endpoint, handler);
+        self
+    }
+
+    /// Add an endpoint handler with the explicit version to the service API scope.
+    ///
+    /// In HTTP backends this type of endpoint corresponds to `POST` requests.
+    /// [Read more.](../../../api/struct.ApiScope.html#versioned_endpoint_mut)
+    pub fn versioned_endpoint_mut<Q, I, F, R>(
+        &mut self,
+        endpoint: VersionedEndpoint,
+        handler: F,
+    ) -> &mut Self
+    where
+        Q: DeserializeOwned + ApiSchema + 'static,
+        I: Serialize + ApiSchema + 'static,
+        F: Fn(&ServiceApiState<'_>, Q) -> R + 'static + Clone + Send + Sync,
+        R: IntoFuture<Item = I, Error = crate::api::Error> + 'static,
+    {
+        let handler = self.wrap_handler(handler);
+        self.inner.versioned_endpoint_mut(endpoint, handler);
+        self
+    }
+
+    /// Converts the service handler into the handler of the underlying API scope.
+    fn wrap_handler<Q, I, F, R>(
+        &self,
+        handler: F,
+    ) -> impl Fn(Q) -> crate::api::FutureResult<I> + 'static + Clone + Send + Sync
     where
         Q: DeserializeOwned + 'static,
         I: Serialize + 'static,
@@ -139,19 +183,17 @@ impl ServiceApiScope {
     {
         let blockchain = self.blockchain.clone();
         let descriptor = self.descriptor.clone();
-        self.inner
-            .endpoint_mut(name, move |query: Q| -> crate::api::FutureResult<I> {
-                let state = ServiceApiState::from_api_context(
-                    &blockchain,
-                    InstanceDescriptor {
-                        id: descriptor.0,
-                        name: descriptor.1.as_ref(),
-                    },
-                );
-                let result = handler(&state, query);
-                Box::new(result.into_future())
-            });
-        self
+        move |query: Q| -> crate::api::FutureResult<I> {
+            let state = ServiceApiState::from_api_context(
+                &blockchain,
+                InstanceDescriptor {
+                    id: descriptor.0,
+                    name: descriptor.1.as_ref(),
+                },
+            );
+            let result = handler(&state, query);
+            Box::new(result.into_future())
+        }
     }
 
     /// Return a mutable reference to the underlying web backend.
@@ -172,6 +214,7 @@ impl ServiceApiScope {
 /// use exonum::{
 ///     blockchain::Schema,
 ///     crypto::{self, Hash},
+///     impl_api_schema,
 ///     node::ExternalMessage,
 ///     runtime::rust::api::{self, ServiceApiBuilder, ServiceApiState},
 /// };
@@ -194,6 +237,10 @@ impl ServiceApiScope {
 ///     pub hash: Hash,
 /// }
 ///
+/// // Request and response types provide their schemas for the API description.
+/// impl_api_schema!(MyQuery { block_height: u64 });
+/// impl_api_schema!(BlockInfo { hash: Hash });
+///
 /// // Create API handlers.
 /// impl MyApi {
 ///     /// Immutable handler which returns a hash of the block at the given height.
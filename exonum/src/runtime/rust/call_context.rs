@@ -1,8 +1,10 @@
+use chrono::{DateTime, Utc};
 use exonum_merkledb::{access::Prefixed, BinaryValue, Fork};
 
-use crate::blockchain::Schema as CoreSchema;
+use crate::blockchain::{Schema as CoreSchema, ServiceEvent};
 use crate::runtime::{
     dispatcher::{Dispatcher, Error as DispatcherError},
+    error::catch_panic,
     ArtifactId, BlockchainData, CallInfo, Caller, ExecutionContext, ExecutionError,
     InstanceDescriptor, InstanceId, InstanceQuery, InstanceSpec, MethodId, SUPERVISOR_INSTANCE_ID,
 };
@@ -28,6 +30,13 @@ impl<'a> CallContext<'a> {
         }
     }
 
+    fn reborrow(&mut self) -> CallContext<'_> {
+        CallContext {
+            inner: self.inner.reborrow(),
+            instance: self.instance,
+        }
+    }
+
     /// Provides access to blockchain data.
     pub fn data(&self) -> BlockchainData<'a, &Fork> {
         BlockchainData::new(self.inner.fork, self.instance)
@@ -48,6 +57,48 @@ impl<'a> CallContext<'a> {
         self.instance
     }
 
+    /// Returns the time of the block being created. The block time is proposed by
+    /// the leader node and agreed upon by the validators, so it can be used
+    /// in transactions and `before_commit` hooks.
+    ///
+    /// Returns `None` if called outside of the block creation.
+    pub fn block_time(&self) -> Option<DateTime<Utc>> {
+        self.inner.block_time()
+    }
+
+    /// Sets an additional header of the block being created. The key of the header
+    /// is prefixed with the name of the executing service instance and a dot,
+    /// e.g., `timestamping.count` for the `count` key set by the `timestamping` service.
+    /// The key itself must not contain dots, so that keys of different services
+    /// cannot collide.
+    ///
+    /// Headers can only be set within the `before_commit` hook.
+    pub fn set_block_header(
+        &mut self,
+        key: &str,
+        value: impl BinaryValue,
+    ) -> Result<(), ExecutionError> {
+        if key.contains('.') {
+            return Err(DispatcherError::malformed_arguments(format!(
+                "Block header key `{}` must not contain dots",
+                key
+            )));
+        }
+        let key = format!("{}.{}", self.instance.name, key);
+        self.inner.set_block_header(key, value)
+    }
+
+    /// Emits an event with the specified name and payload. Events are stored in the blockchain
+    /// together with the block being created and are delivered to websocket subscribers.
+    ///
+    /// The payload is stored in its binary form; clients should decode it according
+    /// to the service specification. Events emitted by a transaction are discarded
+    /// if the transaction execution fails.
+    pub fn emit_event(&mut self, name: impl Into<String>, payload: impl BinaryValue) {
+        let event = ServiceEvent::new(self.instance.id, name, payload);
+        self.inner.emit_event(event);
+    }
+
     #[doc(hidden)]
     pub fn call(
         &mut self,
@@ -66,6 +117,33 @@ impl<'a> CallContext<'a> {
         )
     }
 
+    /// Invokes the closure, isolating the changes it makes to the storage. If the closure
+    /// returns an error or panics, its changes are rolled back and the error is returned,
+    /// while the changes made by the executing service before the invocation are preserved.
+    ///
+    /// This allows a service to call other services within the `before_commit` hook
+    /// without the risk that a failing callee reverts the changes of the whole hook.
+    /// The isolation does not finalize any changes: if the hook itself fails afterwards,
+    /// all changes of the hook are reverted, including the ones made within the closure.
+    /// Changes can only be isolated within the `before_commit` hook.
+    pub fn isolate<T, F>(&mut self, f: F) -> Result<T, ExecutionError>
+    where
+        F: FnOnce(CallContext<'_>) -> Result<T, ExecutionError>,
+    {
+        if self.inner.caller != Caller::Blockchain {
+            return Err(DispatcherError::unauthorized_caller(
+                "Changes can only be isolated within the `before_commit` hook",
+            ));
+        }
+
+        let checkpoint = self.inner.fork.checkpoint();
+        let result = catch_panic(|| f(self.reborrow()));
+        if result.is_err() {
+            self.inner.fork.rollback_to(checkpoint);
+        }
+        result
+    }
+
     // TODO This method is hidden until it is fully tested in next releases. [ECR-3494]
     /// Creates a client to call interface methods of the specified service instance.
     #[doc(hidden)]
@@ -40,6 +40,15 @@ pub struct ProtoSourcesQuery {
     pub artifact: Option<String>,
 }
 
+crate::impl_api_schema!(ProtoSourceFile {
+    name: String,
+    content: String,
+});
+
+crate::impl_api_schema!(ProtoSourcesQuery {
+    artifact: Option<String>,
+});
+
 /// Artifact Protobuf specification for the Exonum clients.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ArtifactProtobufSpec {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::Utc;
 use exonum_crypto::{Hash, PublicKey, PUBLIC_KEY_LENGTH};
 use exonum_derive::exonum_interface;
 use exonum_merkledb::{access::AccessExt, BinaryValue, Fork, Snapshot};
@@ -46,8 +47,13 @@ const SERVICE_INSTANCE_NAME: &str = "test_service_name";
 
 fn create_block(blockchain: &BlockchainMut) -> Fork {
     let height = CoreSchema::new(&blockchain.snapshot()).height();
-    let (_, patch) =
-        blockchain.create_patch(ValidatorId(0), height.next(), &[], &mut BTreeMap::new());
+    let (_, patch) = blockchain.create_patch(
+        ValidatorId(0),
+        height.next(),
+        Utc::now(),
+        &[],
+        &mut BTreeMap::new(),
+    );
     Fork::from(patch)
 }
 
@@ -26,6 +26,7 @@ use crate::{
     crypto::{gen_keypair_from_seed, Hash, Seed, HASH_SIZE, SEED_LENGTH},
     helpers::{Height, Round, ValidatorId},
     messages::{Precommit, Verified},
+    node::{ConsensusEvent, ConsensusEventBus},
     sandbox::{
         self,
         sandbox_tests_helper::*,
@@ -102,6 +103,161 @@ fn test_one_validator() {
     sandbox.assert_state(Height(2), Round(1));
 }
 
+/// The idea of the test is to check that consensus metrics are updated when the block is committed.
+#[test]
+fn test_metrics_after_one_height() {
+    let sandbox = timestamping_sandbox();
+    let sandbox_state = SandboxState::new();
+
+    add_one_height(&sandbox, &sandbox_state);
+
+    let handler = sandbox.node_handler_mut();
+    let metrics = handler.metrics();
+    assert_eq!(metrics.height.get(), 2);
+    assert_eq!(metrics.round.get(), 1);
+    assert_eq!(metrics.rounds_per_height.count(), 1);
+    assert!(metrics.propose_latency.count() >= 1);
+    assert_eq!(metrics.prevote_latency.count(), 1);
+    assert_eq!(metrics.precommit_latency.count(), 1);
+    assert_eq!(metrics.db_merge_duration.count(), 1);
+    assert_eq!(metrics.mempool_size.get(), 0);
+    assert!(metrics
+        .to_prometheus()
+        .contains("exonum_consensus_height 2\n"));
+}
+
+/// The idea of the test is to check that the prevote latency is recorded once the majority
+/// of prevotes is collected, even if the node cannot lock on the unknown proposal.
+#[test]
+fn test_prevote_latency_without_lock() {
+    let sandbox = timestamping_sandbox();
+
+    for validator in 1..4 {
+        let validator = ValidatorId(validator);
+        sandbox.recv(&sandbox.create_prevote(
+            validator,
+            Height(1),
+            Round(1),
+            empty_hash(),
+            NOT_LOCKED,
+            sandbox.secret_key(validator),
+        ));
+    }
+
+    let handler = sandbox.node_handler_mut();
+    assert_eq!(handler.state().locked_round(), NOT_LOCKED);
+    assert_eq!(handler.metrics().prevote_latency.count(), 1);
+}
+
+/// The idea of the test is to check that consensus events recorded by the node
+/// reflect the committed block.
+#[test]
+fn test_consensus_events_after_one_height() {
+    let sandbox = timestamping_sandbox();
+    let sandbox_state = SandboxState::new();
+    let log = tempfile::NamedTempFile::new().unwrap();
+    sandbox
+        .node_handler_mut()
+        .api_state()
+        .consensus_events()
+        .record_to_file(log.path())
+        .unwrap();
+
+    add_one_height(&sandbox, &sandbox_state);
+    sandbox
+        .node_handler_mut()
+        .api_state()
+        .consensus_events()
+        .stop_recording();
+
+    let events = ConsensusEventBus::read_log(log.path())
+        .unwrap()
+        .into_iter()
+        .map(|recorded| recorded.event)
+        .collect::<Vec<_>>();
+    let propose_hash = *sandbox_state.accepted_propose_hash.borrow();
+    let lock_round = events
+        .iter()
+        .find_map(|event| match event {
+            ConsensusEvent::LockAcquired {
+                height: Height(1),
+                round,
+                propose_hash: hash,
+            } if *hash == propose_hash => Some(*round),
+            _ => None,
+        })
+        .unwrap();
+    // Block is committed in the same round the node has locked on.
+    let commit_position = events
+        .iter()
+        .position(|event| match event {
+            ConsensusEvent::BlockCommitted { .. } => true,
+            _ => false,
+        })
+        .unwrap();
+    assert_eq!(
+        events[commit_position..],
+        [
+            ConsensusEvent::BlockCommitted {
+                height: Height(1),
+                round: Some(lock_round),
+                block_hash: *sandbox_state.accepted_block_hash.borrow(),
+                tx_count: 1,
+            },
+            ConsensusEvent::RoundStarted {
+                height: Height(2),
+                round: Round(1),
+            },
+        ]
+    );
+}
+
+/// The idea of the test is to check that replaying the recorded consensus events
+/// against a new sandbox reproduces the behavior of the node.
+#[test]
+fn test_replay_consensus_events() {
+    let record = |sandbox: &TimestampingSandbox, path: &std::path::Path| {
+        sandbox
+            .node_handler_mut()
+            .api_state()
+            .consensus_events()
+            .record_to_file(path)
+            .unwrap();
+    };
+    let stop_recording = |sandbox: &TimestampingSandbox| {
+        sandbox
+            .node_handler_mut()
+            .api_state()
+            .consensus_events()
+            .stop_recording();
+    };
+
+    let sandbox = timestamping_sandbox();
+    let sandbox_state = SandboxState::new();
+    let log = tempfile::NamedTempFile::new().unwrap();
+    record(&sandbox, log.path());
+    // Transactions are not recorded, so the replayed blocks are empty.
+    add_one_height_with_transactions(&sandbox, &sandbox_state, &[]);
+    add_one_height_with_transactions(&sandbox, &sandbox_state, &[]);
+    stop_recording(&sandbox);
+    let recorded = ConsensusEventBus::read_log(log.path()).unwrap();
+    assert!(recorded.iter().any(|recorded| match recorded.event {
+        ConsensusEvent::MessageReceived { .. } => true,
+        _ => false,
+    }));
+
+    let replay_sandbox = timestamping_sandbox();
+    let replay_log = tempfile::NamedTempFile::new().unwrap();
+    record(&replay_sandbox, replay_log.path());
+    replay_sandbox.replay(&recorded);
+    stop_recording(&replay_sandbox);
+
+    let replayed = ConsensusEventBus::read_log(replay_log.path()).unwrap();
+    assert_eq!(replayed, recorded);
+    assert_eq!(replay_sandbox.current_height(), Height(3));
+    assert_eq!(replay_sandbox.last_hash(), sandbox.last_hash());
+}
+
 /// Validator2,3,4 starts in 5th round
 /// Validator1 starts with delay
 /// Validator1 receive consensus messages, and reach actual round
@@ -123,6 +279,7 @@ fn test_reach_actual_round() {
         Round(4),
         block_at_first_height.clone().object_hash(),
         vec![], // there are no transactions in future propose
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(3)),
     );
 
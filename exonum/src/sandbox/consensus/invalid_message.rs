@@ -15,12 +15,18 @@
 //! Tests in this module are designed to test ability of the node to handle
 //! incorrect messages.
 
-use exonum_merkledb::ObjectHash;
+use exonum_merkledb::{BinaryValue, ObjectHash};
+
+use std::time::Duration;
 
 use crate::{
+    blockchain::Equivocation,
+    crypto,
+    explorer::BlockchainExplorer,
     helpers::{Height, Round, ValidatorId},
     messages::{Propose, Verified},
-    sandbox::{sandbox_tests_helper::*, timestamping_sandbox},
+    runtime::{BlockchainData, InstanceDescriptor},
+    sandbox::{sandbox_tests_helper::*, timestamping::TimestampingService, timestamping_sandbox},
 };
 
 /// HANDLE message
@@ -38,6 +44,7 @@ fn test_ignore_message_with_incorrect_signature() {
         Round(1),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(1)),
     );
 
@@ -57,6 +64,7 @@ fn test_ignore_message_with_incorrect_validator_id() {
             Round(1),
             sandbox.last_hash(),
             vec![],
+            sandbox.time().into(),
         ),
         sandbox.public_key(ValidatorId(1)),
         sandbox.secret_key(ValidatorId(1)),
@@ -143,3 +151,228 @@ fn handle_propose_that_sends_before_than_propose_timeout_exceeded() {
         sandbox.secret_key(ValidatorId(0)),
     ));
 }
+
+#[test]
+fn ignore_propose_with_time_too_far_in_future() {
+    let sandbox = timestamping_sandbox();
+
+    let max_drift = sandbox.cfg().max_block_time_drift;
+    let time = sandbox.time() + Duration::from_millis(max_drift + 1);
+    let propose = ProposeBuilder::new(&sandbox)
+        // without this line Prevote would have been broadcast
+        .with_time(time.into())
+        .build();
+
+    sandbox.recv(&propose);
+    //    broadcast here is absent
+}
+
+#[test]
+fn handle_propose_with_time_within_drift() {
+    let sandbox = timestamping_sandbox();
+
+    let max_drift = sandbox.cfg().max_block_time_drift;
+    let time = sandbox.time() + Duration::from_millis(max_drift);
+    let propose = ProposeBuilder::new(&sandbox).with_time(time.into()).build();
+
+    sandbox.recv(&propose);
+
+    sandbox.broadcast(&sandbox.create_prevote(
+        ValidatorId(0),
+        Height(1),
+        Round(1),
+        propose.object_hash(),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(0)),
+    ));
+}
+
+#[test]
+fn ignore_propose_with_time_earlier_than_previous_block() {
+    let sandbox = timestamping_sandbox();
+
+    let previous_block_time = sandbox.last_block().timestamp();
+    let propose = ProposeBuilder::new(&sandbox)
+        // without this line Prevote would have been broadcast
+        .with_time(previous_block_time - chrono::Duration::milliseconds(1))
+        .build();
+
+    sandbox.recv(&propose);
+    //    broadcast here is absent
+}
+
+// HANDLE PREVOTE AND PRECOMMIT
+
+#[test]
+fn record_equivocating_prevotes() {
+    let sandbox = timestamping_sandbox();
+
+    let first = sandbox.create_prevote(
+        ValidatorId(2),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(2)),
+    );
+    let second = sandbox.create_prevote(
+        ValidatorId(2),
+        Height(1),
+        Round(1),
+        crypto::hash(&[2]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(2)),
+    );
+    sandbox.recv(&first);
+    sandbox.recv(&second);
+    // Duplicate messages should not produce additional evidence.
+    sandbox.recv(&second);
+
+    let snapshot = sandbox.blockchain().snapshot();
+    let explorer = BlockchainExplorer::new(snapshot.as_ref());
+    let equivocation = Equivocation::from_prevotes(second, first).unwrap();
+    assert_eq!(explorer.equivocations(None), vec![equivocation.clone()]);
+    assert_eq!(
+        explorer.equivocations(Some(ValidatorId(2))),
+        vec![equivocation]
+    );
+    assert!(explorer.equivocations(Some(ValidatorId(1))).is_empty());
+}
+
+#[test]
+fn services_read_equivocations() {
+    let sandbox = timestamping_sandbox();
+
+    let first = sandbox.create_prevote(
+        ValidatorId(1),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(1)),
+    );
+    let second = sandbox.create_prevote(
+        ValidatorId(1),
+        Height(1),
+        Round(1),
+        crypto::hash(&[2]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(1)),
+    );
+    sandbox.recv(&first);
+    sandbox.recv(&second);
+
+    let snapshot = sandbox.blockchain().snapshot();
+    let instance = InstanceDescriptor {
+        id: TimestampingService::ID,
+        name: "timestamping",
+    };
+    let data = BlockchainData::new(snapshot.as_ref(), instance);
+    let equivocation = Equivocation::from_prevotes(first, second).unwrap();
+    let equivocations = data.for_core().equivocations();
+    assert_eq!(
+        equivocations.get(&equivocation.object_hash()),
+        Some(equivocation)
+    );
+}
+
+#[test]
+fn record_equivocating_precommits() {
+    let sandbox = timestamping_sandbox();
+
+    let first = sandbox.create_precommit(
+        ValidatorId(3),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        crypto::hash(&[2]),
+        sandbox.time().into(),
+        sandbox.secret_key(ValidatorId(3)),
+    );
+    let second = sandbox.create_precommit(
+        ValidatorId(3),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        crypto::hash(&[3]),
+        sandbox.time().into(),
+        sandbox.secret_key(ValidatorId(3)),
+    );
+    sandbox.recv(&first);
+    sandbox.recv(&second);
+
+    let snapshot = sandbox.blockchain().snapshot();
+    let equivocations = BlockchainExplorer::new(snapshot.as_ref()).equivocations(None);
+    assert_eq!(equivocations.len(), 1);
+    assert_eq!(equivocations[0].validator(), ValidatorId(3));
+    assert_eq!(equivocations[0].height(), Height(1));
+
+    let bytes = equivocations[0].to_bytes();
+    assert_eq!(
+        Equivocation::from_bytes(bytes.into()).unwrap(),
+        equivocations[0]
+    );
+}
+
+#[test]
+fn record_precommits_for_same_block_and_different_proposals() {
+    let sandbox = timestamping_sandbox();
+
+    let first = sandbox.create_precommit(
+        ValidatorId(3),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        crypto::hash(&[3]),
+        sandbox.time().into(),
+        sandbox.secret_key(ValidatorId(3)),
+    );
+    let second = sandbox.create_precommit(
+        ValidatorId(3),
+        Height(1),
+        Round(1),
+        crypto::hash(&[2]),
+        crypto::hash(&[3]),
+        sandbox.time().into(),
+        sandbox.secret_key(ValidatorId(3)),
+    );
+    sandbox.recv(&first);
+    sandbox.recv(&second);
+
+    let snapshot = sandbox.blockchain().snapshot();
+    let equivocation = Equivocation::from_precommits(first, second).unwrap();
+    assert_eq!(
+        BlockchainExplorer::new(snapshot.as_ref()).equivocations(None),
+        vec![equivocation]
+    );
+}
+
+#[test]
+fn votes_in_different_rounds_are_not_equivocation() {
+    let sandbox = timestamping_sandbox();
+
+    let first = sandbox.create_prevote(
+        ValidatorId(2),
+        Height(1),
+        Round(1),
+        crypto::hash(&[1]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(2)),
+    );
+    let second = sandbox.create_prevote(
+        ValidatorId(2),
+        Height(1),
+        Round(2),
+        crypto::hash(&[2]),
+        NOT_LOCKED,
+        sandbox.secret_key(ValidatorId(2)),
+    );
+    assert!(Equivocation::from_prevotes(first.clone(), second.clone()).is_none());
+
+    sandbox.recv(&first);
+    sandbox.recv(&second);
+    let snapshot = sandbox.blockchain().snapshot();
+    assert!(BlockchainExplorer::new(snapshot.as_ref())
+        .equivocations(None)
+        .is_empty());
+}
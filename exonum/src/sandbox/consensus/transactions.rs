@@ -121,6 +121,7 @@ fn tx_pool_size_overflow() {
         Round(1),
         sandbox.last_hash(),
         vec![tx1.object_hash()],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(2)),
     );
 
@@ -34,6 +34,7 @@ fn test_queue_message_from_future_round() {
         Round(2),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(3)),
     );
 
@@ -94,10 +95,13 @@ fn test_queue_propose_message_from_next_height() {
 
     let tx = gen_timestamping_tx();
 
+    // The node proposes the block at the first height after the propose timeout.
+    let block_time = sandbox.time() + Duration::from_millis(PROPOSE_TIMEOUT + 1);
     let block_at_first_height = BlockBuilder::new(&sandbox)
         .with_proposer_id(ValidatorId(0))
         .with_tx_hash(&compute_tx_hash(&[tx.clone()]))
         .with_state_hash(&sandbox.compute_state_hash(&[tx.clone()]))
+        .with_time(block_time.into())
         .build();
 
     let future_propose = sandbox.create_propose(
@@ -106,6 +110,7 @@ fn test_queue_propose_message_from_next_height() {
         Round(2),
         block_at_first_height.clone().object_hash(),
         vec![], // there are no transactions in future propose
+        block_time.into(),
         sandbox.secret_key(ValidatorId(0)),
     );
 
@@ -47,9 +47,9 @@ use crate::{
         SignedMessage, Status, TransactionsRequest, TransactionsResponse, Verified,
     },
     node::{
-        ApiSender, Configuration, ConnectInfo, ConnectList, ConnectListConfig, ExternalMessage,
-        ListenerConfig, NodeHandler, NodeSender, PeerAddress, ServiceConfig, State,
-        SystemStateProvider,
+        ApiSender, Configuration, ConnectInfo, ConnectList, ConnectListConfig, ConsensusEvent,
+        ExternalMessage, ListenerConfig, NodeHandler, NodeSender, PeerAddress, RecordedEvent,
+        ServiceConfig, State, SystemStateProvider,
     },
     sandbox::{
         config_updater::ConfigUpdaterService, sandbox_tests_helper::PROPOSE_TIMEOUT,
@@ -338,6 +338,7 @@ impl Sandbox {
     }
 
     /// Creates a `Propose` message signed by this validator.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_propose(
         &self,
         validator_id: ValidatorId,
@@ -345,10 +346,11 @@ impl Sandbox {
         round: Round,
         last_hash: Hash,
         tx_hashes: impl IntoIterator<Item = Hash>,
+        time: chrono::DateTime<chrono::Utc>,
         secret_key: &SecretKey,
     ) -> Verified<Propose> {
         Verified::from_value(
-            Propose::new(validator_id, height, round, last_hash, tx_hashes),
+            Propose::new(validator_id, height, round, last_hash, tx_hashes, time),
             self.public_key(validator_id),
             secret_key,
         )
@@ -667,6 +669,35 @@ impl Sandbox {
         self.node_state().is_leader()
     }
 
+    /// Replays consensus events recorded by a node. Messages received by the node are
+    /// delivered to the sandbox at the recorded time, and the sandbox time is advanced
+    /// so that the recorded timeouts fire. Messages sent by the sandbox node are discarded.
+    ///
+    /// Only consensus messages are recorded, so the transactions of the replayed blocks
+    /// must be known to the sandbox node beforehand.
+    pub fn replay(&self, log: &[RecordedEvent]) {
+        for recorded in log {
+            self.inner.borrow_mut().sent.clear();
+            let delay = SystemTime::from(recorded.time)
+                .duration_since(self.time())
+                .unwrap_or_default();
+            let is_timeout = match recorded.event {
+                ConsensusEvent::TimeoutFired { .. } => true,
+                _ => false,
+            };
+            if is_timeout || delay > Duration::default() {
+                self.add_time(delay);
+                self.inner.borrow_mut().sent.clear();
+            }
+
+            if let ConsensusEvent::MessageReceived { ref message } = recorded.event {
+                let event = NetworkEvent::MessageReceived(message.to_bytes());
+                self.inner.borrow_mut().handle_event(event);
+            }
+        }
+        self.inner.borrow_mut().sent.clear();
+    }
+
     pub fn leader(&self, round: Round) -> ValidatorId {
         self.node_state().leader(round)
     }
@@ -737,8 +768,13 @@ impl Sandbox {
         blockchain.merge(fork.into_patch()).unwrap();
 
         let mut fork_with_new_block = blockchain.fork();
-        let (_, patch) =
-            blockchain.create_patch(ValidatorId(0), height, &hashes, &mut BTreeMap::new());
+        let (_, patch) = blockchain.create_patch(
+            ValidatorId(0),
+            height,
+            self.time().into(),
+            &hashes,
+            &mut BTreeMap::new(),
+        );
         fork_with_new_block.merge(patch);
 
         let fork = blockchain.fork();
@@ -1027,6 +1063,7 @@ impl SandboxBuilder {
                 min_propose_timeout: PROPOSE_TIMEOUT,
                 max_propose_timeout: PROPOSE_TIMEOUT,
                 propose_timeout_threshold: std::u32::MAX,
+                max_block_time_drift: ConsensusConfig::DEFAULT_MAX_BLOCK_TIME_DRIFT,
                 validator_keys: Vec::default(),
             },
         }
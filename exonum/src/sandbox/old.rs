@@ -47,6 +47,7 @@ fn test_send_propose_and_prevote() {
         Round(3),
         sandbox.last_hash(),
         vec![tx.object_hash()],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(0)),
     );
 
@@ -71,6 +72,7 @@ fn test_send_prevote() {
         Round(1),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(2)),
     );
 
@@ -95,6 +97,7 @@ fn test_get_lock_and_send_precommit() {
         Round(1),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(2)),
     );
 
@@ -105,6 +108,7 @@ fn test_get_lock_and_send_precommit() {
         sandbox.last_hash(),
         HashTag::empty_list_hash(),
         sandbox.last_state_hash(),
+        propose.payload().time(),
     );
 
     sandbox.recv(&propose);
@@ -155,6 +159,7 @@ fn test_commit() {
         Round(1),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(2)),
     );
 
@@ -165,6 +170,7 @@ fn test_commit() {
         sandbox.last_hash(),
         HashTag::empty_list_hash(),
         sandbox.last_state_hash(),
+        propose.payload().time(),
     );
 
     sandbox.recv(&propose);
@@ -233,6 +239,7 @@ fn received_unexpected_propose() {
         Round(1),
         sandbox.last_hash(),
         vec![],
+        sandbox.time().into(),
         sandbox.secret_key(ValidatorId(1)),
     );
 
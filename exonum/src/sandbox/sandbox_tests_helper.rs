@@ -15,6 +15,7 @@
 //! Functions with reusable code used for sandbox tests.
 
 use bit_vec::BitVec;
+use chrono::{DateTime, Utc};
 use exonum_merkledb::{access::AccessExt, Database, HashTag, ObjectHash, TemporaryDB};
 
 use std::{cell::RefCell, collections::BTreeMap, time::Duration};
@@ -45,6 +46,7 @@ pub struct BlockBuilder<'a> {
     tx_hash: Option<Hash>,
     state_hash: Option<Hash>,
     tx_count: Option<u32>,
+    time: Option<DateTime<Utc>>,
 
     sandbox: &'a TimestampingSandbox,
 }
@@ -58,6 +60,7 @@ impl<'a> BlockBuilder<'a> {
             tx_hash: None,
             state_hash: None,
             tx_count: None,
+            time: None,
 
             sandbox,
         }
@@ -97,6 +100,11 @@ impl<'a> BlockBuilder<'a> {
         self
     }
 
+    pub fn with_time(mut self, time: DateTime<Utc>) -> Self {
+        self.time = Some(time);
+        self
+    }
+
     pub fn build(&self) -> Block {
         Block::new(
             self.proposer_id
@@ -107,6 +115,7 @@ impl<'a> BlockBuilder<'a> {
             self.tx_hash.unwrap_or_else(HashTag::empty_list_hash),
             self.state_hash
                 .unwrap_or_else(|| self.sandbox.last_state_hash()),
+            self.time.unwrap_or_else(|| self.sandbox.time().into()),
         )
     }
 }
@@ -119,6 +128,7 @@ pub struct ProposeBuilder<'a> {
     round: Option<Round>,
     prev_hash: Option<&'a Hash>,
     tx_hashes: Option<&'a [Hash]>,
+    time: Option<DateTime<Utc>>,
 
     sandbox: &'a TimestampingSandbox,
 }
@@ -131,6 +141,7 @@ impl<'a> ProposeBuilder<'a> {
             round: None,
             prev_hash: None,
             tx_hashes: None,
+            time: None,
             sandbox,
         }
     }
@@ -160,6 +171,11 @@ impl<'a> ProposeBuilder<'a> {
         self
     }
 
+    pub fn with_time(mut self, time: DateTime<Utc>) -> Self {
+        self.time = Some(time);
+        self
+    }
+
     pub fn build(&self) -> Verified<Propose> {
         self.sandbox.create_propose(
             self.validator_id
@@ -168,6 +184,7 @@ impl<'a> ProposeBuilder<'a> {
             self.round.unwrap_or_else(|| self.sandbox.current_round()),
             *self.prev_hash.unwrap_or(&self.sandbox.last_hash()),
             self.tx_hashes.unwrap_or(&[]).iter().cloned(),
+            self.time.unwrap_or_else(|| self.sandbox.time().into()),
             self.sandbox.secret_key(
                 self.validator_id
                     .unwrap_or_else(|| self.sandbox.current_leader()),
@@ -540,6 +557,7 @@ fn get_propose_with_transactions_for_validator(
         sandbox.current_round(),
         sandbox.last_hash(),
         transactions.iter().cloned(),
+        sandbox.time().into(),
         sandbox.secret_key(validator),
     )
 }
@@ -619,6 +637,7 @@ pub fn receive_valid_propose_with_transactions(
         sandbox.current_round(),
         sandbox.last_hash(),
         transactions.iter().cloned(),
+        sandbox.time().into(),
         sandbox.secret_key(sandbox.current_leader()),
     );
     sandbox.recv(&propose);
@@ -15,7 +15,9 @@
 //! Simplified blockchain emulation for the `BlockchainExplorer`.
 
 use exonum::{
-    blockchain::{Blockchain, BlockchainBuilder, BlockchainMut, InstanceCollection},
+    blockchain::{
+        Blockchain, BlockchainBuilder, BlockchainMut, InstanceCollection, TransactionIndexes,
+    },
     crypto::{self, Hash, PublicKey, SecretKey},
     helpers::generate_testnet_config,
     messages::Verified,
@@ -119,6 +121,11 @@ pub fn consensus_keys() -> (PublicKey, SecretKey) {
 
 /// Creates a blockchain with no blocks.
 pub fn create_blockchain() -> BlockchainMut {
+    create_blockchain_with_indexes(TransactionIndexes::default())
+}
+
+/// Creates a blockchain with no blocks and the specified transaction indexes.
+pub fn create_blockchain_with_indexes(transaction_indexes: TransactionIndexes) -> BlockchainMut {
     let config = generate_testnet_config(1, 0)[0].clone();
     let blockchain = Blockchain::new(
         TemporaryDB::new(),
@@ -130,6 +137,7 @@ pub fn create_blockchain() -> BlockchainMut {
         vec![InstanceCollection::new(MyService).with_instance(SERVICE_ID, "my-service", ())];
     BlockchainBuilder::new(blockchain, config.consensus)
         .with_rust_runtime(mpsc::channel(1).0, services)
+        .with_transaction_indexes(transaction_indexes)
         .build()
         .unwrap()
 }
@@ -145,9 +153,15 @@ pub fn create_block(blockchain: &mut BlockchainMut, transactions: Vec<Verified<A
     let height = blockchain.as_ref().last_block().height().next();
     blockchain.add_transactions_into_pool(transactions);
 
+    let block_time = SystemTime::now().into();
     let mut tx_cache = BTreeMap::new();
-    let (block_hash, patch) =
-        blockchain.create_patch(ValidatorId(0), height, &tx_hashes, &mut tx_cache);
+    let (block_hash, patch) = blockchain.create_patch(
+        ValidatorId(0),
+        height,
+        block_time,
+        &tx_hashes,
+        &mut tx_cache,
+    );
     let (consensus_public_key, consensus_secret_key) = consensus_keys();
 
     let propose = Verified::from_value(
@@ -157,6 +171,7 @@ pub fn create_block(blockchain: &mut BlockchainMut, transactions: Vec<Verified<A
             Round::first(),
             blockchain.as_ref().last_hash(),
             tx_hashes,
+            block_time,
         ),
         consensus_public_key,
         &consensus_secret_key,
@@ -25,7 +25,7 @@ extern crate serde_derive;
 extern crate pretty_assertions;
 
 use exonum::{
-    blockchain::TxLocation,
+    blockchain::{TransactionIndexes, TxLocation},
     crypto::{self, Hash},
     explorer::*,
     helpers::Height,
@@ -36,7 +36,10 @@ use exonum_merkledb::ObjectHash;
 
 use std::iter;
 
-use crate::blockchain::{create_block, create_blockchain, CreateWallet, Transfer, SERVICE_ID};
+use crate::blockchain::{
+    create_block, create_blockchain, create_blockchain_with_indexes, CreateWallet, Transfer,
+    SERVICE_ID,
+};
 
 mod blockchain;
 
@@ -370,6 +373,142 @@ fn test_transaction_iterator() {
     assert_eq!(create_wallet_positions, vec![0, 1]);
 }
 
+#[test]
+fn test_committed_transactions() {
+    fn list(
+        explorer: &BlockchainExplorer<'_>,
+        filter: CommittedTransactionFilter,
+        before: Option<&TxLocation>,
+    ) -> Vec<Hash> {
+        explorer
+            .committed_transactions(filter, before)
+            .map(|tx| tx.content().object_hash())
+            .collect()
+    }
+
+    let indexes = vec![
+        TransactionIndexes::default(),
+        TransactionIndexes {
+            by_instance: true,
+            by_author: false,
+        },
+        TransactionIndexes {
+            by_instance: true,
+            by_author: true,
+        },
+    ];
+    for transaction_indexes in indexes {
+        let mut blockchain = create_blockchain_with_indexes(transaction_indexes);
+        let wallets: Vec<_> = tx_generator().take(5).collect();
+        create_block(&mut blockchain, wallets.clone());
+
+        let (pk_alice, key_alice) = crypto::gen_keypair();
+        let (pk_bob, key_bob) = crypto::gen_keypair();
+        let tx_alice = CreateWallet::new(&pk_alice, "Alice").sign(SERVICE_ID, pk_alice, &key_alice);
+        let tx_bob = CreateWallet::new(&pk_bob, "Bob").sign(SERVICE_ID, pk_bob, &key_bob);
+        let tx_transfer =
+            Transfer::new(&pk_alice, &pk_bob, 2).sign(SERVICE_ID, pk_alice, &key_alice);
+        create_block(
+            &mut blockchain,
+            vec![tx_alice.clone(), tx_bob.clone(), tx_transfer.clone()],
+        );
+        create_block(&mut blockchain, vec![]);
+
+        let snapshot = blockchain.snapshot();
+        let explorer = BlockchainExplorer::new(snapshot.as_ref());
+        let mut all_hashes: Vec<_> = wallets
+            .iter()
+            .chain(vec![&tx_alice, &tx_bob, &tx_transfer])
+            .map(ObjectHash::object_hash)
+            .collect();
+        all_hashes.reverse();
+
+        let filter = CommittedTransactionFilter::new();
+        assert_eq!(list(&explorer, filter, None), all_hashes);
+        let filter = CommittedTransactionFilter::new().with_instance(SERVICE_ID, None);
+        assert_eq!(list(&explorer, filter, None), all_hashes);
+        let filter = CommittedTransactionFilter::new().with_instance(SERVICE_ID + 1, None);
+        assert!(list(&explorer, filter, None).is_empty());
+
+        let filter = CommittedTransactionFilter::new()
+            .with_instance(SERVICE_ID, Some(CreateWallet::METHOD_ID));
+        assert_eq!(list(&explorer, filter, None), &all_hashes[1..]);
+        let filter = CommittedTransactionFilter::new().with_author(pk_alice);
+        assert_eq!(
+            list(&explorer, filter, None),
+            vec![tx_transfer.object_hash(), tx_alice.object_hash()]
+        );
+        let hashes: Vec<_> = explorer
+            .transactions_by_author(pk_alice, Some(&TxLocation::new(Height(2), 2)))
+            .map(|tx| tx.content().object_hash())
+            .collect();
+        assert_eq!(hashes, vec![tx_alice.object_hash()]);
+        let filter = CommittedTransactionFilter::new()
+            .with_author(pk_bob)
+            .with_instance(SERVICE_ID, None);
+        assert_eq!(list(&explorer, filter, None), vec![tx_bob.object_hash()]);
+        let filter = CommittedTransactionFilter::new()
+            .with_instance(SERVICE_ID, None)
+            .with_status(StatusFilter::Failure);
+        assert_eq!(
+            list(&explorer, filter, None),
+            vec![tx_transfer.object_hash(), tx_bob.object_hash()]
+        );
+
+        // Pagination.
+        let before = TxLocation::new(Height(2), 1);
+        let filter = CommittedTransactionFilter::new();
+        assert_eq!(list(&explorer, filter, Some(&before)), &all_hashes[2..]);
+        let filter = CommittedTransactionFilter::new().with_instance(SERVICE_ID, None);
+        assert_eq!(list(&explorer, filter, Some(&before)), &all_hashes[2..]);
+        let before = TxLocation::new(Height(1), 0);
+        let filter = CommittedTransactionFilter::new().with_instance(SERVICE_ID, None);
+        assert!(list(&explorer, filter, Some(&before)).is_empty());
+    }
+}
+
+#[test]
+fn test_committed_transactions_scan_limit() {
+    let mut blockchain = create_blockchain();
+    let tx = tx_generator().next().unwrap();
+    create_block(&mut blockchain, vec![tx.clone()]);
+    for _ in 0..5 {
+        create_block(&mut blockchain, vec![]);
+    }
+
+    let snapshot = blockchain.snapshot();
+    let explorer = BlockchainExplorer::new(snapshot.as_ref());
+    // The scan starts at height 6 and stops after moving through 2 blocks.
+    let mut transactions = explorer
+        .committed_transactions(CommittedTransactionFilter::new(), None)
+        .scan_limit(2);
+    assert!(transactions.next().is_none());
+    let cursor = transactions.cursor().unwrap();
+    assert_eq!(cursor, TxLocation::new(Height(4), 0));
+    // Lookups by author without the `by_author` index are limited as well.
+    let mut transactions = explorer
+        .transactions_by_author(tx.author(), None)
+        .scan_limit(2);
+    assert!(transactions.next().is_none());
+    assert_eq!(transactions.cursor(), Some(TxLocation::new(Height(4), 0)));
+
+    let mut transactions = explorer
+        .committed_transactions(CommittedTransactionFilter::new(), Some(&cursor))
+        .scan_limit(2);
+    assert!(transactions.next().is_none());
+    let cursor = transactions.cursor().unwrap();
+    assert_eq!(cursor, TxLocation::new(Height(2), 0));
+
+    let mut transactions = explorer
+        .committed_transactions(CommittedTransactionFilter::new(), Some(&cursor))
+        .scan_limit(2);
+    let committed = transactions.next().unwrap();
+    assert_eq!(committed.content().object_hash(), tx.object_hash());
+    assert!(transactions.next().is_none());
+    // The scan has reached the genesis block.
+    assert!(transactions.cursor().is_none());
+}
+
 #[test]
 fn test_block_with_transactions() {
     let mut blockchain = create_blockchain();
@@ -15,6 +15,7 @@
 // This is a regression test for exonum node.
 
 use exonum::{
+    api::backends::actix::TlsConfig,
     blockchain::InstanceCollection,
     crypto::Hash,
     helpers,
@@ -27,10 +28,15 @@ use exonum::{
 use exonum_derive::{exonum_interface, ServiceDispatcher, ServiceFactory};
 use exonum_merkledb::{Database, Snapshot, TemporaryDB};
 use futures::{sync::mpsc, Future, Stream};
+use rustls::{internal::pemfile, ClientConfig, ClientSession};
 use tokio::util::FutureExt;
 use tokio_core::reactor::Core;
 
 use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    net::TcpStream,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     thread::{self, JoinHandle},
     time::Duration,
@@ -190,3 +196,113 @@ fn test_node_restart_regression() {
     // and `commit_service` methods), and then once on each new node startup.
     assert_eq!(*start_times.lock().unwrap(), 3);
 }
+
+// Certificates in `tests/fixtures/tls` are issued for `localhost` by the `ca.pem` authority.
+fn tls_fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+fn run_node_with_tls(listen_port: u16, api_port: u16, tls: TlsConfig) -> RunHandle {
+    let mut node_cfg = helpers::generate_testnet_config(1, listen_port).remove(0);
+    node_cfg.api.public_api_address = Some(([127, 0, 0, 1], api_port).into());
+    node_cfg.api.public_api_tls = Some(tls);
+
+    let external_runtimes: Vec<(u32, Box<dyn Runtime>)> = vec![];
+    let node = Node::new(
+        TemporaryDB::new(),
+        external_runtimes,
+        vec![],
+        node_cfg,
+        None,
+    );
+    let api_tx = node.channel();
+    let node_thread = thread::spawn(move || {
+        node.run().unwrap();
+    });
+    // Wait until the API server has started.
+    thread::sleep(Duration::from_secs(1));
+    RunHandle {
+        node_thread,
+        api_tx,
+    }
+}
+
+fn client_tls_config(with_certificate: bool) -> Arc<ClientConfig> {
+    let mut config = ClientConfig::new();
+    let mut ca = BufReader::new(File::open(tls_fixture("ca.pem")).unwrap());
+    config.root_store.add_pem_file(&mut ca).unwrap();
+    if with_certificate {
+        let mut certificate = BufReader::new(File::open(tls_fixture("client.pem")).unwrap());
+        let mut key = BufReader::new(File::open(tls_fixture("client.key")).unwrap());
+        config.set_single_client_cert(
+            pemfile::certs(&mut certificate).unwrap(),
+            pemfile::pkcs8_private_keys(&mut key).unwrap().remove(0),
+        );
+    }
+    Arc::new(config)
+}
+
+/// Requests the user agent of the node over TLS and returns the beginning of the response,
+/// or `None` if the connection has failed.
+fn get_user_agent(port: u16, config: Arc<ClientConfig>) -> Option<String> {
+    let dns_name = webpki::DNSNameRef::try_from_ascii_str("localhost").unwrap();
+    let mut session = ClientSession::new(&config, dns_name);
+    let mut socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut stream = rustls::Stream::new(&mut session, &mut socket);
+
+    let request = "GET /api/system/v1/user_agent HTTP/1.1\r\n\
+                   Host: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).ok()?;
+    let mut response = [0; 1_024];
+    let len = stream.read(&mut response).ok()?;
+    Some(String::from_utf8_lossy(&response[..len]).into_owned())
+}
+
+fn shutdown(node: RunHandle) {
+    node.api_tx
+        .send_external_message(ExternalMessage::Shutdown)
+        .unwrap();
+    node.node_thread.join().unwrap();
+}
+
+#[test]
+fn test_public_api_over_tls() {
+    let tls = TlsConfig {
+        certificate_path: tls_fixture("server.pem"),
+        private_key_path: tls_fixture("server.key"),
+        client_ca_path: None,
+    };
+    let node = run_node_with_tls(3_610, 18_080, tls);
+
+    let response = get_user_agent(18_080, client_tls_config(false)).expect("TLS handshake failed");
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    // Plain HTTP requests are not served.
+    assert!(reqwest::get("http://127.0.0.1:18080/api/system/v1/user_agent").is_err());
+
+    shutdown(node);
+}
+
+#[test]
+fn test_public_api_tls_requires_client_certificate() {
+    let tls = TlsConfig {
+        certificate_path: tls_fixture("server.pem"),
+        private_key_path: tls_fixture("server.key"),
+        client_ca_path: Some(tls_fixture("ca.pem")),
+    };
+    let node = run_node_with_tls(3_620, 18_081, tls);
+
+    let response = get_user_agent(18_081, client_tls_config(false));
+    assert!(
+        response.map_or(true, |response| response.is_empty()),
+        "Request without a client certificate was served"
+    );
+    let response = get_user_agent(18_081, client_tls_config(true)).expect("TLS handshake failed");
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    shutdown(node);
+}
@@ -56,6 +56,20 @@ fn create_ws_client(addr: &str) -> WebSocketResult<Client<TcpStream>> {
     Err(last_err.unwrap())
 }
 
+fn get_status(url: &str) -> reqwest::StatusCode {
+    let mut last_err = None;
+    for _ in 0..5 {
+        match reqwest::get(url) {
+            Ok(response) => return response.status(),
+            Err(e) => {
+                sleep(Duration::from_millis(100));
+                last_err = Some(e);
+            }
+        }
+    }
+    panic!("Cannot connect to node: {}", last_err.unwrap());
+}
+
 fn recv_text_msg(client: &mut Client<TcpStream>) -> Option<String> {
     if let Ok(response) = client.recv_message() {
         match response {
@@ -188,7 +202,7 @@ fn test_transactions_subscribe_with_filter() {
 
     // Create client with filter
     let mut client = create_ws_client(
-        "ws://localhost:8082/api/explorer/v1/transactions/subscribe?service_id=118&message_id=0",
+        "ws://localhost:8082/api/explorer/v1/transactions/subscribe?instance_id=118&method_id=0",
     )
     .expect("Cannot connect to node");
     client
@@ -247,7 +261,7 @@ fn test_transactions_subscribe_with_partial_filter() {
 
     // Create client with filter
     let mut client = create_ws_client(
-        "ws://localhost:8083/api/explorer/v1/transactions/subscribe?service_id=118",
+        "ws://localhost:8083/api/explorer/v1/transactions/subscribe?instance_id=118",
     )
     .expect("Cannot connect to node");
     client
@@ -312,31 +326,37 @@ fn test_transactions_subscribe_with_partial_filter() {
 #[test]
 fn test_transactions_subscribe_with_bad_filter() {
     let node_handler = run_node(6335, 8084);
-    // A service id is missing in filter !!!
-    let mut client =
-        create_ws_client("ws://localhost:8084/api/explorer/v1/transactions/subscribe?message_id=0")
-            .unwrap();
+    // `message_id` is not a field of the filter.
+    let url = "localhost:8084/api/explorer/v1/transactions/subscribe?message_id=0";
+    assert_eq!(
+        get_status(&format!("http://{}", url)),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+    assert!(create_ws_client(&format!("ws://{}", url)).is_err());
 
-    client
-        .stream_ref()
-        .set_read_timeout(Some(Duration::from_secs(5)))
-        .unwrap();
-    let (pk, sk) = gen_keypair();
-    let tx = CreateWallet::new(pk, "Bob").sign(SERVICE_ID, pk, &sk);
-    let tx_json = json!({ "tx_body": tx });
-    let http_client = reqwest::Client::new();
-    let _res = http_client
-        .post("http://localhost:8084/api/explorer/v1/transactions")
-        .json(&tx_json)
-        .send()
+    // Shutdown node.
+    node_handler
+        .api_tx
+        .send_external_message(ExternalMessage::Shutdown)
         .unwrap();
+    node_handler.node_thread.join().unwrap();
+}
 
-    // Get one message and check that it is text.
-    let resp_text = recv_text_msg(&mut client);
-    assert!(resp_text.is_none());
+#[test]
+fn test_subscribe_with_unknown_filter_fields() {
+    let node_handler = run_node(6339, 8088);
+    let urls = [
+        // Filter of the previous versions of the API.
+        "http://localhost:8088/api/explorer/v1/transactions/subscribe?service_id=118",
+        // Typo in the field name.
+        "http://localhost:8088/api/explorer/v1/transactions/subscribe?instance=118",
+        "http://localhost:8088/api/explorer/v1/events/subscribe?event_name=transfer",
+    ];
+    for url in &urls {
+        assert_eq!(get_status(url), reqwest::StatusCode::BAD_REQUEST, "{}", url);
+    }
 
     // Shutdown node.
-    client.shutdown().unwrap();
     node_handler
         .api_tx
         .send_external_message(ExternalMessage::Shutdown)
@@ -391,6 +411,89 @@ fn test_subscribe() {
     node_handler.node_thread.join().unwrap();
 }
 
+#[test]
+fn test_change_subscriptions_with_rich_filter() {
+    let node_handler = run_node(6338, 8087);
+
+    let mut client =
+        create_ws_client("ws://localhost:8087/api/explorer/v1/ws").expect("Cannot connect to node");
+    client
+        .stream_ref()
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    // Subscribe to failed transactions of the service signed by Alice.
+    let (alice_pk, alice_sk) = gen_keypair();
+    let filter = TransactionFilter::new(SERVICE_ID, None)
+        .with_author(alice_pk)
+        .with_status(StatusFilter::Failure);
+    let subscription = SubscriptionType::Transactions {
+        filter: Some(filter),
+    };
+    let message = json!({ "type": "subscribe", "payload": [&subscription] });
+    client
+        .send_message(&OwnedMessage::Text(message.to_string()))
+        .unwrap();
+    let resp_text = recv_text_msg(&mut client).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp_text).unwrap(),
+        json!({"result": "success"})
+    );
+
+    // Neither the successful transaction of Alice nor the failed transaction of Bob
+    // should be delivered.
+    let (bob_pk, bob_sk) = gen_keypair();
+    let http_client = reqwest::Client::new();
+    let send_tx = |tx| {
+        http_client
+            .post("http://localhost:8087/api/explorer/v1/transactions")
+            .json(&json!({ "tx_body": tx }))
+            .send()
+            .unwrap();
+    };
+    send_tx(CreateWallet::new(alice_pk, "Alice").sign(SERVICE_ID, alice_pk, &alice_sk));
+    send_tx(CreateWallet::new(bob_pk, "Bob").sign(SERVICE_ID, bob_pk, &bob_sk));
+    let failed_tx = CreateWallet::new(alice_pk, "Bob").sign(SERVICE_ID, alice_pk, &alice_sk);
+    let failed_tx_hash = failed_tx.object_hash();
+    send_tx(failed_tx);
+
+    let resp_text = recv_text_msg(&mut client).unwrap();
+    match serde_json::from_str::<Notification>(&resp_text).unwrap() {
+        Notification::Transaction(summary) => {
+            assert_eq!(summary.tx_hash, failed_tx_hash);
+            assert_eq!(summary.instance_id, SERVICE_ID);
+            assert_eq!(summary.author, alice_pk);
+        }
+        other => panic!(
+            "Incorrect notification type (expected Transaction): {:?}",
+            other
+        ),
+    }
+    assert!(recv_text_msg(&mut client).is_none());
+
+    // Remove the subscription on the open socket.
+    let message = json!({ "type": "unsubscribe", "payload": [&subscription] });
+    client
+        .send_message(&OwnedMessage::Text(message.to_string()))
+        .unwrap();
+    let resp_text = recv_text_msg(&mut client).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&resp_text).unwrap(),
+        json!({"result": "success"})
+    );
+
+    send_tx(CreateWallet::new(alice_pk, "Bobby").sign(SERVICE_ID, alice_pk, &alice_sk));
+    assert!(recv_text_msg(&mut client).is_none());
+
+    // Shutdown node.
+    client.shutdown().unwrap();
+    node_handler
+        .api_tx
+        .send_external_message(ExternalMessage::Shutdown)
+        .unwrap();
+    node_handler.node_thread.join().unwrap();
+}
+
 #[test]
 fn test_node_shutdown_with_active_ws_client_should_not_wait_for_timeout() {
     let node_handler = run_node(6337, 8086);
@@ -429,3 +532,37 @@ fn test_node_shutdown_with_active_ws_client_should_not_wait_for_timeout() {
         let _ = client.shutdown();
     }
 }
+
+#[test]
+fn test_wait_for_commit_is_notified_on_commit() {
+    let node_handler = run_node(6340, 8089);
+    // Wait until the node API is available.
+    let url = "http://localhost:8089/api/explorer/v1/blocks?count=1";
+    assert_eq!(get_status(url), reqwest::StatusCode::OK);
+
+    let (pk, sk) = gen_keypair();
+    let tx = CreateWallet::new(pk, "Alice").sign(SERVICE_ID, pk, &sk);
+    let tx_hash = tx.object_hash();
+    let http_client = reqwest::Client::new();
+    http_client
+        .post("http://localhost:8089/api/explorer/v1/transactions")
+        .json(&json!({ "tx_body": tx }))
+        .send()
+        .unwrap();
+
+    // The request should be woken up by the commit notification long before the timeout.
+    let now = Instant::now();
+    let url = format!(
+        "http://localhost:8089/api/explorer/v1/transactions/wait?hash={}&timeout=30000",
+        tx_hash.to_hex()
+    );
+    let info: serde_json::Value = http_client.get(&url).send().unwrap().json().unwrap();
+    assert_eq!(info["type"], "committed");
+    assert!(now.elapsed() < Duration::from_secs(15));
+
+    node_handler
+        .api_tx
+        .send_external_message(ExternalMessage::Shutdown)
+        .unwrap();
+    node_handler.node_thread.join().unwrap();
+}
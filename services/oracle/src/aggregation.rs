@@ -0,0 +1,189 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions aggregating the values reported by the validators into the consolidated value.
+//!
+//! All the aggregation functions tolerate up to `f` Byzantine validators, where `f`
+//! is the largest integer such that `3 * f < n` and `n` is the number of validators.
+
+use std::fmt;
+
+/// Parameters of the aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregationParams {
+    /// The largest number of Byzantine validators.
+    pub max_byzantine_nodes: usize,
+    /// The minimum number of values required to aggregate them.
+    pub min_sources: usize,
+}
+
+impl AggregationParams {
+    /// Creates parameters for the specified number of validators. The values of at least
+    /// `2 * f + 1` validators are required for the aggregation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of validators is zero.
+    pub fn new(validators_count: usize) -> Self {
+        assert!(
+            validators_count > 0,
+            "There should be at least one validator"
+        );
+        let max_byzantine_nodes = (validators_count - 1) / 3;
+        Self {
+            max_byzantine_nodes,
+            min_sources: 2 * max_byzantine_nodes + 1,
+        }
+    }
+
+    /// Sets the minimum number of values required for the aggregation. At least one
+    /// of the values should be reported by an honest validator, so the number
+    /// is never less than `f + 1`.
+    pub fn with_min_sources(mut self, min_sources: usize) -> Self {
+        self.min_sources = min_sources.max(self.max_byzantine_nodes + 1);
+        self
+    }
+}
+
+/// Function aggregating the values reported by the validators.
+pub trait Aggregator<V>: Send + Sync + fmt::Debug + 'static {
+    /// Name of the aggregation function. The name is a part of the artifact name
+    /// of the oracle services, so the services consolidating the values differently
+    /// cannot be confused.
+    const NAME: &'static str;
+
+    /// Returns the consolidated value, or `None` if it cannot be determined from the values.
+    /// The values are passed in an arbitrary order.
+    fn aggregate(&self, values: Vec<V>, params: &AggregationParams) -> Option<V>;
+}
+
+/// Selects the `f + 1`-th greatest value, so at least one honest validator has reported
+/// a value not less than the selected one.
+///
+/// This is the aggregation used by the time oracle: if the values of at least `2 * f + 1`
+/// validators are known, the selected value is also not greater than the value of
+/// some honest validator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuorumMin;
+
+impl<V: Ord> Aggregator<V> for QuorumMin {
+    const NAME: &'static str = "quorum-min";
+
+    fn aggregate(&self, mut values: Vec<V>, params: &AggregationParams) -> Option<V> {
+        if values.len() < params.min_sources {
+            return None;
+        }
+        // Ordering values from highest to lowest.
+        values.sort_by(|a, b| b.cmp(a));
+        values.into_iter().nth(params.max_byzantine_nodes)
+    }
+}
+
+/// Selects the median of the values. For an even number of values, the lower median
+/// is selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Median;
+
+impl<V: Ord> Aggregator<V> for Median {
+    const NAME: &'static str = "median";
+
+    fn aggregate(&self, mut values: Vec<V>, params: &AggregationParams) -> Option<V> {
+        if values.is_empty() || values.len() < params.min_sources {
+            return None;
+        }
+        values.sort();
+        let median = (values.len() - 1) / 2;
+        values.into_iter().nth(median)
+    }
+}
+
+/// Selects the value reported by at least `min_sources` validators. If there are
+/// several such values, the value reported by the greatest number of validators
+/// is selected; ties are resolved in favor of the greater value.
+///
+/// This aggregation is suitable for the values which cannot be averaged, e.g.,
+/// headers of the blocks in an external blockchain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExactMatch;
+
+impl<V: Ord> Aggregator<V> for ExactMatch {
+    const NAME: &'static str = "exact-match";
+
+    fn aggregate(&self, mut values: Vec<V>, params: &AggregationParams) -> Option<V> {
+        values.sort();
+
+        let mut selected: Option<(V, usize)> = None;
+        let mut values = values.into_iter().peekable();
+        while let Some(value) = values.next() {
+            let mut count = 1;
+            while values.peek() == Some(&value) {
+                values.next();
+                count += 1;
+            }
+
+            let is_better = selected
+                .as_ref()
+                .map_or(true, |(_, selected_count)| count >= *selected_count);
+            if count >= params.min_sources && is_better {
+                selected = Some((value, count));
+            }
+        }
+        selected.map(|(value, _)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregation_params() {
+        let params = AggregationParams::new(4);
+        assert_eq!(params.max_byzantine_nodes, 1);
+        assert_eq!(params.min_sources, 3);
+        assert_eq!(params.with_min_sources(1).min_sources, 2);
+        assert_eq!(params.with_min_sources(4).min_sources, 4);
+
+        let params = AggregationParams::new(1);
+        assert_eq!(params.max_byzantine_nodes, 0);
+        assert_eq!(params.min_sources, 1);
+    }
+
+    #[test]
+    fn quorum_min() {
+        let params = AggregationParams::new(4);
+        assert_eq!(QuorumMin.aggregate(vec![1, 5], &params), None);
+        assert_eq!(QuorumMin.aggregate(vec![1, 5, 3], &params), Some(3));
+        assert_eq!(QuorumMin.aggregate(vec![4, 1, 5, 3], &params), Some(4));
+    }
+
+    #[test]
+    fn median() {
+        let params = AggregationParams::new(4);
+        assert_eq!(Median.aggregate(vec![1, 5], &params), None);
+        assert_eq!(Median.aggregate(vec![1, 5, 3], &params), Some(3));
+        assert_eq!(Median.aggregate(vec![4, 1, 5, 3], &params), Some(3));
+    }
+
+    #[test]
+    fn exact_match() {
+        let params = AggregationParams::new(4);
+        assert_eq!(ExactMatch.aggregate(vec![1, 1, 2, 3], &params), None);
+        assert_eq!(ExactMatch.aggregate(vec![2, 1, 2, 2], &params), Some(2));
+
+        let params = params.with_min_sources(2);
+        assert_eq!(ExactMatch.aggregate(vec![1, 2, 1, 2], &params), Some(2));
+        assert_eq!(ExactMatch.aggregate(vec![1, 3, 1, 2], &params), Some(1));
+    }
+}
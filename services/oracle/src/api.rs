@@ -0,0 +1,102 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Oracle service API.
+
+use exonum::{
+    api::{description, ApiSchema, Schema},
+    crypto::PublicKey,
+    runtime::rust::api,
+};
+use exonum_merkledb::{BinaryValue, ObjectHash};
+use serde::Serialize;
+
+use crate::{config::Config, schema::OracleSchema};
+
+/// Public key of the validator and the latest value reported by it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorValue<V> {
+    /// Public key of the validator.
+    pub public_key: PublicKey,
+    /// The latest value reported by the validator.
+    pub value: Option<V>,
+}
+
+impl<V: ApiSchema> ApiSchema for ValidatorValue<V> {
+    fn api_schema() -> Schema {
+        description::object_schema(vec![
+            ("public_key", PublicKey::api_schema(), false),
+            ("value", Option::<V>::api_schema(), false),
+        ])
+    }
+}
+
+/// Implement the public API for the oracle service.
+#[derive(Debug, Clone)]
+pub struct PublicApi;
+
+impl PublicApi {
+    /// Endpoint for getting the consolidated value.
+    pub fn value<V>(state: &api::ServiceApiState<'_>, _query: ()) -> api::Result<Option<V>>
+    where
+        V: BinaryValue + ObjectHash,
+    {
+        Ok(OracleSchema::new(state.service_data()).value.get())
+    }
+
+    /// Endpoint for getting the configuration of the oracle.
+    pub fn config<V>(state: &api::ServiceApiState<'_>, _query: ()) -> api::Result<Config>
+    where
+        V: BinaryValue + ObjectHash,
+    {
+        Ok(OracleSchema::<_, V>::new(state.service_data())
+            .config
+            .get()
+            .unwrap_or_default())
+    }
+
+    /// Endpoint for getting the latest values reported by the current validators.
+    pub fn validators_values<V>(
+        state: &api::ServiceApiState<'_>,
+        _query: (),
+    ) -> api::Result<Vec<ValidatorValue<V>>>
+    where
+        V: BinaryValue + ObjectHash,
+    {
+        let validator_keys = state.data().for_core().consensus_config().validator_keys;
+        let schema = OracleSchema::new(state.service_data());
+
+        // `None` if the value of the validator is unknown.
+        let validators_values = validator_keys
+            .iter()
+            .map(|validator| ValidatorValue {
+                public_key: validator.service_key,
+                value: schema.validators_values.get(&validator.service_key),
+            })
+            .collect::<Vec<_>>();
+        Ok(validators_values)
+    }
+
+    /// Extend API.
+    pub fn wire<V>(self, builder: &mut api::ServiceApiBuilder)
+    where
+        V: BinaryValue + ObjectHash + Serialize + ApiSchema + Send + Sync + 'static,
+    {
+        builder
+            .public_scope()
+            .endpoint("v1/value", Self::value::<V>)
+            .endpoint("v1/config", Self::config::<V>)
+            .endpoint("v1/validators_values", Self::validators_values::<V>);
+    }
+}
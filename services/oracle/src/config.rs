@@ -0,0 +1,132 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum::{
+    impl_api_schema,
+    runtime::{rust::CallContext, DispatcherError, ExecutionError},
+};
+use exonum_proto::ProtobufConvert;
+use exonum_supervisor::Configure;
+
+use crate::{
+    aggregation::Aggregator, proto, provider::ValueProvider, schema::OracleSchema,
+    transactions::Error, OracleService,
+};
+
+/// Configuration of the oracle.
+///
+/// The default configuration takes into account all known values of the validators,
+/// and the consolidated value is updated as soon as 2/3+1 of the validators have
+/// reported their values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BinaryValue, ObjectHash)]
+pub struct Config {
+    /// Maximum age of the validator value, in blocks. Values reported more than `max_age`
+    /// blocks ago are stale and are not taken into account. If not specified, the age
+    /// is not limited.
+    pub max_age: Option<u64>,
+    /// Minimum number of fresh validator values required to update the consolidated value.
+    /// If not specified, 2/3+1 of the validators are required. Values less than 1/3+1
+    /// of the validators are treated as 1/3+1.
+    pub min_sources: Option<u32>,
+}
+
+impl_api_schema!(Config {
+    max_age: Option<u64>,
+    min_sources: Option<u32>,
+});
+
+impl Config {
+    /// Checks whether the value reported at `reported_height` is still fresh
+    /// at `height`.
+    pub fn is_fresh(&self, reported_height: u64, height: u64) -> bool {
+        let age = height.saturating_sub(reported_height);
+        self.max_age.map_or(true, |max_age| age <= max_age)
+    }
+}
+
+impl ProtobufConvert for Config {
+    type ProtoStruct = proto::Config;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        pb.set_max_age(self.max_age.unwrap_or(0));
+        pb.set_min_sources(self.min_sources.unwrap_or(0));
+        pb
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        // Zero values denote unspecified parameters.
+        Ok(Self {
+            max_age: Some(pb.get_max_age()).filter(|&value| value != 0),
+            min_sources: Some(pb.get_min_sources()).filter(|&value| value != 0),
+        })
+    }
+}
+
+/// Verifies that the configuration is applicable to the current validators.
+/// Oracles extending the configuration, e.g., the time oracle, should also
+/// use this check.
+pub fn check_config(context: &CallContext<'_>, config: &Config) -> Result<(), ExecutionError> {
+    // The consolidated value cannot be updated if there are more required sources
+    // than validators.
+    let validator_count = context
+        .data()
+        .for_core()
+        .consensus_config()
+        .validator_keys
+        .len();
+    if let Some(min_sources) = config.min_sources {
+        if min_sources as usize > validator_count {
+            return Err(Error::InvalidConfig.into());
+        }
+    }
+    Ok(())
+}
+
+impl<P, A> Configure for OracleService<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value>,
+{
+    type Params = Config;
+
+    fn verify_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+        check_config(&context, &params)
+    }
+
+    fn apply_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+        OracleSchema::<_, P::Value>::new(context.service_data())
+            .config
+            .set(params);
+        Ok(())
+    }
+}
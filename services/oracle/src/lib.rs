@@ -0,0 +1,224 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generic oracle service for Exonum.
+//!
+//! The oracle imports values from the external world to the blockchain: every validator
+//! observes the value with a [`ValueProvider`] and reports it in a transaction after
+//! the commit of each block. The reported values are consolidated with an [`Aggregator`],
+//! e.g., the [`Median`], [`QuorumMin`] or [`ExactMatch`] one.
+//!
+//! Values reported more than `max_age` blocks ago are considered stale and are not taken
+//! into account; the maximum age and the minimum number of fresh values required
+//! to update the consolidated value are set by the oracle [`Config`], which can be changed
+//! via the supervisor.
+//!
+//! The time oracle (`exonum-time`) follows the same pattern with the time of the validators
+//! as the value and [`QuorumMin`] as the aggregation function. However, it is not built
+//! on top of this crate yet: it shares the aggregation functions and extends the [`Config`],
+//! but keeps its own transactions, schema and API, which additionally support the time
+//! history and timers.
+//!
+//! [`ValueProvider`]: provider/trait.ValueProvider.html
+//! [`Aggregator`]: aggregation/trait.Aggregator.html
+//! [`Median`]: aggregation/struct.Median.html
+//! [`QuorumMin`]: aggregation/struct.QuorumMin.html
+//! [`ExactMatch`]: aggregation/struct.ExactMatch.html
+//! [`Config`]: config/struct.Config.html
+
+#![deny(
+    unsafe_code,
+    bare_trait_objects,
+    missing_docs,
+    missing_debug_implementations
+)]
+
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate exonum_derive;
+
+/// Aggregation of the reported values.
+pub mod aggregation;
+/// Node API.
+pub mod api;
+/// Service configuration.
+pub mod config;
+/// Protobuf generated structs.
+pub mod proto;
+/// Value providers.
+pub mod provider;
+/// Database schema.
+pub mod schema;
+/// Node transactions.
+pub mod transactions;
+
+use exonum::{
+    crypto::Hash,
+    runtime::{
+        rust::{
+            api::ServiceApiBuilder, AfterCommitContext, ArtifactProtobufSpec, CallContext,
+            RustArtifactId, Service, ServiceFactory,
+        },
+        BlockchainData, DispatcherError, ExecutionError,
+    },
+};
+use exonum_merkledb::{BinaryValue, Snapshot};
+
+use std::sync::Arc;
+
+use crate::{
+    aggregation::Aggregator,
+    config::{check_config, Config},
+    provider::ValueProvider,
+    schema::OracleSchema,
+    transactions::{OracleInterface, TxValue},
+};
+
+/// Oracle service reporting the values of the provider and consolidating them
+/// with the aggregator.
+#[derive(Debug, ServiceDispatcher)]
+#[service_dispatcher(implements(
+    "OracleInterface",
+    "exonum_supervisor::Configure<Params = Config>"
+))]
+pub struct OracleService<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value>,
+{
+    provider: Arc<P>,
+    aggregator: A,
+}
+
+impl<P, A> Service for OracleService<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value>,
+{
+    fn initialize(&self, context: CallContext<'_>, params: Vec<u8>) -> Result<(), ExecutionError> {
+        // Empty parameters correspond to the default configuration.
+        let config =
+            Config::from_bytes(params.into()).map_err(DispatcherError::malformed_arguments)?;
+        check_config(&context, &config)?;
+        OracleSchema::<_, P::Value>::new(context.service_data())
+            .config
+            .set(config);
+        Ok(())
+    }
+
+    fn state_hash(&self, data: BlockchainData<&dyn Snapshot>) -> Vec<Hash> {
+        OracleSchema::<_, P::Value>::new(data.for_executing_service()).state_hash()
+    }
+
+    /// Creates transaction after commit of the block.
+    fn after_commit(&self, context: AfterCommitContext<'_>) {
+        let broadcast = match context.broadcaster() {
+            Some(broadcast) => broadcast,
+            None => return,
+        };
+        let value = match self.provider.current_value() {
+            Some(value) => value,
+            None => return,
+        };
+
+        // There is no need to report the value once again if it has not changed,
+        // unless the reported value is going to become stale. The value is reported anew
+        // once half of its maximum age has passed.
+        let schema = OracleSchema::<_, P::Value>::new(context.service_data());
+        let service_key = context.service_key();
+        let is_reported = schema.validators_values.get(&service_key).as_ref() == Some(&value);
+        let config = schema.config.get().unwrap_or_default();
+        let refresh_config = Config {
+            max_age: config.max_age.map(|max_age| max_age / 2),
+            ..config
+        };
+        let next_height = context.height().next().0;
+        let is_fresh = schema
+            .validators_heights
+            .get(&service_key)
+            .map_or(false, |height| refresh_config.is_fresh(height, next_height));
+        if is_reported && is_fresh {
+            return;
+        }
+        broadcast.send(TxValue::new(value, context.height())).ok();
+    }
+
+    fn wire_api(&self, builder: &mut ServiceApiBuilder) {
+        api::PublicApi.wire::<P::Value>(builder);
+    }
+}
+
+/// Oracle service factory implementation.
+///
+/// Oracles importing different kinds of values should use distinct artifact names,
+/// e.g., `exchange-rates` and `bitcoin-headers`. The name of the aggregator is appended
+/// to the artifact name, e.g., `exchange-rates.median`, so the oracles consolidating
+/// the values differently have distinct artifacts.
+#[derive(Debug)]
+pub struct OracleServiceFactory<P, A> {
+    artifact_id: RustArtifactId,
+    provider: Arc<P>,
+    aggregator: A,
+}
+
+impl<P, A> OracleServiceFactory<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value> + Clone,
+{
+    /// Creates a new factory of the oracle services with the specified artifact name.
+    /// The name is followed by a dot and the name of the aggregator. The version
+    /// of the artifact coincides with the version of this crate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the artifact name contains illegal characters.
+    pub fn new(artifact_name: &str, provider: impl Into<Arc<P>>, aggregator: A) -> Self {
+        let artifact_id = format!(
+            "{}.{}:{}",
+            artifact_name,
+            A::NAME,
+            env!("CARGO_PKG_VERSION")
+        )
+        .parse()
+        .expect("Invalid oracle artifact name");
+        Self {
+            artifact_id,
+            provider: provider.into(),
+            aggregator,
+        }
+    }
+}
+
+impl<P, A> ServiceFactory for OracleServiceFactory<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value> + Clone,
+{
+    fn artifact_id(&self) -> RustArtifactId {
+        self.artifact_id.clone()
+    }
+
+    fn artifact_protobuf_spec(&self) -> ArtifactProtobufSpec {
+        ArtifactProtobufSpec::new(proto::PROTO_SOURCES.as_ref(), proto::INCLUDES.as_ref())
+    }
+
+    fn create_instance(&self) -> Box<dyn Service> {
+        Box::new(OracleService {
+            provider: Arc::clone(&self.provider),
+            aggregator: self.aggregator.clone(),
+        })
+    }
+}
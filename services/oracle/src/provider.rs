@@ -0,0 +1,110 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Providers of the values observed by the validator nodes.
+
+use exonum::api::ApiSchema;
+use exonum_merkledb::{BinaryValue, ObjectHash};
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+/// A helper trait that provides the node with a current value of the external data,
+/// e.g., an exchange rate or a header of the latest block in an external blockchain.
+pub trait ValueProvider: Send + Sync + fmt::Debug + 'static {
+    /// Type of the provided values.
+    type Value: BinaryValue
+        + ObjectHash
+        + Ord
+        + Clone
+        + fmt::Debug
+        + Serialize
+        + DeserializeOwned
+        + ApiSchema
+        + Send
+        + Sync
+        + 'static;
+
+    /// Returns the current value, or `None` if it is unavailable at the moment.
+    fn current_value(&self) -> Option<Self::Value>;
+}
+
+/// Mock value provider for service testing.
+///
+/// Clones of the provider control the same value as the original instance. Therefore,
+/// one may clone the provider to construct a service, while keeping the original instance
+/// to adjust the value reported to the validators.
+#[derive(Debug)]
+pub struct MockValueProvider<V> {
+    value: Arc<RwLock<Option<V>>>,
+}
+
+impl<V> Clone for MockValueProvider<V> {
+    fn clone(&self) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+        }
+    }
+}
+
+impl<V> Default for MockValueProvider<V> {
+    fn default() -> Self {
+        Self {
+            value: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+impl<V: Clone> MockValueProvider<V> {
+    /// Creates a mock provider with the specified value.
+    pub fn new(value: V) -> Self {
+        let provider = Self::default();
+        provider.set_value(value);
+        provider
+    }
+
+    /// Gets the value currently reported by the mock provider.
+    pub fn value(&self) -> Option<V> {
+        self.value.read().unwrap().clone()
+    }
+
+    /// Sets the value reported by the mock provider.
+    pub fn set_value(&self, value: V) {
+        *self.value.write().unwrap() = Some(value);
+    }
+}
+
+impl<V> ValueProvider for MockValueProvider<V>
+where
+    V: BinaryValue
+        + ObjectHash
+        + Ord
+        + Clone
+        + fmt::Debug
+        + Serialize
+        + DeserializeOwned
+        + ApiSchema
+        + Send
+        + Sync
+        + 'static,
+{
+    type Value = V;
+
+    fn current_value(&self) -> Option<V> {
+        self.value()
+    }
+}
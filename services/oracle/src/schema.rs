@@ -0,0 +1,98 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum::{
+    blockchain::ValidatorKeys,
+    crypto::{Hash, PublicKey},
+};
+use exonum_merkledb::{
+    access::{Access, RawAccessMut},
+    BinaryValue, Entry, ObjectHash, ProofMapIndex,
+};
+
+use crate::{
+    aggregation::{AggregationParams, Aggregator},
+    config::Config,
+};
+
+/// Oracle service database schema.
+#[derive(Debug, FromAccess)]
+pub struct OracleSchema<T: Access, V: BinaryValue + ObjectHash> {
+    /// The latest values reported by the validators. May contain keys corresponding
+    /// to past validators.
+    pub validators_values: ProofMapIndex<T::Base, PublicKey, V>,
+    /// Heights of the blocks in which the latest values of the validators were reported.
+    pub validators_heights: ProofMapIndex<T::Base, PublicKey, u64>,
+    /// The consolidated value.
+    pub value: Entry<T::Base, V>,
+    /// Configuration of the oracle.
+    pub config: Entry<T::Base, Config>,
+}
+
+impl<T: Access, V: BinaryValue + ObjectHash> OracleSchema<T, V> {
+    /// Returns hashes for stored tables.
+    pub fn state_hash(&self) -> Vec<Hash> {
+        vec![
+            self.validators_values.object_hash(),
+            self.validators_heights.object_hash(),
+            self.value.object_hash(),
+            self.config.object_hash(),
+        ]
+    }
+}
+
+impl<T, V> OracleSchema<T, V>
+where
+    T: Access,
+    T::Base: RawAccessMut,
+    V: BinaryValue + ObjectHash + Clone + PartialEq,
+{
+    /// Updates the value reported by the validator in the block at the given height
+    /// and recalculates the consolidated value from the fresh values.
+    pub(crate) fn update_value(
+        &mut self,
+        author: PublicKey,
+        value: V,
+        height: u64,
+        validator_keys: &[ValidatorKeys],
+        aggregator: &impl Aggregator<V>,
+    ) {
+        self.validators_values.put(&author, value);
+        self.validators_heights.put(&author, height);
+
+        // Values of the validators which are no longer active, as well as stale values,
+        // are not taken into account. If there are not enough fresh values,
+        // the consolidated value is not updated.
+        let config = self.config.get().unwrap_or_default();
+        let values = validator_keys
+            .iter()
+            .filter(|validator| {
+                let reported_height = self.validators_heights.get(&validator.service_key);
+                reported_height.map_or(false, |reported_height| {
+                    config.is_fresh(reported_height, height)
+                })
+            })
+            .filter_map(|validator| self.validators_values.get(&validator.service_key))
+            .collect::<Vec<_>>();
+        let mut params = AggregationParams::new(validator_keys.len());
+        if let Some(min_sources) = config.min_sources {
+            params = params.with_min_sources(min_sources as usize);
+        }
+        if let Some(value) = aggregator.aggregate(values, &params) {
+            if self.value.get().as_ref() != Some(&value) {
+                self.value.set(value);
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum::{helpers::Height, runtime::rust::CallContext};
+use exonum_merkledb::BinaryValue;
+use exonum_proto::ProtobufConvert;
+
+use crate::{
+    aggregation::Aggregator, proto, provider::ValueProvider, schema::OracleSchema, OracleService,
+};
+
+/// Common errors emitted by transactions during execution.
+#[derive(Debug, IntoExecutionError)]
+pub enum Error {
+    /// The sender of the transaction is not among the active validators.
+    UnknownSender = 0,
+    /// The reported value cannot be deserialized.
+    MalformedValue = 1,
+    /// The oracle configuration is invalid.
+    InvalidConfig = 2,
+}
+
+/// Transaction that is sent by the validator after the commit of the block.
+#[derive(Serialize, Deserialize, Debug, Clone, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::TxValue")]
+pub struct TxValue {
+    /// Value observed by the validator, serialized with the `BinaryValue` trait.
+    pub value: Vec<u8>,
+    /// Height of the latest committed block at the moment of the observation.
+    /// Makes the transactions reporting the same value at different heights distinct.
+    pub height: Height,
+}
+
+impl TxValue {
+    /// New `TxValue` transaction.
+    pub fn new(value: impl BinaryValue, height: Height) -> Self {
+        Self {
+            value: value.into_bytes(),
+            height,
+        }
+    }
+}
+
+/// Oracle service transaction.
+#[exonum_interface]
+pub trait OracleInterface {
+    /// Receives a new value from one of validators.
+    fn report(&self, ctx: CallContext<'_>, arg: TxValue) -> Result<(), Error>;
+}
+
+impl<P, A> OracleInterface for OracleService<P, A>
+where
+    P: ValueProvider,
+    A: Aggregator<P::Value>,
+{
+    fn report(&self, context: CallContext<'_>, arg: TxValue) -> Result<(), Error> {
+        let author = context.caller().author().ok_or(Error::UnknownSender)?;
+        // Check that the transaction is signed by a validator.
+        let core_schema = context.data().for_core();
+        core_schema
+            .validator_id(author)
+            .ok_or(Error::UnknownSender)?;
+
+        let value = P::Value::from_bytes(arg.value.into()).map_err(|_| Error::MalformedValue)?;
+        let validator_keys = core_schema.consensus_config().validator_keys;
+        // The value is reported in the block which is being created.
+        let height = core_schema.height().next().0;
+        OracleSchema::new(context.service_data()).update_value(
+            author,
+            value,
+            height,
+            &validator_keys,
+            &self.aggregator,
+        );
+        Ok(())
+    }
+}
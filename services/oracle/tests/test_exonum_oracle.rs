@@ -0,0 +1,245 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[macro_use]
+extern crate pretty_assertions;
+
+use exonum::{
+    blockchain::ExecutionErrorKind,
+    crypto::gen_keypair,
+    helpers::Height,
+    messages::Verified,
+    runtime::{
+        rust::{ServiceFactory, Transaction},
+        AnyTx, InstanceId, SnapshotExt,
+    },
+};
+use exonum_merkledb::{access::Access, Snapshot};
+use exonum_supervisor::{ConfigPropose, SimpleSupervisor};
+use exonum_testkit::{ApiKind, InstanceCollection, TestKit, TestKitBuilder, TestNode};
+
+use exonum_oracle::{
+    aggregation::{Aggregator, ExactMatch, Median, QuorumMin},
+    api::ValidatorValue,
+    config::Config,
+    provider::MockValueProvider,
+    schema::OracleSchema,
+    transactions::{Error, TxValue},
+    OracleServiceFactory,
+};
+
+const INSTANCE_ID: InstanceId = 120;
+const INSTANCE_NAME: &str = "exchange-rates";
+
+fn create_testkit<A>(
+    validator_count: u16,
+    provider: MockValueProvider<u64>,
+    aggregator: A,
+) -> TestKit
+where
+    A: Aggregator<u64> + Clone,
+{
+    create_testkit_with_config(validator_count, provider, aggregator, Config::default())
+}
+
+fn create_testkit_with_config<A>(
+    validator_count: u16,
+    provider: MockValueProvider<u64>,
+    aggregator: A,
+    config: Config,
+) -> TestKit
+where
+    A: Aggregator<u64> + Clone,
+{
+    let factory = OracleServiceFactory::new("exchange-rates", provider, aggregator);
+    TestKitBuilder::validator()
+        .with_validators(validator_count)
+        .with_rust_service(InstanceCollection::new(factory).with_instance(
+            INSTANCE_ID,
+            INSTANCE_NAME,
+            config,
+        ))
+        .with_rust_service(SimpleSupervisor::new())
+        .create()
+}
+
+fn get_schema<'a>(snapshot: &'a dyn Snapshot) -> OracleSchema<impl Access + 'a, u64> {
+    OracleSchema::new(snapshot.for_service(INSTANCE_NAME).unwrap())
+}
+
+fn tx_value(validator: &TestNode, value: u64, height: Height) -> Verified<AnyTx> {
+    let (pub_key, sec_key) = validator.service_keypair();
+    TxValue::new(value, height).sign(INSTANCE_ID, pub_key, &sec_key)
+}
+
+#[test]
+fn test_median_of_reported_values() {
+    let mut testkit = create_testkit(4, MockValueProvider::default(), Median);
+    let validators = testkit.network().validators().to_vec();
+
+    testkit.create_block_with_transactions(vec![
+        tx_value(&validators[0], 100, Height(0)),
+        tx_value(&validators[1], 110, Height(0)),
+    ]);
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), None);
+
+    testkit.create_block_with_transaction(tx_value(&validators[2], 90, Height(1)));
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(100));
+
+    testkit.create_block_with_transactions(vec![
+        tx_value(&validators[3], 120, Height(2)),
+        tx_value(&validators[0], 130, Height(2)),
+    ]);
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(110));
+
+    let validators_values: Vec<ValidatorValue<u64>> = testkit
+        .api()
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .get("v1/validators_values")
+        .unwrap();
+    assert_eq!(
+        validators_values
+            .into_iter()
+            .map(|validator| validator.value)
+            .collect::<Vec<_>>(),
+        vec![Some(130), Some(110), Some(90), Some(120)]
+    );
+}
+
+#[test]
+fn test_exact_match_of_reported_values() {
+    let mut testkit = create_testkit(4, MockValueProvider::default(), ExactMatch);
+    let validators = testkit.network().validators().to_vec();
+
+    testkit.create_block_with_transactions(vec![
+        tx_value(&validators[0], 5, Height(0)),
+        tx_value(&validators[1], 6, Height(0)),
+        tx_value(&validators[2], 5, Height(0)),
+    ]);
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), None);
+
+    testkit.create_block_with_transaction(tx_value(&validators[3], 5, Height(1)));
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(5));
+}
+
+#[test]
+fn test_transaction_from_non_validator() {
+    let mut testkit = create_testkit(1, MockValueProvider::default(), Median);
+
+    let (pub_key, sec_key) = gen_keypair();
+    let tx = TxValue::new(100_u64, Height(0)).sign(INSTANCE_ID, pub_key, &sec_key);
+    let block = testkit.create_block_with_transaction(tx);
+    assert_eq!(
+        block[0].status().unwrap_err().kind,
+        ExecutionErrorKind::service(Error::UnknownSender as u8)
+    );
+
+    let snapshot = testkit.snapshot();
+    let schema = get_schema(&snapshot);
+    assert!(schema.value.get().is_none());
+    assert!(schema.validators_values.get(&pub_key).is_none());
+}
+
+#[test]
+fn test_mock_provider() {
+    let provider = MockValueProvider::new(42);
+    let mut testkit = create_testkit(1, provider.clone(), Median);
+
+    testkit.create_blocks_until(Height(2));
+    let value: Option<u64> = testkit
+        .api()
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .get("v1/value")
+        .unwrap();
+    assert_eq!(value, Some(42));
+
+    provider.set_value(40);
+    testkit.create_blocks_until(Height(4));
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(40));
+}
+
+#[test]
+fn test_stale_values() {
+    let config = Config {
+        max_age: Some(1),
+        ..Config::default()
+    };
+    let mut testkit = create_testkit_with_config(4, MockValueProvider::default(), Median, config);
+    let validators = testkit.network().validators().to_vec();
+
+    testkit.create_block_with_transactions(vec![
+        tx_value(&validators[0], 100, Height(0)),
+        tx_value(&validators[1], 110, Height(0)),
+        tx_value(&validators[2], 90, Height(0)),
+    ]);
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(100));
+
+    // Values reported more than `max_age` blocks ago are not taken into account,
+    // so the single fresh value is not enough to update the consolidated value.
+    testkit.create_blocks_until(Height(3));
+    testkit.create_block_with_transaction(tx_value(&validators[3], 50, Height(3)));
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(100));
+
+    testkit.create_block_with_transactions(vec![
+        tx_value(&validators[0], 60, Height(4)),
+        tx_value(&validators[1], 70, Height(4)),
+    ]);
+    assert_eq!(get_schema(&testkit.snapshot()).value.get(), Some(60));
+}
+
+#[test]
+fn test_change_config() {
+    let mut testkit = create_testkit(1, MockValueProvider::default(), Median);
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+
+    let new_config = Config {
+        max_age: Some(10),
+        min_sources: Some(1),
+    };
+    let cfg_change_height = Height(5);
+    testkit.create_block_with_transaction(
+        ConfigPropose::new(0, cfg_change_height)
+            .service_config(INSTANCE_ID, new_config.clone())
+            .sign_for_supervisor(pub_key, &sec_key),
+    );
+    testkit.create_blocks_until(cfg_change_height);
+
+    let config: Config = testkit
+        .api()
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .get("v1/config")
+        .unwrap();
+    assert_eq!(config, new_config);
+
+    // Configuration requiring more sources than validators is discarded.
+    let invalid_config = Config {
+        min_sources: Some(2),
+        ..Config::default()
+    };
+    let block = testkit.create_block_with_transaction(
+        ConfigPropose::new(1, cfg_change_height.next())
+            .service_config(INSTANCE_ID, invalid_config)
+            .sign_for_supervisor(pub_key, &sec_key),
+    );
+    assert!(block[0].status().is_err());
+}
+
+#[test]
+fn test_artifact_name_contains_aggregator() {
+    let provider = MockValueProvider::<u64>::default();
+    let median = OracleServiceFactory::new("exchange-rates", provider.clone(), Median);
+    let quorum_min = OracleServiceFactory::new("exchange-rates", provider, QuorumMin);
+    assert_eq!(median.artifact_id().name, "exchange-rates.median");
+    assert_eq!(quorum_min.artifact_id().name, "exchange-rates.quorum-min");
+}
@@ -15,18 +15,57 @@
 use exonum::{
     blockchain::ConsensusConfig,
     crypto::Hash,
-    runtime::rust::{
-        api::{self, ServiceApiBuilder, ServiceApiState},
-        Transaction,
+    impl_api_schema,
+    runtime::{
+        rust::{
+            api::{self, ServiceApiBuilder, ServiceApiState},
+            Transaction,
+        },
+        InstanceId,
     },
 };
 use failure::Fail;
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::HashSet;
 
 use super::{
-    schema::Schema, transactions::SupervisorInterface, ConfigProposalWithHash, ConfigPropose,
-    ConfigVote, DeployRequest,
+    schema::Schema, transactions::SupervisorInterface, vacant_instance_id, ConfigCancel,
+    ConfigProposalRecord, ConfigProposalWithHash, ConfigPropose, ConfigRejection, ConfigVote,
+    DeployPlan, DeployRequest, SupervisorConfig,
 };
 
+/// Maximum number of records returned by a single proposal history request.
+pub const MAX_PROPOSAL_HISTORY_RECORDS: u64 = 100;
+
+/// Query for the page of the config proposal history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProposalHistoryQuery {
+    /// Index of the first record in the history; `0` if not specified.
+    pub start: Option<u64>,
+    /// Maximum number of records; `MAX_PROPOSAL_HISTORY_RECORDS` if not specified.
+    pub count: Option<u64>,
+}
+
+impl_api_schema!(ProposalHistoryQuery {
+    start: Option<u64>,
+    count: Option<u64>,
+});
+
+/// Page of the config proposal history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposalHistory {
+    /// Total number of records in the history.
+    pub total: u64,
+    /// Records starting from the requested index, in the order of their outcomes.
+    pub records: Vec<ConfigProposalRecord>,
+}
+
+impl_api_schema!(ProposalHistory {
+    total: u64,
+    records: Vec<ConfigProposalRecord>,
+});
+
 /// Private API specification of the supervisor service.
 pub trait PrivateApi {
     /// Error type for the current API implementation.
@@ -36,6 +75,10 @@ pub trait PrivateApi {
     /// by the current node, and returns its hash.
     fn deploy_artifact(&self, artifact: DeployRequest) -> Result<Hash, Self::Error>;
 
+    /// Creates and broadcasts the `DeployPlan` transaction, which is signed
+    /// by the current node, and returns its hash.
+    fn request_deploy_plan(&self, plan: DeployPlan) -> Result<Hash, Self::Error>;
+
     /// Creates and broadcasts the `ConfigPropose` transaction, which is signed
     /// by the current node, and returns its hash.
     fn propose_config(&self, proposal: ConfigPropose) -> Result<Hash, Self::Error>;
@@ -44,6 +87,14 @@ pub trait PrivateApi {
     /// by the current node, and returns its hash.
     fn confirm_config(&self, vote: ConfigVote) -> Result<Hash, Self::Error>;
 
+    /// Creates and broadcasts the `ConfigRejection` transaction, which is signed
+    /// by the current node, and returns its hash.
+    fn reject_config(&self, rejection: ConfigRejection) -> Result<Hash, Self::Error>;
+
+    /// Creates and broadcasts the `ConfigCancel` transaction, which is signed
+    /// by the current node, and returns its hash.
+    fn cancel_config(&self, cancel: ConfigCancel) -> Result<Hash, Self::Error>;
+
     /// Returns the number of processed configurations.
     fn configuration_number(&self) -> Result<u64, Self::Error>;
 }
@@ -55,6 +106,20 @@ pub trait PublicApi {
     fn consensus_config(&self) -> Result<ConsensusConfig, Self::Error>;
     /// Returns an pending propose config change.
     fn config_proposal(&self) -> Result<Option<ConfigProposalWithHash>, Self::Error>;
+    /// Returns a pending deploy plan.
+    fn deploy_plan(&self) -> Result<Option<DeployPlan>, Self::Error>;
+    /// Returns an identifier which will be assigned to the next service instance
+    /// started without an explicit identifier.
+    fn next_instance_id(&self) -> Result<InstanceId, Self::Error>;
+    /// Returns a page of the history of the finished config proposals.
+    fn config_proposal_history(
+        &self,
+        query: ProposalHistoryQuery,
+    ) -> Result<ProposalHistory, Self::Error>;
+    /// Returns the current configuration of the supervisor. If the `mode` of the config
+    /// is set, it overrides the mode defined by the type of the supervisor service;
+    /// otherwise, the latter is used.
+    fn supervisor_config(&self) -> Result<SupervisorConfig, Self::Error>;
 }
 
 struct ApiImpl<'a>(&'a ServiceApiState<'a>);
@@ -80,6 +145,10 @@ impl PrivateApi for ApiImpl<'_> {
         self.broadcast_transaction(artifact).map_err(From::from)
     }
 
+    fn request_deploy_plan(&self, plan: DeployPlan) -> Result<Hash, Self::Error> {
+        self.broadcast_transaction(plan).map_err(From::from)
+    }
+
     fn propose_config(&self, proposal: ConfigPropose) -> Result<Hash, Self::Error> {
         self.broadcast_transaction(proposal).map_err(From::from)
     }
@@ -88,6 +157,14 @@ impl PrivateApi for ApiImpl<'_> {
         self.broadcast_transaction(vote).map_err(From::from)
     }
 
+    fn reject_config(&self, rejection: ConfigRejection) -> Result<Hash, Self::Error> {
+        self.broadcast_transaction(rejection).map_err(From::from)
+    }
+
+    fn cancel_config(&self, cancel: ConfigCancel) -> Result<Hash, Self::Error> {
+        self.broadcast_transaction(cancel).map_err(From::from)
+    }
+
     fn configuration_number(&self) -> Result<u64, Self::Error> {
         let configuration_number = Schema::new(self.0.service_data()).get_configuration_number();
 
@@ -105,6 +182,44 @@ impl PublicApi for ApiImpl<'_> {
     fn config_proposal(&self) -> Result<Option<ConfigProposalWithHash>, Self::Error> {
         Ok(Schema::new(self.0.service_data()).pending_proposal.get())
     }
+
+    fn deploy_plan(&self) -> Result<Option<DeployPlan>, Self::Error> {
+        Ok(Schema::new(self.0.service_data()).pending_deploy_plan.get())
+    }
+
+    fn next_instance_id(&self) -> Result<InstanceId, Self::Error> {
+        let schema = Schema::new(self.0.service_data());
+        let dispatcher_schema = self.0.data().for_dispatcher();
+        Ok(vacant_instance_id(
+            &schema,
+            &dispatcher_schema,
+            &HashSet::new(),
+        ))
+    }
+
+    fn config_proposal_history(
+        &self,
+        query: ProposalHistoryQuery,
+    ) -> Result<ProposalHistory, Self::Error> {
+        let count = query.count.unwrap_or(MAX_PROPOSAL_HISTORY_RECORDS);
+        if count > MAX_PROPOSAL_HISTORY_RECORDS {
+            return Err(api::Error::BadRequest(format!(
+                "Max number of records in the proposal history page is {}",
+                MAX_PROPOSAL_HISTORY_RECORDS
+            )));
+        }
+
+        let history = Schema::new(self.0.service_data()).proposal_history;
+        let start = query.start.unwrap_or(0);
+        Ok(ProposalHistory {
+            total: history.len(),
+            records: history.iter_from(start).take(count as usize).collect(),
+        })
+    }
+
+    fn supervisor_config(&self) -> Result<SupervisorConfig, Self::Error> {
+        Ok(Schema::new(self.0.service_data()).supervisor_config())
+    }
 }
 
 pub fn wire(builder: &mut ServiceApiBuilder) {
@@ -113,14 +228,26 @@ pub fn wire(builder: &mut ServiceApiBuilder) {
         .endpoint_mut("deploy-artifact", |state, query| {
             ApiImpl(state).deploy_artifact(query)
         })
+        .endpoint_mut("request-deploy-plan", |state, query| {
+            ApiImpl(state).request_deploy_plan(query)
+        })
         .endpoint_mut("propose-config", |state, query| {
             ApiImpl(state).propose_config(query)
         })
         .endpoint_mut("confirm-config", |state, query| {
             ApiImpl(state).confirm_config(query)
         })
+        .endpoint_mut("reject-config", |state, query| {
+            ApiImpl(state).reject_config(query)
+        })
+        .endpoint_mut("cancel-config", |state, query| {
+            ApiImpl(state).cancel_config(query)
+        })
         .endpoint("configuration-number", |state, _query: ()| {
             ApiImpl(state).configuration_number()
+        })
+        .endpoint("config-proposal-history", |state, query| {
+            ApiImpl(state).config_proposal_history(query)
         });
     builder
         .public_scope()
@@ -129,5 +256,17 @@ pub fn wire(builder: &mut ServiceApiBuilder) {
         })
         .endpoint("config-proposal", |state, _query: ()| {
             ApiImpl(state).config_proposal()
+        })
+        .endpoint("deploy-plan", |state, _query: ()| {
+            ApiImpl(state).deploy_plan()
+        })
+        .endpoint("next-instance-id", |state, _query: ()| {
+            ApiImpl(state).next_instance_id()
+        })
+        .endpoint("config-proposal-history", |state, query| {
+            ApiImpl(state).config_proposal_history(query)
+        })
+        .endpoint("supervisor-config", |state, _query: ()| {
+            ApiImpl(state).supervisor_config()
         });
 }
@@ -47,4 +47,14 @@ pub enum Error {
     UnknownArtifact = 13,
     /// Incorrect configuration number.
     IncorrectConfigurationNumber = 14,
+    /// Transaction author is not the author of the configuration change proposal.
+    NotProposalAuthor = 15,
+    /// Active deploy plan already exists.
+    DeployPlanExists = 16,
+    /// Malformed deploy plan.
+    MalformedDeployPlan = 17,
+    /// Instance with the given identifier already exists.
+    InstanceIdExists = 18,
+    /// Malformed supervisor configuration.
+    MalformedSupervisorConfig = 19,
 }
@@ -13,11 +13,14 @@
 // limitations under the License.
 
 pub use self::{
+    api::{ProposalHistory, ProposalHistoryQuery, MAX_PROPOSAL_HISTORY_RECORDS},
     configure::{Configure, ConfigureCall, CONFIGURE_INTERFACE_NAME},
     errors::Error,
     proto_structures::{
-        ConfigChange, ConfigProposalWithHash, ConfigPropose, ConfigVote, DeployConfirmation,
-        DeployRequest, ServiceConfig, StartService,
+        ApprovalPolicy, ApprovalThreshold, ConfigCancel, ConfigChange, ConfigProposalOutcome,
+        ConfigProposalRecord, ConfigProposalWithHash, ConfigPropose, ConfigRejection, ConfigVote,
+        DeployConfirmation, DeployPlan, DeployRequest, InstancePolicy, ServiceConfig, StartService,
+        SupervisorConfig, ValidatorWeight,
     },
     schema::Schema,
     transactions::SupervisorInterface,
@@ -26,13 +29,22 @@ pub use self::{
 use exonum::{
     blockchain::InstanceCollection,
     crypto::Hash,
+    helpers::{Height, ValidateInput},
     runtime::{
         rust::{api::ServiceApiBuilder, AfterCommitContext, Broadcaster, CallContext, Service},
-        BlockchainData, InstanceId, SUPERVISOR_INSTANCE_ID,
+        BlockchainData, DispatcherError, DispatcherSchema, ExecutionError, InstanceId,
+        SUPERVISOR_INSTANCE_ID,
     },
 };
 use exonum_derive::*;
-use exonum_merkledb::Snapshot;
+use exonum_merkledb::{
+    access::{Access, AsReadonly},
+    BinaryValue, Snapshot,
+};
+
+use std::collections::HashSet;
+
+use crate::mode::ValidatorWeights;
 
 pub mod mode;
 
@@ -46,9 +58,13 @@ mod schema;
 mod transactions;
 
 /// Decentralized supervisor.
+///
+/// The decentralized mode is used until the `mode` of the supervisor config is set.
 pub type DecentralizedSupervisor = Supervisor<mode::Decentralized>;
 
 /// Simple supervisor.
+///
+/// The simple mode is used until the `mode` of the supervisor config is set.
 pub type SimpleSupervisor = Supervisor<mode::Simple>;
 
 /// Returns the `Supervisor` entity name.
@@ -63,6 +79,15 @@ const NOT_SUPERVISOR_MSG: &str = "`Supervisor` is installed as a non-privileged
 /// Applies configuration changes.
 /// Upon any failure, execution of this method stops and `Err(())` is returned.
 fn update_configs(context: &mut CallContext<'_>, changes: Vec<ConfigChange>) -> Result<(), ()> {
+    // IDs explicitly requested for new instances shouldn't be assigned to other instances.
+    let reserved_ids = changes
+        .iter()
+        .filter_map(|change| match change {
+            ConfigChange::StartService(start_service) => start_service.instance_id,
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
     for change in changes.into_iter() {
         match change {
             ConfigChange::Consensus(config) => {
@@ -101,7 +126,10 @@ fn update_configs(context: &mut CallContext<'_>, changes: Vec<ConfigChange>) ->
                     start_service.artifact
                 );
 
-                let id = assign_instance_id(context);
+                let id = match start_service.instance_id {
+                    Some(id) => id,
+                    None => assign_instance_id(context, &reserved_ids),
+                };
                 let (instance_spec, config) = start_service.into_parts(id);
 
                 context
@@ -115,42 +143,64 @@ fn update_configs(context: &mut CallContext<'_>, changes: Vec<ConfigChange>) ->
     Ok(())
 }
 
-/// Assigns the instance ID for a new service, initializing the schema `vacant_instance_id`
-/// entry if needed.
-fn assign_instance_id(context: &CallContext<'_>) -> InstanceId {
-    let mut schema = Schema::new(context.service_data());
-    match schema.assign_instance_id() {
-        Some(id) => id,
-        None => {
-            // Instance ID entry is not initialized, do it now.
-            // We have to do it lazy, since dispatcher doesn't know the amount
-            // of builtin instances until the genesis block is committed, and
-            // `before_commit` hook is not invoked for services at the genesis
-            // block.
-
-            // ID for the new instance is next to the highest builtin ID to avoid
-            // overlap if builtin identifiers space is sparse.
-            let dispatcher_schema = context.data().for_dispatcher();
-            let builtin_instances = dispatcher_schema.running_instances();
-
-            let new_instance_id = builtin_instances
-                .values()
-                .map(|spec| spec.id)
-                .max()
-                .unwrap_or(SUPERVISOR_INSTANCE_ID)
-                + 1;
-
-            // We're going to use ID obtained above, so the vacant ID is next to it.
-            let vacant_instance_id = new_instance_id + 1;
-            schema.vacant_instance_id.set(vacant_instance_id);
-
-            new_instance_id
-        }
+/// Returns the ID which will be assigned to the next service instance started without
+/// an explicit ID. IDs of the existing instances and the `reserved` IDs are skipped.
+fn vacant_instance_id<T, D>(
+    schema: &Schema<T>,
+    dispatcher_schema: &DispatcherSchema<D>,
+    reserved: &HashSet<InstanceId>,
+) -> InstanceId
+where
+    T: Access,
+    D: AsReadonly,
+{
+    let mut id = schema.vacant_instance_id.get().unwrap_or_else(|| {
+        // Instance ID entry is not initialized, it will be done on the first assignment.
+        // We have to do it lazy, since dispatcher doesn't know the amount
+        // of builtin instances until the genesis block is committed, and
+        // `before_commit` hook is not invoked for services at the genesis
+        // block.
+
+        // ID for the new instance is next to the highest builtin ID to avoid
+        // overlap if builtin identifiers space is sparse.
+        dispatcher_schema
+            .running_instances()
+            .values()
+            .map(|spec| spec.id)
+            .max()
+            .unwrap_or(SUPERVISOR_INSTANCE_ID)
+            + 1
+    });
+
+    // Skip IDs explicitly requested for other instances.
+    while reserved.contains(&id) || dispatcher_schema.get_instance(id).is_some() {
+        id += 1;
     }
+    id
 }
 
+/// Assigns the instance ID for a new service, updating the schema `vacant_instance_id`
+/// entry.
+fn assign_instance_id(context: &CallContext<'_>, reserved: &HashSet<InstanceId>) -> InstanceId {
+    let mut schema = Schema::new(context.service_data());
+    let id = vacant_instance_id(&schema, &context.data().for_dispatcher(), reserved);
+    // We're going to use ID obtained above, so the vacant ID is next to it.
+    schema.vacant_instance_id.set(id + 1);
+    id
+}
+
+/// Supervisor service.
+///
+/// The `Mode` type parameter defines only the initial mode of the supervisor.
+/// If the `mode` field of the [`SupervisorConfig`] stored in the supervisor schema is set,
+/// it overrides `Mode`, so the effective mode can differ from the type of the service.
+///
+/// [`SupervisorConfig`]: struct.SupervisorConfig.html
 #[derive(Debug, Default, Clone, ServiceFactory, ServiceDispatcher)]
-#[service_dispatcher(implements("transactions::SupervisorInterface"))]
+#[service_dispatcher(implements(
+    "transactions::SupervisorInterface",
+    "Configure<Params = SupervisorConfig>"
+))]
 #[service_factory(
     proto_sources = "proto",
     artifact_name = "exonum-supervisor",
@@ -179,6 +229,109 @@ where
     pub fn construct(&self) -> Box<Self> {
         Box::new(Self::new())
     }
+
+    /// Returns the supervisor instance initialized with the given configuration.
+    /// Instances created via `From<Supervisor>` use the default configuration.
+    pub fn builtin_instance(config: SupervisorConfig) -> InstanceCollection {
+        InstanceCollection::new(Self::new()).with_instance(
+            SUPERVISOR_INSTANCE_ID,
+            Self::NAME,
+            config,
+        )
+    }
+
+    /// Drives the pending deploy plan. The plan is discarded if its deadline is exceeded
+    /// or one of its artifacts cannot be deployed. Once all the artifacts are deployed,
+    /// the configuration changes of the plan are scheduled for application at the next height.
+    ///
+    /// Artifacts are considered deployed only after they are registered in the dispatcher,
+    /// which requires deploy confirmations from all validators, so the plan doesn't need
+    /// to count `DeployConfirmation`s itself; the quorum of the supervisor mode is only
+    /// used to approve the plan.
+    fn process_deploy_plan(&self, context: &mut CallContext<'_>, height: Height) {
+        let mut schema = Schema::new(context.service_data());
+        let plan = match schema.pending_deploy_plan.get() {
+            Some(plan) => plan,
+            None => return,
+        };
+
+        if let Some(application_height) = schema.deploy_plan_application_height.get() {
+            if application_height < height.0 {
+                // The application was rolled back, otherwise the plan would have been removed.
+                log::error!("Deploy plan application failed: {:?}", plan);
+                schema.fail_deploy_plan(&plan, Height(application_height).next());
+                return;
+            }
+
+            // Remove the plan before applying its changes.
+            // If the changes application will fail, this entry will be restored due to rollback,
+            // and the plan will be marked as failed at the next height.
+            schema.deploy_plan_requests.remove(&plan);
+            schema.pending_deploy_plan.remove();
+            schema.deploy_plan_application_height.remove();
+            drop(schema);
+
+            if let Err(e) = self.verify_config_changeset(context, &plan.changes) {
+                log::warn!("Discarded deploy plan with incorrect changes: {}", e);
+                Schema::new(context.service_data()).fail_deploy_plan(&plan, height.next());
+                return;
+            }
+
+            log::info!("Deploy plan has been completed: {:?}", plan);
+            if update_configs(context, plan.changes).is_err() {
+                // Panic will cause changes to be rolled back.
+                // TODO: Return error instead of panic once the signature
+                // of `before_commit` will allow it. [ECR-3811]
+                panic!("Deploy plan application failed")
+            }
+            return;
+        }
+
+        if plan.deadline_height < height {
+            log::trace!("Removed outdated deploy plan {:?}", plan);
+            schema.discard_deploy_plan(&plan);
+            return;
+        }
+
+        let dispatcher_data = context.data().for_dispatcher();
+        let mut all_deployed = true;
+        for request in &plan.artifacts {
+            // Artifacts are compared by the full identifiers, since an artifact with
+            // the same name may be deployed in another runtime.
+            match dispatcher_data.get_artifact(&request.artifact.name) {
+                Some((spec, _)) if spec.artifact == request.artifact => continue,
+                Some((spec, _)) => {
+                    log::warn!(
+                        "Discarded deploy plan, artifact {:?} conflicts with the deployed \
+                         artifact {:?}",
+                        request.artifact,
+                        spec.artifact
+                    );
+                    schema.discard_deploy_plan(&plan);
+                    return;
+                }
+                None => {}
+            }
+            if !schema.pending_deployments.contains(&request.artifact) {
+                // Deployment request was removed without registering the artifact.
+                log::warn!(
+                    "Discarded deploy plan, artifact {:?} was not deployed",
+                    request.artifact
+                );
+                schema.discard_deploy_plan(&plan);
+                return;
+            }
+            all_deployed = false;
+        }
+        if !all_deployed {
+            return;
+        }
+
+        // The changes are applied separately from the other steps of the plan, so that
+        // a failure of their application can be detected at the next height.
+        log::trace!("All artifacts of the deploy plan are deployed {:?}", plan);
+        schema.deploy_plan_application_height.set(height.next().0);
+    }
 }
 
 impl<Mode> Service for Supervisor<Mode>
@@ -189,10 +342,20 @@ where
         Schema::new(data.for_executing_service()).state_hash()
     }
 
+    fn initialize(&self, context: CallContext<'_>, params: Vec<u8>) -> Result<(), ExecutionError> {
+        // Empty parameters correspond to the default configuration.
+        let config = SupervisorConfig::from_bytes(params.into())
+            .map_err(DispatcherError::malformed_arguments)?;
+        config.validate()?;
+        Schema::new(context.service_data())
+            .supervisor_config
+            .set(config);
+        Ok(())
+    }
+
     fn before_commit(&self, mut context: CallContext<'_>) {
         let mut schema = Schema::new(context.service_data());
         let core_schema = context.data().for_core();
-        let validator_count = core_schema.consensus_config().validator_keys.len();
         let height = core_schema.height();
 
         // Removes pending deploy requests for which deadline was exceeded.
@@ -212,14 +375,14 @@ where
             if entry.config_propose.actual_from <= height {
                 // Remove pending config proposal for which deadline was exceeded.
                 log::trace!("Removed outdated config proposal");
-                schema.pending_proposal.remove();
+                schema.finalize_pending_proposal(ConfigProposalOutcome::Expired, height.next());
             } else if entry.config_propose.actual_from == height.next() {
                 // Config should be applied at the next height.
-                if Mode::config_approved(
-                    &entry.propose_hash,
-                    &schema.config_confirms,
-                    validator_count,
-                ) {
+                let config = schema.supervisor_config();
+                let weights =
+                    ValidatorWeights::new(&core_schema.consensus_config().validator_keys, &config);
+                let votes = schema.config_confirms.confirmed_by_all(&entry.propose_hash);
+                if Mode::config_approved(&config, &entry.config_propose.changes, &votes, &weights) {
                     log::info!(
                         "New configuration has been accepted: {:?}",
                         entry.config_propose
@@ -228,7 +391,11 @@ where
                     // Remove config from proposals.
                     // If the config update will fail, this entry will be restored due to rollback.
                     // However, it won't be actual anymore and will be removed at the next height.
-                    schema.pending_proposal.remove();
+                    schema.finalize_pending_proposal(ConfigProposalOutcome::Applied, height.next());
+                    // The application of the deploy plan scheduled for this height is postponed.
+                    if schema.deploy_plan_application_height.get() == Some(height.0) {
+                        schema.deploy_plan_application_height.set(height.next().0);
+                    }
                     drop(schema);
 
                     // Perform the application of configs.
@@ -240,9 +407,15 @@ where
                         // of `before_commit` will allow it. [ECR-3811]
                         panic!("Config update failed")
                     }
+
+                    // The pending deploy plan is processed at the next height, so that
+                    // a failure of its application won't roll back the applied config.
+                    return;
                 }
             }
         }
+
+        self.process_deploy_plan(&mut context, height);
     }
 
     /// Sends confirmation transaction for unconfirmed deployment requests.
@@ -292,6 +465,44 @@ where
     }
 }
 
+impl<Mode> Configure for Supervisor<Mode>
+where
+    Mode: mode::SupervisorMode,
+{
+    type Params = SupervisorConfig;
+
+    fn verify_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+        params.validate()
+    }
+
+    fn apply_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+        log::info!("Supervisor configuration has been updated: {:?}", params);
+        // New policies are used for the requests and proposals starting from the next one,
+        // including votes for the already registered ones.
+        Schema::new(context.service_data())
+            .supervisor_config
+            .set(params);
+        Ok(())
+    }
+}
+
 impl<Mode> From<Supervisor<Mode>> for InstanceCollection
 where
     Mode: mode::SupervisorMode,
@@ -20,12 +20,27 @@
 //!   by the validator.
 //! - Decentralized mode. Within decentralized mode, deploy requests
 //!   and config proposals should be approved by at least (2/3+1) validators.
+//!   Config proposals are dropped as soon as more than 1/3 of validators reject them.
+//!
+//! The mode defines the default approval policy. Policies for the certain kinds of
+//! changes and weights of the validator votes can be overridden in the [`SupervisorConfig`].
+//!
+//! The type of the supervisor service defines only the initial mode. The mode can be switched
+//! at runtime by setting the `mode` field of the [`SupervisorConfig`], which is changed
+//! through the configuration change proposal targeting the supervisor itself.
+//!
+//! [`SupervisorConfig`]: ../struct.SupervisorConfig.html
 
-use exonum::helpers::byzantine_quorum;
-use exonum_crypto::Hash;
-use exonum_merkledb::access::Access;
+use exonum::{
+    api::{description, ApiSchema, Schema},
+    blockchain::ValidatorKeys,
+    crypto::PublicKey,
+};
+use serde_derive::{Deserialize, Serialize};
 
-use super::{multisig::MultisigIndex, DeployRequest};
+use std::collections::BTreeMap;
+
+use super::{ApprovalPolicy, ApprovalThreshold, ConfigChange, SupervisorConfig};
 
 /// Simple supervisor mode: to deploy service one have to send
 /// one request to any of the validators.
@@ -38,62 +53,203 @@ pub struct Simple;
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Decentralized;
 
-/// Trait encapsulating the decision making logic of the supervisor.
-pub trait SupervisorMode: std::fmt::Debug + Send + Sync + Copy + 'static {
-    /// Checks whether deploy should be performed within the network.
-    fn deploy_approved<T: Access>(
-        deploy: &DeployRequest,
-        deploy_requests: &MultisigIndex<T, DeployRequest>,
-        validators: usize,
-    ) -> bool;
-
-    /// Checks whether config can be applied for the network.
-    fn config_approved<T: Access>(
-        config_hash: &Hash,
-        config_confirms: &MultisigIndex<T, Hash>,
-        validators: usize,
-    ) -> bool;
+/// Supervisor mode which can be set at runtime in the supervisor config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModeKind {
+    /// Simple mode.
+    Simple,
+    /// Decentralized mode.
+    Decentralized,
 }
 
-impl SupervisorMode for Simple {
-    fn deploy_approved<T: Access>(
-        deploy: &DeployRequest,
-        deploy_requests: &MultisigIndex<T, DeployRequest>,
-        _validators: usize,
-    ) -> bool {
-        // For simple supervisor request from 1 validator is enough.
-        deploy_requests.confirmations(deploy) >= 1
+impl ApiSchema for ModeKind {
+    fn api_schema() -> Schema {
+        description::enum_schema(&["simple", "decentralized"])
     }
+}
 
-    fn config_approved<T: Access>(
-        config_hash: &Hash,
-        config_confirms: &MultisigIndex<T, Hash>,
-        _validators: usize,
-    ) -> bool {
-        config_confirms.confirmations(&config_hash) >= 1
+impl ModeKind {
+    /// Returns the approval policy of the changes which have no policy specified
+    /// in the supervisor config.
+    pub fn default_policy(self) -> ApprovalPolicy {
+        match self {
+            ModeKind::Simple => Simple::default_policy(),
+            ModeKind::Decentralized => Decentralized::default_policy(),
+        }
     }
 }
 
-impl SupervisorMode for Decentralized {
-    fn deploy_approved<T: Access>(
-        deploy: &DeployRequest,
-        deploy_requests: &MultisigIndex<T, DeployRequest>,
-        validators: usize,
+/// Weights of the validator votes.
+#[derive(Debug, Clone)]
+pub struct ValidatorWeights {
+    weights: BTreeMap<PublicKey, u64>,
+}
+
+impl ValidatorWeights {
+    /// Creates weights of the validators with the given keys. Weights which are not
+    /// specified in the supervisor config are equal to 1.
+    pub fn new(validator_keys: &[ValidatorKeys], config: &SupervisorConfig) -> Self {
+        let weights = validator_keys
+            .iter()
+            .map(|keys| {
+                let weight = config
+                    .validator_weights
+                    .iter()
+                    .find(|weight| weight.service_key == keys.service_key)
+                    .map_or(1, |weight| weight.weight);
+                (keys.service_key, weight)
+            })
+            .collect();
+        Self { weights }
+    }
+
+    /// Returns the weight of the votes of the given key. Weight of non-validators is 0.
+    pub fn weight(&self, key: &PublicKey) -> u64 {
+        self.weights.get(key).cloned().unwrap_or(0)
+    }
+
+    /// Returns the total weight of the votes of the given keys.
+    fn weight_of(&self, keys: &[PublicKey]) -> u128 {
+        keys.iter().map(|key| u128::from(self.weight(key))).sum()
+    }
+
+    /// Returns the total weight of all validators.
+    fn total(&self) -> u128 {
+        self.weights
+            .values()
+            .map(|&weight| u128::from(weight))
+            .sum()
+    }
+}
+
+impl ApprovalThreshold {
+    /// Checks whether the threshold is reached. Weights and fractions are bounded
+    /// by the validation of the supervisor config, so the products shouldn't overflow;
+    /// if they do, the threshold is considered not reached.
+    fn is_reached(self, weight: u128, total: u128) -> bool {
+        match self {
+            ApprovalThreshold::Single => weight > 0,
+            ApprovalThreshold::ByzantineMajority => {
+                match (weight.checked_mul(3), total.checked_mul(2)) {
+                    (Some(votes), Some(required)) => votes > required,
+                    _ => false,
+                }
+            }
+            ApprovalThreshold::All => weight >= total,
+            ApprovalThreshold::Fraction {
+                numerator,
+                denominator,
+            } => match (
+                weight.checked_mul(u128::from(denominator)),
+                total.checked_mul(u128::from(numerator)),
+            ) {
+                (Some(votes), Some(required)) => votes >= required,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl ApprovalPolicy {
+    /// Checks whether the policy is satisfied by the given votes.
+    pub fn is_approved(&self, votes: &[PublicKey], weights: &ValidatorWeights) -> bool {
+        votes.iter().any(|key| self.admin_keys.contains(key))
+            || self
+                .threshold
+                .is_reached(weights.weight_of(votes), weights.total())
+    }
+
+    /// Checks whether the policy cannot be satisfied anymore because of the given
+    /// rejections. Changes cannot be rejected while there is an admin which has not
+    /// rejected them.
+    pub fn is_rejected(&self, rejections: &[PublicKey], weights: &ValidatorWeights) -> bool {
+        let admins_rejected = self.admin_keys.iter().all(|key| rejections.contains(key));
+        let total = weights.total();
+        let remaining = total.saturating_sub(weights.weight_of(rejections));
+        admins_rejected && !self.threshold.is_reached(remaining, total)
+    }
+}
+
+/// Trait encapsulating the decision making logic of the supervisor.
+pub trait SupervisorMode: std::fmt::Debug + Send + Sync + Copy + 'static {
+    /// Returns the approval policy of the changes which have no policy specified
+    /// in the supervisor config.
+    fn default_policy() -> ApprovalPolicy;
+
+    /// Returns the default approval policy taking into account the mode set
+    /// in the supervisor config.
+    fn config_default_policy(config: &SupervisorConfig) -> ApprovalPolicy {
+        config
+            .mode
+            .map_or_else(Self::default_policy, ModeKind::default_policy)
+    }
+
+    /// Checks whether deploy should be performed within the network.
+    ///
+    /// The same rules are used both for the deploy requests and for the deploy plans.
+    fn deploy_approved(
+        config: &SupervisorConfig,
+        votes: &[PublicKey],
+        weights: &ValidatorWeights,
     ) -> bool {
-        let confirmations = deploy_requests.confirmations(&deploy);
+        match config.deploy_policy {
+            Some(ref policy) => policy.is_approved(votes, weights),
+            None => Self::config_default_policy(config).is_approved(votes, weights),
+        }
+    }
 
-        // Approve deploy in case 2/3+1 validators confirmed it.
-        confirmations >= byzantine_quorum(validators)
+    /// Checks whether config can be applied for the network, i.e., the policy
+    /// of every change is satisfied.
+    fn config_approved(
+        config: &SupervisorConfig,
+        changes: &[ConfigChange],
+        votes: &[PublicKey],
+        weights: &ValidatorWeights,
+    ) -> bool {
+        let default_policy = Self::config_default_policy(config);
+        if changes.is_empty() {
+            return default_policy.is_approved(votes, weights);
+        }
+        changes.iter().all(|change| {
+            config
+                .change_policy(change)
+                .unwrap_or(&default_policy)
+                .is_approved(votes, weights)
+        })
     }
 
-    fn config_approved<T: Access>(
-        config_hash: &Hash,
-        config_confirms: &MultisigIndex<T, Hash>,
-        validators: usize,
+    /// Checks whether config cannot be approved anymore because of rejections, i.e.,
+    /// the policy of any change cannot be satisfied.
+    fn config_rejected(
+        config: &SupervisorConfig,
+        changes: &[ConfigChange],
+        rejections: &[PublicKey],
+        weights: &ValidatorWeights,
     ) -> bool {
-        let confirmations = config_confirms.confirmations(&config_hash);
+        let default_policy = Self::config_default_policy(config);
+        if changes.is_empty() {
+            return default_policy.is_rejected(rejections, weights);
+        }
+        changes.iter().any(|change| {
+            config
+                .change_policy(change)
+                .unwrap_or(&default_policy)
+                .is_rejected(rejections, weights)
+        })
+    }
+}
 
-        // Apply pending config in case 2/3+1 validators voted for it.
-        confirmations >= byzantine_quorum(validators)
+impl SupervisorMode for Simple {
+    fn default_policy() -> ApprovalPolicy {
+        // For simple supervisor request from 1 validator is enough.
+        ApprovalPolicy::new(ApprovalThreshold::Single)
+    }
+}
+
+impl SupervisorMode for Decentralized {
+    fn default_policy() -> ApprovalPolicy {
+        // Changes are approved in case 2/3+1 validators confirmed them.
+        ApprovalPolicy::new(ApprovalThreshold::ByzantineMajority)
     }
 }
@@ -66,6 +66,13 @@ where
     pub fn confirmations(&self, id: &V) -> usize {
         self.index.get(id).map_or(0, |confirms| confirms.0.len())
     }
+
+    /// Returns authors of the votes for the given value in the ascending order.
+    pub fn confirmed_by_all(&self, id: &V) -> Vec<PublicKey> {
+        self.index
+            .get(id)
+            .map_or_else(Vec::new, |confirms| confirms.0.into_iter().collect())
+    }
 }
 
 impl<T, V> MultisigIndex<T, V>
@@ -81,6 +88,11 @@ where
         self.index.put(id, confirmations);
         len
     }
+
+    /// Removes all the votes for the given value.
+    pub fn remove(&mut self, id: &V) {
+        self.index.remove(id);
+    }
 }
 
 impl<T, V> ObjectHash for MultisigIndex<T, V>
@@ -15,11 +15,12 @@
 use serde_derive::{Deserialize, Serialize};
 
 use exonum::{
+    api::{description, ApiSchema, Schema},
     blockchain::ConsensusConfig,
     crypto::Hash,
     exonum_merkledb::ObjectHash,
     helpers::Height,
-    impl_serde_hex_for_binary_value,
+    impl_api_schema, impl_serde_hex_for_binary_value,
     messages::{AnyTx, Verified},
     runtime::{rust::Transaction, ArtifactId, InstanceId, InstanceSpec, SUPERVISOR_INSTANCE_ID},
 };
@@ -28,7 +29,7 @@ use exonum_derive::*;
 use exonum_merkledb::{impl_binary_key_for_binary_value, BinaryValue};
 use exonum_proto::ProtobufConvert;
 
-use super::{proto, transactions::SupervisorInterface};
+use super::{mode::ModeKind, proto, transactions::SupervisorInterface};
 
 /// Request for the artifact deployment.
 #[derive(Debug, Clone, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
@@ -51,8 +52,7 @@ pub struct DeployConfirmation {
 }
 
 /// Request for the artifact deployment.
-#[protobuf_convert(source = "proto::StartService")]
-#[derive(Debug, Clone, PartialEq, Eq, ProtobufConvert, Hash, BinaryValue, ObjectHash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, BinaryValue, ObjectHash)]
 pub struct StartService {
     /// Artifact identifier.
     pub artifact: ArtifactId,
@@ -60,6 +60,37 @@ pub struct StartService {
     pub name: String,
     /// Instance configuration.
     pub config: Vec<u8>,
+    /// Instance identifier. If not specified, the identifier is assigned
+    /// by the supervisor.
+    pub instance_id: Option<InstanceId>,
+}
+
+impl ProtobufConvert for StartService {
+    type ProtoStruct = proto::StartService;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        pb.set_artifact(self.artifact.to_pb());
+        pb.set_name(self.name.clone());
+        pb.set_config(self.config.clone());
+        // Supervisor identifier is never assigned to new instances, so it denotes
+        // that the identifier is not specified.
+        pb.set_instance_id(self.instance_id.unwrap_or(SUPERVISOR_INSTANCE_ID));
+        pb
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        let instance_id = match pb.get_instance_id() {
+            SUPERVISOR_INSTANCE_ID => None,
+            id => Some(id),
+        };
+        Ok(Self {
+            artifact: ArtifactId::from_pb(pb.take_artifact())?,
+            name: pb.take_name(),
+            config: pb.take_config(),
+            instance_id,
+        })
+    }
 }
 
 impl StartService {
@@ -191,6 +222,84 @@ pub struct ConfigVote {
     pub propose_hash: Hash,
 }
 
+/// Plan of the multi-step service rollout.
+///
+/// Once the plan is approved, the supervisor deploys all its artifacts and waits
+/// until every validator confirms their deployment. At the next height, the configuration
+/// changes of the plan (e.g., start of new service instances and the consensus
+/// config update) are applied atomically in the order of their declaration.
+/// If the artifacts are not deployed until `deadline_height`, the plan is discarded.
+/// If the application of the changes fails, none of them are applied, and the plan
+/// is recorded to the `failed_deploy_plans` index of the supervisor schema.
+#[derive(Debug, Clone, Eq, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::DeployPlan")]
+pub struct DeployPlan {
+    /// Artifacts to deploy.
+    pub artifacts: Vec<DeployRequest>,
+    /// Configuration changes applied after all artifacts are deployed.
+    pub changes: Vec<ConfigChange>,
+    /// The height until which the plan should be completed.
+    pub deadline_height: Height,
+}
+
+impl DeployPlan {
+    /// Creates a new empty plan which should be completed until the specified height.
+    pub fn new(deadline_height: Height) -> Self {
+        Self {
+            artifacts: Vec::default(),
+            changes: Vec::default(),
+            deadline_height,
+        }
+    }
+
+    /// Adds the deployment of the artifact to this plan.
+    pub fn deploy_artifact(mut self, artifact: ArtifactId, spec: Vec<u8>) -> Self {
+        self.artifacts.push(DeployRequest {
+            artifact,
+            spec,
+            deadline_height: self.deadline_height,
+        });
+        self
+    }
+
+    /// Adds service start request to this plan.
+    pub fn start_service(mut self, start_service: StartService) -> Self {
+        self.changes.push(ConfigChange::StartService(start_service));
+        self
+    }
+
+    /// Adds change of the configuration for the specified service instance to this plan.
+    pub fn service_config(mut self, instance_id: InstanceId, config: impl BinaryValue) -> Self {
+        self.changes.push(ConfigChange::Service(ServiceConfig {
+            instance_id,
+            params: config.into_bytes(),
+        }));
+        self
+    }
+
+    /// Adds a change of consensus configuration to this plan.
+    pub fn consensus_config(mut self, config: ConsensusConfig) -> Self {
+        self.changes.push(ConfigChange::Consensus(config));
+        self
+    }
+}
+
+/// Rejection vote for the configuration change
+#[derive(Debug, Clone, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ConfigRejection")]
+pub struct ConfigRejection {
+    /// Hash of configuration proposition.
+    pub propose_hash: Hash,
+}
+
+/// Cancellation of the configuration change by the author of the proposal
+#[derive(Debug, Clone, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ConfigCancel")]
+pub struct ConfigCancel {
+    /// Hash of configuration proposition.
+    pub propose_hash: Hash,
+}
+
 /// Pending config change proposal entry
 #[derive(
     Clone, Debug, Eq, PartialEq, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash,
@@ -203,17 +312,414 @@ pub struct ConfigProposalWithHash {
     pub config_propose: ConfigPropose,
 }
 
+/// Outcome of the configuration change proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigProposalOutcome {
+    /// Proposal has been approved and applied.
+    Applied,
+    /// Proposal has not been approved until its `actual_from` height.
+    Expired,
+    /// Proposal cannot be approved anymore because of rejections.
+    Rejected,
+    /// Proposal has been canceled by its author.
+    Canceled,
+}
+
+impl ProtobufConvert for ConfigProposalOutcome {
+    type ProtoStruct = proto::ConfigProposalOutcome;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            ConfigProposalOutcome::Applied => proto::ConfigProposalOutcome::APPLIED,
+            ConfigProposalOutcome::Expired => proto::ConfigProposalOutcome::EXPIRED,
+            ConfigProposalOutcome::Rejected => proto::ConfigProposalOutcome::REJECTED,
+            ConfigProposalOutcome::Canceled => proto::ConfigProposalOutcome::CANCELED,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        Ok(match pb {
+            proto::ConfigProposalOutcome::APPLIED => ConfigProposalOutcome::Applied,
+            proto::ConfigProposalOutcome::EXPIRED => ConfigProposalOutcome::Expired,
+            proto::ConfigProposalOutcome::REJECTED => ConfigProposalOutcome::Rejected,
+            proto::ConfigProposalOutcome::CANCELED => ConfigProposalOutcome::Canceled,
+        })
+    }
+}
+
+/// Config change proposal stored in the proposal history
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, BinaryValue, ObjectHash)]
+pub struct ConfigProposalRecord {
+    /// The configuration change proposal together with its hash.
+    pub proposal: ConfigProposalWithHash,
+    /// Author of the proposal. The author is unknown for the proposals made before
+    /// the authors were recorded.
+    pub proposer: Option<PublicKey>,
+    /// Validators which confirmed the proposal, including its author.
+    pub confirmations: Vec<PublicKey>,
+    /// Validators which rejected the proposal.
+    pub rejections: Vec<PublicKey>,
+    /// Outcome of the proposal.
+    pub outcome: ConfigProposalOutcome,
+    /// Height of the block in which the outcome was recorded.
+    pub height: Height,
+}
+
+impl ProtobufConvert for ConfigProposalRecord {
+    type ProtoStruct = proto::ConfigProposalRecord;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        pb.set_proposal(self.proposal.to_pb());
+        if let Some(ref proposer) = self.proposer {
+            pb.set_proposer(proposer.to_pb());
+        }
+        pb.set_confirmations(self.confirmations.to_pb());
+        pb.set_rejections(self.rejections.to_pb());
+        pb.set_outcome(self.outcome.to_pb());
+        pb.set_height(self.height.0);
+        pb
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        let proposer = if pb.has_proposer() {
+            Some(PublicKey::from_pb(pb.take_proposer())?)
+        } else {
+            None
+        };
+        Ok(Self {
+            proposal: ConfigProposalWithHash::from_pb(pb.take_proposal())?,
+            proposer,
+            confirmations: Vec::<PublicKey>::from_pb(pb.take_confirmations())?,
+            rejections: Vec::<PublicKey>::from_pb(pb.take_rejections())?,
+            outcome: ConfigProposalOutcome::from_pb(pb.get_outcome())?,
+            height: Height(pb.get_height()),
+        })
+    }
+}
+
+/// Threshold of the validator votes required to approve a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalThreshold {
+    /// Vote of a single validator.
+    Single,
+    /// Votes of more than 2/3 of the total validator weight.
+    ByzantineMajority,
+    /// Votes of all validators.
+    All,
+    /// Votes of at least `numerator / denominator` of the total validator weight.
+    Fraction {
+        /// Numerator of the fraction.
+        numerator: u64,
+        /// Denominator of the fraction, at most `u32::MAX`.
+        denominator: u64,
+    },
+}
+
+/// Policy of the approval of a certain kind of changes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    /// Threshold of the validator votes.
+    pub threshold: ApprovalThreshold,
+    /// Keys which are able to approve changes on their own. The owners of these keys
+    /// are not required to be validators.
+    pub admin_keys: Vec<PublicKey>,
+}
+
+impl ApprovalPolicy {
+    /// Creates a policy with the given threshold and without admin keys.
+    pub fn new(threshold: ApprovalThreshold) -> Self {
+        Self {
+            threshold,
+            admin_keys: Vec::new(),
+        }
+    }
+
+    /// Adds an admin key to the policy.
+    pub fn with_admin_key(mut self, key: PublicKey) -> Self {
+        self.admin_keys.push(key);
+        self
+    }
+}
+
+impl ProtobufConvert for ApprovalPolicy {
+    type ProtoStruct = proto::ApprovalPolicy;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        let threshold = match self.threshold {
+            ApprovalThreshold::Single => proto::ApprovalThreshold::SINGLE,
+            ApprovalThreshold::ByzantineMajority => proto::ApprovalThreshold::BYZANTINE_MAJORITY,
+            ApprovalThreshold::All => proto::ApprovalThreshold::ALL,
+            ApprovalThreshold::Fraction {
+                numerator,
+                denominator,
+            } => {
+                pb.set_numerator(numerator);
+                pb.set_denominator(denominator);
+                proto::ApprovalThreshold::FRACTION
+            }
+        };
+        pb.set_threshold(threshold);
+        pb.set_admin_keys(self.admin_keys.to_pb());
+        pb
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        let threshold = match pb.get_threshold() {
+            proto::ApprovalThreshold::SINGLE => ApprovalThreshold::Single,
+            proto::ApprovalThreshold::BYZANTINE_MAJORITY => ApprovalThreshold::ByzantineMajority,
+            proto::ApprovalThreshold::ALL => ApprovalThreshold::All,
+            proto::ApprovalThreshold::FRACTION => ApprovalThreshold::Fraction {
+                numerator: pb.get_numerator(),
+                denominator: pb.get_denominator(),
+            },
+        };
+        Ok(Self {
+            threshold,
+            admin_keys: Vec::<PublicKey>::from_pb(pb.take_admin_keys())?,
+        })
+    }
+}
+
+/// Weight of the votes of the certain validator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ProtobufConvert)]
+#[protobuf_convert(source = "proto::ValidatorWeight")]
+pub struct ValidatorWeight {
+    /// Service key of the validator.
+    pub service_key: PublicKey,
+    /// Weight of the validator votes, from 1 to `u32::MAX`.
+    pub weight: u64,
+}
+
+/// Approval policy for the configuration changes of the certain service instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ProtobufConvert)]
+#[protobuf_convert(source = "proto::InstancePolicy")]
+pub struct InstancePolicy {
+    /// Corresponding service instance ID.
+    pub instance_id: InstanceId,
+    /// Approval policy of the instance configuration changes.
+    pub policy: ApprovalPolicy,
+}
+
+/// Configuration of the supervisor.
+///
+/// Policies which are not specified are defined by the supervisor mode. The configuration
+/// can be changed by the `ConfigChange::Service` targeting the supervisor instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BinaryValue, ObjectHash)]
+pub struct SupervisorConfig {
+    /// Weights of the validator votes. Validators which are not listed have the weight of 1.
+    pub validator_weights: Vec<ValidatorWeight>,
+    /// Approval policy of the deploy requests and deploy plans.
+    pub deploy_policy: Option<ApprovalPolicy>,
+    /// Approval policy of the consensus configuration changes.
+    pub consensus_policy: Option<ApprovalPolicy>,
+    /// Approval policy of the service configuration changes.
+    pub service_config_policy: Option<ApprovalPolicy>,
+    /// Approval policy of the service instance starts.
+    pub start_service_policy: Option<ApprovalPolicy>,
+    /// Approval policies of the configuration changes for the certain service instances.
+    /// These policies override the `service_config_policy`.
+    pub instance_policies: Vec<InstancePolicy>,
+    /// Mode of the supervisor. If not specified, the mode is defined by the type
+    /// of the supervisor service.
+    pub mode: Option<ModeKind>,
+}
+
+impl ProtobufConvert for SupervisorConfig {
+    type ProtoStruct = proto::SupervisorConfig;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        pb.set_validator_weights(self.validator_weights.to_pb());
+        if let Some(ref policy) = self.deploy_policy {
+            pb.set_deploy_policy(policy.to_pb());
+        }
+        if let Some(ref policy) = self.consensus_policy {
+            pb.set_consensus_policy(policy.to_pb());
+        }
+        if let Some(ref policy) = self.service_config_policy {
+            pb.set_service_config_policy(policy.to_pb());
+        }
+        if let Some(ref policy) = self.start_service_policy {
+            pb.set_start_service_policy(policy.to_pb());
+        }
+        pb.set_instance_policies(self.instance_policies.to_pb());
+        let mode = match self.mode {
+            None => proto::SupervisorMode::UNSPECIFIED,
+            Some(ModeKind::Simple) => proto::SupervisorMode::SIMPLE,
+            Some(ModeKind::Decentralized) => proto::SupervisorMode::DECENTRALIZED,
+        };
+        pb.set_mode(mode);
+        pb
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        let deploy_policy = if pb.has_deploy_policy() {
+            Some(ApprovalPolicy::from_pb(pb.take_deploy_policy())?)
+        } else {
+            None
+        };
+        let consensus_policy = if pb.has_consensus_policy() {
+            Some(ApprovalPolicy::from_pb(pb.take_consensus_policy())?)
+        } else {
+            None
+        };
+        let service_config_policy = if pb.has_service_config_policy() {
+            Some(ApprovalPolicy::from_pb(pb.take_service_config_policy())?)
+        } else {
+            None
+        };
+        let start_service_policy = if pb.has_start_service_policy() {
+            Some(ApprovalPolicy::from_pb(pb.take_start_service_policy())?)
+        } else {
+            None
+        };
+        let mode = match pb.get_mode() {
+            proto::SupervisorMode::UNSPECIFIED => None,
+            proto::SupervisorMode::SIMPLE => Some(ModeKind::Simple),
+            proto::SupervisorMode::DECENTRALIZED => Some(ModeKind::Decentralized),
+        };
+
+        Ok(Self {
+            validator_weights: Vec::<ValidatorWeight>::from_pb(pb.take_validator_weights())?,
+            deploy_policy,
+            consensus_policy,
+            service_config_policy,
+            start_service_policy,
+            instance_policies: Vec::<InstancePolicy>::from_pb(pb.take_instance_policies())?,
+            mode,
+        })
+    }
+}
+
+impl SupervisorConfig {
+    /// Returns the policy of the configuration change, or `None` if the policy
+    /// is not specified.
+    pub fn change_policy(&self, change: &ConfigChange) -> Option<&ApprovalPolicy> {
+        match change {
+            ConfigChange::Consensus(_) => self.consensus_policy.as_ref(),
+            ConfigChange::Service(config) => self
+                .instance_policies
+                .iter()
+                .find(|instance_policy| instance_policy.instance_id == config.instance_id)
+                .map(|instance_policy| &instance_policy.policy)
+                .or_else(|| self.service_config_policy.as_ref()),
+            ConfigChange::StartService(_) => self.start_service_policy.as_ref(),
+        }
+    }
+
+    /// Checks whether the given key is an admin key for every change in the non-empty
+    /// list of changes, so that its owner may propose and vote for these changes
+    /// without being a validator.
+    pub fn is_admin(&self, changes: &[ConfigChange], key: &PublicKey) -> bool {
+        !changes.is_empty()
+            && changes.iter().all(|change| {
+                self.change_policy(change)
+                    .map_or(false, |policy| policy.admin_keys.contains(key))
+            })
+    }
+}
+
 impl_binary_key_for_binary_value! { DeployRequest }
 impl_binary_key_for_binary_value! { DeployConfirmation }
 impl_binary_key_for_binary_value! { StartService }
 impl_binary_key_for_binary_value! { ConfigPropose }
 impl_binary_key_for_binary_value! { ConfigVote }
+impl_binary_key_for_binary_value! { ConfigRejection }
+impl_binary_key_for_binary_value! { ConfigCancel }
+impl_binary_key_for_binary_value! { DeployPlan }
 
 impl_serde_hex_for_binary_value! { DeployRequest }
 impl_serde_hex_for_binary_value! { DeployConfirmation }
 impl_serde_hex_for_binary_value! { StartService }
 impl_serde_hex_for_binary_value! { ConfigPropose }
 impl_serde_hex_for_binary_value! { ConfigVote }
+impl_serde_hex_for_binary_value! { ConfigRejection }
+impl_serde_hex_for_binary_value! { ConfigCancel }
+impl_serde_hex_for_binary_value! { DeployPlan }
+
+macro_rules! impl_hex_api_schema {
+    ($($ty:ty),*) => {
+        $(
+            impl ApiSchema for $ty {
+                fn api_schema() -> Schema {
+                    description::hex_schema(None)
+                }
+            }
+        )*
+    };
+}
+
+impl_hex_api_schema!(
+    DeployRequest,
+    DeployConfirmation,
+    StartService,
+    ConfigPropose,
+    ConfigVote,
+    ConfigRejection,
+    ConfigCancel,
+    DeployPlan
+);
+
+impl_api_schema!(ConfigProposalWithHash {
+    propose_hash: Hash,
+    config_propose: ConfigPropose,
+});
+
+impl ApiSchema for ConfigProposalOutcome {
+    fn api_schema() -> Schema {
+        description::enum_schema(&["applied", "expired", "rejected", "canceled"])
+    }
+}
+
+impl_api_schema!(ConfigProposalRecord {
+    proposal: ConfigProposalWithHash,
+    proposer: Option<PublicKey>,
+    confirmations: Vec<PublicKey>,
+    rejections: Vec<PublicKey>,
+    outcome: ConfigProposalOutcome,
+    height: Height,
+});
+
+impl ApiSchema for ApprovalThreshold {
+    fn api_schema() -> Schema {
+        let fraction = description::object_schema(vec![
+            ("numerator", u64::api_schema(), false),
+            ("denominator", u64::api_schema(), false),
+        ]);
+        let fraction = description::object_schema(vec![("fraction", fraction, false)]);
+        let unit_variants = description::enum_schema(&["single", "byzantine_majority", "all"]);
+        serde_json::json!({ "oneOf": [unit_variants, fraction] })
+    }
+}
+
+impl_api_schema!(ApprovalPolicy {
+    threshold: ApprovalThreshold,
+    admin_keys: Vec<PublicKey>,
+});
+
+impl_api_schema!(ValidatorWeight {
+    service_key: PublicKey,
+    weight: u64,
+});
+
+impl_api_schema!(InstancePolicy {
+    instance_id: InstanceId,
+    policy: ApprovalPolicy,
+});
+
+impl_api_schema!(SupervisorConfig {
+    validator_weights: Vec<ValidatorWeight>,
+    deploy_policy: Option<ApprovalPolicy>,
+    consensus_policy: Option<ApprovalPolicy>,
+    service_config_policy: Option<ApprovalPolicy>,
+    start_service_policy: Option<ApprovalPolicy>,
+    instance_policies: Vec<InstancePolicy>,
+    mode: Option<ModeKind>,
+});
 
 impl From<DeployRequest> for DeployConfirmation {
     fn from(v: DeployRequest) -> Self {
@@ -13,18 +13,19 @@
 // limitations under the License.
 
 use exonum::{
-    crypto::Hash,
+    crypto::{Hash, PublicKey},
+    helpers::Height,
     runtime::{ArtifactId, InstanceId},
 };
 use exonum_derive::FromAccess;
 use exonum_merkledb::{
     access::{Access, Prefixed},
-    Entry, Fork, ObjectHash, ProofMapIndex,
+    Entry, Fork, ObjectHash, ProofListIndex, ProofMapIndex,
 };
 
 use super::{
-    multisig::MultisigIndex, ConfigProposalWithHash, DeployConfirmation, DeployRequest,
-    StartService,
+    multisig::MultisigIndex, ConfigProposalOutcome, ConfigProposalRecord, ConfigProposalWithHash,
+    DeployConfirmation, DeployPlan, DeployRequest, StartService, SupervisorConfig,
 };
 
 /// Service information schema.
@@ -34,10 +35,21 @@ pub struct Schema<T: Access> {
     pub deploy_confirmations: MultisigIndex<T, DeployConfirmation>,
     pub pending_deployments: ProofMapIndex<T::Base, ArtifactId, DeployRequest>,
     pub pending_instances: MultisigIndex<T, StartService>,
+    pub deploy_plan_requests: MultisigIndex<T, DeployPlan>,
+    pub pending_deploy_plan: Entry<T::Base, DeployPlan>,
+    /// Height at which the changes of the pending deploy plan are applied.
+    pub deploy_plan_application_height: Entry<T::Base, u64>,
+    /// Heights of the blocks in which the changes of the failed deploy plans were applied,
+    /// keyed by the plan hash.
+    pub failed_deploy_plans: ProofMapIndex<T::Base, Hash, u64>,
     pub config_confirms: MultisigIndex<T, Hash>,
+    pub config_rejections: MultisigIndex<T, Hash>,
     pub pending_proposal: Entry<T::Base, ConfigProposalWithHash>,
+    pub pending_proposal_author: Entry<T::Base, PublicKey>,
+    pub proposal_history: ProofListIndex<T::Base, ConfigProposalRecord>,
     pub configuration_number: Entry<T::Base, u64>,
     pub vacant_instance_id: Entry<T::Base, InstanceId>,
+    pub supervisor_config: Entry<T::Base, SupervisorConfig>,
 }
 
 impl<T: Access> Schema<T> {
@@ -45,6 +57,12 @@ impl<T: Access> Schema<T> {
         self.configuration_number.get().unwrap_or(0)
     }
 
+    /// Returns the supervisor configuration. If the configuration is not set,
+    /// the default one is returned, so that all policies are defined by the supervisor mode.
+    pub fn supervisor_config(&self) -> SupervisorConfig {
+        self.supervisor_config.get().unwrap_or_default()
+    }
+
     /// Returns hashes for tables with proofs.
     pub fn state_hash(&self) -> Vec<Hash> {
         vec![
@@ -52,7 +70,12 @@ impl<T: Access> Schema<T> {
             self.deploy_confirmations.object_hash(),
             self.pending_deployments.object_hash(),
             self.pending_instances.object_hash(),
+            self.deploy_plan_requests.object_hash(),
+            self.failed_deploy_plans.object_hash(),
             self.config_confirms.object_hash(),
+            self.config_rejections.object_hash(),
+            self.proposal_history.object_hash(),
+            self.supervisor_config.object_hash(),
         ]
     }
 }
@@ -63,11 +86,60 @@ impl Schema<Prefixed<'_, &Fork>> {
         self.configuration_number.set(new_configuration_number);
     }
 
-    /// Assign unique identifier for an instance.
-    /// Returns `None` if `vacant_instance_id` entry was not initialized.
-    pub(crate) fn assign_instance_id(&mut self) -> Option<InstanceId> {
-        let id = self.vacant_instance_id.get()?;
-        self.vacant_instance_id.set(id + 1);
-        Some(id)
+    /// Removes the pending configuration change proposal and records it
+    /// to the proposal history with the given outcome.
+    pub(crate) fn finalize_pending_proposal(
+        &mut self,
+        outcome: ConfigProposalOutcome,
+        height: Height,
+    ) {
+        let proposal = match self.pending_proposal.get() {
+            Some(proposal) => proposal,
+            None => return,
+        };
+        // The author is not recorded for the proposals made before the upgrade
+        // of the supervisor.
+        let proposer = self.pending_proposal_author.get();
+
+        let record = ConfigProposalRecord {
+            confirmations: self
+                .config_confirms
+                .confirmed_by_all(&proposal.propose_hash),
+            rejections: self
+                .config_rejections
+                .confirmed_by_all(&proposal.propose_hash),
+            proposal,
+            proposer,
+            outcome,
+            height,
+        };
+        let propose_hash = record.proposal.propose_hash;
+        self.proposal_history.push(record);
+
+        // Votes are kept in the history record, so they are no longer needed.
+        self.config_confirms.remove(&propose_hash);
+        self.config_rejections.remove(&propose_hash);
+        self.pending_proposal.remove();
+        self.pending_proposal_author.remove();
+    }
+
+    /// Removes the pending deploy plan together with the pending deployments
+    /// of its artifacts and the votes for the plan, so that the plan can be requested anew.
+    pub(crate) fn discard_deploy_plan(&mut self, plan: &DeployPlan) {
+        for request in &plan.artifacts {
+            if self.pending_deployments.get(&request.artifact).as_ref() == Some(request) {
+                self.pending_deployments.remove(&request.artifact);
+            }
+        }
+        self.deploy_plan_requests.remove(plan);
+        self.pending_deploy_plan.remove();
+        self.deploy_plan_application_height.remove();
+    }
+
+    /// Discards the pending deploy plan, whose changes could not be applied
+    /// in the block with the given height, and marks it as failed.
+    pub(crate) fn fail_deploy_plan(&mut self, plan: &DeployPlan, height: Height) {
+        self.failed_deploy_plans.put(&plan.object_hash(), height.0);
+        self.discard_deploy_plan(plan);
     }
 }
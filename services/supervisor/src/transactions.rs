@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use exonum::{
+    crypto::{Hash, PublicKey},
     helpers::{Height, ValidateInput},
     runtime::{rust::CallContext, DispatcherError, ExecutionError, InstanceSpec},
 };
@@ -22,8 +23,11 @@ use exonum_merkledb::ObjectHash;
 use std::collections::HashSet;
 
 use super::{
-    mode, ConfigChange, ConfigProposalWithHash, ConfigPropose, ConfigVote, ConfigureCall,
-    DeployConfirmation, DeployRequest, Error, Schema, StartService, Supervisor,
+    mode::{self, ValidatorWeights},
+    ApprovalPolicy, ApprovalThreshold, ConfigCancel, ConfigChange, ConfigProposalOutcome,
+    ConfigProposalWithHash, ConfigPropose, ConfigRejection, ConfigVote, ConfigureCall,
+    DeployConfirmation, DeployPlan, DeployRequest, Error, Schema, StartService, Supervisor,
+    SupervisorConfig,
 };
 
 /// Supervisor service transactions.
@@ -51,6 +55,19 @@ pub trait SupervisorInterface {
         artifact: DeployConfirmation,
     ) -> Result<(), ExecutionError>;
 
+    /// Requests a multi-step deploy plan.
+    ///
+    /// This request should be initiated by the validator; the plan is approved according
+    /// to the same rules as `request_artifact_deploy`. After the approval, the supervisor
+    /// deploys all artifacts of the plan and, once every validator confirms their deployment,
+    /// applies the configuration changes of the plan within `before_commit` at the next height.
+    /// Note: only one plan at time is possible.
+    fn request_deploy_plan(
+        &self,
+        context: CallContext<'_>,
+        plan: DeployPlan,
+    ) -> Result<(), ExecutionError>;
+
     /// Propose config change
     ///
     /// This request should be sent by one of validators as the proposition to change
@@ -58,7 +75,9 @@ pub trait SupervisorInterface {
     /// configuration by sending `confirm_config_change` transaction.
     /// The configuration application rules depend on the `Supervisor` mode, e.g. confirmations
     /// are not required for the `Simple` mode, and for `Decentralized` mode (2/3+1) confirmations
-    /// are required.
+    /// are required. These rules can be overridden by the approval policies of the supervisor
+    /// config; an admin of the policies of all the proposed changes can propose and vote
+    /// for them without being a validator.
     /// Note: only one proposal at time is possible.
     fn propose_config_change(
         &self,
@@ -77,6 +96,28 @@ pub trait SupervisorInterface {
         context: CallContext<'_>,
         vote: ConfigVote,
     ) -> Result<(), ExecutionError>;
+
+    /// Reject config change
+    ///
+    /// This rejection should be sent by validators to vote against proposed configuration.
+    /// A validator can either confirm or reject the proposal, but not both.
+    /// The proposal is removed as soon as it cannot be approved anymore; the exact
+    /// rules depend on the `Supervisor` mode.
+    fn reject_config_change(
+        &self,
+        context: CallContext<'_>,
+        rejection: ConfigRejection,
+    ) -> Result<(), ExecutionError>;
+
+    /// Cancel config change
+    ///
+    /// This cancellation can be sent only by the author of the `propose_config_change`
+    /// transaction. The pending proposal is removed immediately.
+    fn cancel_config_change(
+        &self,
+        context: CallContext<'_>,
+        cancel: ConfigCancel,
+    ) -> Result<(), ExecutionError>;
 }
 
 impl ValidateInput for DeployRequest {
@@ -99,6 +140,84 @@ impl ValidateInput for DeployConfirmation {
     }
 }
 
+impl ValidateInput for DeployPlan {
+    type Error = ExecutionError;
+
+    fn validate(&self) -> Result<(), Self::Error> {
+        if self.artifacts.is_empty() {
+            return Err(Error::MalformedDeployPlan.into());
+        }
+
+        let mut artifacts = HashSet::new();
+        for request in &self.artifacts {
+            request.validate()?;
+            // Each artifact should be deployed only once and before the plan deadline.
+            if !artifacts.insert(request.artifact.name.as_str())
+                || request.deadline_height > self.deadline_height
+            {
+                return Err(Error::MalformedDeployPlan.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The maximum value of validator weights and fraction denominators in the supervisor config.
+/// The bound guarantees that the approval thresholds can be computed without overflow.
+const MAX_POLICY_VALUE: u64 = u32::max_value() as u64;
+
+impl ValidateInput for ApprovalPolicy {
+    type Error = ExecutionError;
+
+    fn validate(&self) -> Result<(), Self::Error> {
+        if let ApprovalThreshold::Fraction {
+            numerator,
+            denominator,
+        } = self.threshold
+        {
+            if numerator == 0 || numerator > denominator || denominator > MAX_POLICY_VALUE {
+                return Err(Error::MalformedSupervisorConfig.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ValidateInput for SupervisorConfig {
+    type Error = ExecutionError;
+
+    fn validate(&self) -> Result<(), Self::Error> {
+        let mut validators = HashSet::new();
+        for weight in &self.validator_weights {
+            if weight.weight == 0
+                || weight.weight > MAX_POLICY_VALUE
+                || !validators.insert(weight.service_key)
+            {
+                return Err(Error::MalformedSupervisorConfig.into());
+            }
+        }
+
+        let mut instances = HashSet::new();
+        for instance_policy in &self.instance_policies {
+            if !instances.insert(instance_policy.instance_id) {
+                return Err(Error::MalformedSupervisorConfig.into());
+            }
+            instance_policy.policy.validate()?;
+        }
+
+        let policies = [
+            &self.deploy_policy,
+            &self.consensus_policy,
+            &self.service_config_policy,
+            &self.start_service_policy,
+        ];
+        for policy in policies.iter().filter_map(|policy| policy.as_ref()) {
+            policy.validate()?;
+        }
+        Ok(())
+    }
+}
+
 impl StartService {
     fn validate(&self, context: &CallContext<'_>) -> Result<(), ExecutionError> {
         self.artifact
@@ -133,6 +252,18 @@ impl StartService {
             return Err(Error::InstanceExists.into());
         }
 
+        // Check that there is no instance with the requested identifier.
+        if let Some(id) = self.instance_id {
+            if dispatcher_data.get_instance(id).is_some() {
+                log::trace!(
+                    "Discarded start of the instance {} with the already used ID {}.",
+                    &self.name,
+                    id
+                );
+                return Err(Error::InstanceIdExists.into());
+            }
+        }
+
         Ok(())
     }
 }
@@ -151,12 +282,13 @@ where
             .as_transaction()
             .ok_or(DispatcherError::UnauthorizedCaller)?;
 
-        // Verifies that transaction author is validator.
-        context
-            .data()
-            .for_core()
-            .validator_id(author)
-            .ok_or(Error::UnknownAuthor)?;
+        // Verifies that transaction author is either a validator or an admin
+        // of all the proposed changes.
+        let is_validator = context.data().for_core().validator_id(author).is_some();
+        let config = Schema::new(context.service_data()).supervisor_config();
+        if !is_validator && !config.is_admin(&propose.changes, &author) {
+            return Err(Error::UnknownAuthor.into());
+        }
 
         let current_height = context.data().for_core().height();
 
@@ -179,7 +311,10 @@ where
             } else {
                 // Proposal is outdated but was not removed (e.g. because of the panic
                 // during config applying), clean it.
-                schema.pending_proposal.remove();
+                schema.finalize_pending_proposal(
+                    ConfigProposalOutcome::Expired,
+                    current_height.next(),
+                );
             }
         }
         drop(schema);
@@ -202,6 +337,7 @@ where
             propose_hash,
         };
         schema.pending_proposal.set(config_entry);
+        schema.pending_proposal_author.set(author);
 
         Ok(())
     }
@@ -210,17 +346,71 @@ where
         &self,
         context: CallContext<'_>,
         vote: ConfigVote,
+    ) -> Result<(), ExecutionError> {
+        let (author, _) = verify_config_vote(&context, &vote.propose_hash)?;
+
+        let mut schema = Schema::new(context.service_data());
+        schema.config_confirms.confirm(&vote.propose_hash, author);
+        log::trace!(
+            "Propose config {:?} has been confirmed by {:?}",
+            vote.propose_hash,
+            author
+        );
+
+        Ok(())
+    }
+
+    fn reject_config_change(
+        &self,
+        context: CallContext<'_>,
+        rejection: ConfigRejection,
+    ) -> Result<(), ExecutionError> {
+        let (author, entry) = verify_config_vote(&context, &rejection.propose_hash)?;
+        let core_schema = context.data().for_core();
+        let height = core_schema.height();
+
+        let mut schema = Schema::new(context.service_data());
+        schema
+            .config_rejections
+            .confirm(&rejection.propose_hash, author);
+        log::trace!(
+            "Propose config {:?} has been rejected by {:?}",
+            rejection.propose_hash,
+            author
+        );
+
+        let config = schema.supervisor_config();
+        let weights =
+            ValidatorWeights::new(&core_schema.consensus_config().validator_keys, &config);
+        let rejections = schema
+            .config_rejections
+            .confirmed_by_all(&rejection.propose_hash);
+        if Mode::config_rejected(
+            &config,
+            &entry.config_propose.changes,
+            &rejections,
+            &weights,
+        ) {
+            log::trace!(
+                "Removed config proposal {:?} which cannot be approved anymore",
+                rejection.propose_hash
+            );
+            schema.finalize_pending_proposal(ConfigProposalOutcome::Rejected, height.next());
+        }
+
+        Ok(())
+    }
+
+    fn cancel_config_change(
+        &self,
+        context: CallContext<'_>,
+        cancel: ConfigCancel,
     ) -> Result<(), ExecutionError> {
         let (_, author) = context
             .caller()
             .as_transaction()
             .ok_or(DispatcherError::UnauthorizedCaller)?;
-
-        // Verify that transaction author is a validator.
-        let core_schema = context.data().for_core();
-        core_schema
-            .validator_id(author)
-            .ok_or(Error::UnknownAuthor)?;
+        let height = context.data().for_core().height();
 
         let mut schema = Schema::new(context.service_data());
         let entry = schema
@@ -229,26 +419,18 @@ where
             .ok_or_else(|| Error::ConfigProposeNotRegistered)?;
 
         // Verifies that this config proposal is registered.
-        if entry.propose_hash != vote.propose_hash {
+        if entry.propose_hash != cancel.propose_hash {
             return Err(Error::ConfigProposeNotRegistered.into());
         }
-
-        let config_propose = entry.config_propose;
-        // Verifies that we didn't reach the deadline height.
-        if config_propose.actual_from <= core_schema.height() {
-            return Err(Error::DeadlineExceeded.into());
-        }
-        if schema
-            .config_confirms
-            .confirmed_by(&entry.propose_hash, &author)
-        {
-            return Err(Error::AttemptToVoteTwice.into());
+        // Verifies that transaction author is the author of the proposal.
+        if schema.pending_proposal_author.get() != Some(author) {
+            return Err(Error::NotProposalAuthor.into());
         }
 
-        schema.config_confirms.confirm(&vote.propose_hash, author);
+        schema.finalize_pending_proposal(ConfigProposalOutcome::Canceled, height.next());
         log::trace!(
-            "Propose config {:?} has been confirmed by {:?}",
-            vote.propose_hash,
+            "Propose config {:?} has been canceled by {:?}",
+            cancel.propose_hash,
             author
         );
 
@@ -262,7 +444,6 @@ where
     ) -> Result<(), ExecutionError> {
         deploy.validate()?;
         let core_schema = context.data().for_core();
-        let validator_count = core_schema.consensus_config().validator_keys.len();
         // Verifies that we doesn't reach deadline height.
         if deploy.deadline_height < core_schema.height() {
             return Err(Error::ActualFromIsPast.into());
@@ -300,7 +481,11 @@ where
         }
 
         schema.deploy_requests.confirm(&deploy, author);
-        if Mode::deploy_approved(&deploy, &schema.deploy_requests, validator_count) {
+        let config = schema.supervisor_config();
+        let weights =
+            ValidatorWeights::new(&core_schema.consensus_config().validator_keys, &config);
+        let votes = schema.deploy_requests.confirmed_by_all(&deploy);
+        if Mode::deploy_approved(&config, &votes, &weights) {
             log::trace!("Deploy artifact request accepted {:?}", deploy.artifact);
             let artifact = deploy.artifact.clone();
             schema.pending_deployments.put(&artifact, deploy);
@@ -308,6 +493,84 @@ where
         Ok(())
     }
 
+    fn request_deploy_plan(
+        &self,
+        context: CallContext<'_>,
+        plan: DeployPlan,
+    ) -> Result<(), ExecutionError> {
+        plan.validate()?;
+        let core_schema = context.data().for_core();
+        // Verifies that we doesn't reach deadline height.
+        if plan.deadline_height < core_schema.height() {
+            return Err(Error::ActualFromIsPast.into());
+        }
+
+        // Verifies that transaction author is validator.
+        let author = context.caller().author().ok_or(Error::UnknownAuthor)?;
+        core_schema
+            .validator_id(author)
+            .ok_or(Error::UnknownAuthor)?;
+
+        // Verifies that services are started from the artifacts which are either deployed
+        // or deployed within the plan. Other changes are verified when they are applied.
+        let dispatcher_data = context.data().for_dispatcher();
+        for change in &plan.changes {
+            if let ConfigChange::StartService(start_service) = change {
+                let artifact = &start_service.artifact;
+                let is_known = plan
+                    .artifacts
+                    .iter()
+                    .any(|request| request.artifact == *artifact)
+                    || dispatcher_data
+                        .get_artifact(&artifact.name)
+                        .map_or(false, |(spec, _)| spec.artifact == *artifact);
+                if !is_known {
+                    return Err(Error::UnknownArtifact.into());
+                }
+            }
+        }
+
+        let mut schema = Schema::new(context.service_data());
+        if schema.deploy_plan_requests.confirmed_by(&plan, &author) {
+            // Author already confirmed this plan, so it's a duplicate.
+            return Err(Error::DeployRequestAlreadyRegistered.into());
+        }
+        if let Some(pending_plan) = schema.pending_deploy_plan.get() {
+            if pending_plan != plan {
+                return Err(Error::DeployPlanExists.into());
+            }
+            // It's OK, just an additional confirmation.
+            schema.deploy_plan_requests.confirm(&plan, author);
+            return Ok(());
+        }
+
+        schema.deploy_plan_requests.confirm(&plan, author);
+        let config = schema.supervisor_config();
+        let weights =
+            ValidatorWeights::new(&core_schema.consensus_config().validator_keys, &config);
+        let votes = schema.deploy_plan_requests.confirmed_by_all(&plan);
+        // Configuration changes of the plan should satisfy their policies as well.
+        let approved = Mode::deploy_approved(&config, &votes, &weights)
+            && (plan.changes.is_empty()
+                || Mode::config_approved(&config, &plan.changes, &votes, &weights));
+        if approved {
+            log::trace!("Deploy plan accepted {:?}", plan);
+            // Artifacts are deployed by the same procedure as ones from the deploy requests.
+            for request in &plan.artifacts {
+                let is_deployed = dispatcher_data
+                    .get_artifact(&request.artifact.name)
+                    .map_or(false, |(spec, _)| spec.artifact == request.artifact);
+                if !is_deployed && !schema.pending_deployments.contains(&request.artifact) {
+                    schema
+                        .pending_deployments
+                        .put(&request.artifact, request.clone());
+                }
+            }
+            schema.pending_deploy_plan.set(plan);
+        }
+        Ok(())
+    }
+
     fn confirm_artifact_deploy(
         &self,
         context: CallContext<'_>,
@@ -353,12 +616,59 @@ where
     }
 }
 
+/// Verifies that the transaction author is a validator or an admin of the proposed changes
+/// which has not voted yet for the pending config proposal with the given hash.
+/// Returns the author of the vote and the pending proposal.
+fn verify_config_vote(
+    context: &CallContext<'_>,
+    propose_hash: &Hash,
+) -> Result<(PublicKey, ConfigProposalWithHash), ExecutionError> {
+    let (_, author) = context
+        .caller()
+        .as_transaction()
+        .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+    let core_schema = context.data().for_core();
+    let schema = Schema::new(context.service_data());
+    let entry = schema.pending_proposal.get();
+
+    // Verify that transaction author is a validator or an admin of the proposed changes.
+    let is_admin = entry.as_ref().map_or(false, |entry| {
+        entry.propose_hash == *propose_hash
+            && schema
+                .supervisor_config()
+                .is_admin(&entry.config_propose.changes, &author)
+    });
+    if core_schema.validator_id(author).is_none() && !is_admin {
+        return Err(Error::UnknownAuthor.into());
+    }
+
+    let entry = entry.ok_or_else(|| Error::ConfigProposeNotRegistered)?;
+
+    // Verifies that this config proposal is registered.
+    if entry.propose_hash != *propose_hash {
+        return Err(Error::ConfigProposeNotRegistered.into());
+    }
+
+    // Verifies that we didn't reach the deadline height.
+    if entry.config_propose.actual_from <= core_schema.height() {
+        return Err(Error::DeadlineExceeded.into());
+    }
+    if schema.config_confirms.confirmed_by(propose_hash, &author)
+        || schema.config_rejections.confirmed_by(propose_hash, &author)
+    {
+        return Err(Error::AttemptToVoteTwice.into());
+    }
+
+    Ok((author, entry))
+}
+
 impl<Mode> Supervisor<Mode>
 where
     Mode: mode::SupervisorMode,
 {
     /// Verifies that each change introduced within config proposal is valid.
-    fn verify_config_changeset(
+    pub(crate) fn verify_config_changeset(
         &self,
         context: &mut CallContext<'_>,
         changes: &[ConfigChange],
@@ -369,6 +679,8 @@ where
         let mut service_ids = HashSet::new();
         // To prevent multiple services start in one request.
         let mut services_to_start = HashSet::new();
+        // To prevent multiple services start with the same ID in one request.
+        let mut instance_ids_to_start = HashSet::new();
 
         // Perform config verification.
         for change in changes {
@@ -406,6 +718,15 @@ where
                         return Err(Error::MalformedConfigPropose.into());
                     }
 
+                    if let Some(id) = start_service.instance_id {
+                        if !instance_ids_to_start.insert(id) {
+                            log::trace!(
+                                "Discarded multiple instances with the same ID in one request."
+                            );
+                            return Err(Error::MalformedConfigPropose.into());
+                        }
+                    }
+
                     start_service.validate(&context)?;
                 }
             }
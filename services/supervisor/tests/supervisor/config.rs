@@ -23,7 +23,9 @@ use exonum::{
 };
 
 use crate::{utils::*, IncService as ConfigChangeService};
-use exonum_supervisor::{ConfigVote, DecentralizedSupervisor, Error, SupervisorInterface};
+use exonum_supervisor::{
+    ConfigCancel, ConfigRejection, ConfigVote, DecentralizedSupervisor, Error, SupervisorInterface,
+};
 
 #[test]
 fn test_multiple_consensus_change_proposes() {
@@ -246,6 +248,124 @@ fn test_send_confirmation_by_initiator() {
     assert_eq!(status, Err(&Error::AttemptToVoteTwice.into()));
 }
 
+#[test]
+fn test_reject_config_by_byzantine_minority() {
+    let mut testkit = testkit_with_supervisor(4);
+    let initiator_id = testkit.network().us().validator_id().unwrap();
+    let base_consensus_config = testkit.consensus_config();
+
+    let cfg_change_height = Height(6);
+    let consensus_config = consensus_config_propose_first_variant(&testkit);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config.clone())
+        .build();
+    let proposal_hash = config_proposal.object_hash();
+
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal.clone(),
+            initiator_id,
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+
+    let rejection = ConfigRejection {
+        propose_hash: proposal_hash,
+    };
+    // Single rejection out of 4 validators does not prevent the approval.
+    let keys = testkit.network().validators()[1].service_keypair();
+    testkit
+        .create_block_with_transaction(rejection.clone().sign(
+            SUPERVISOR_INSTANCE_ID,
+            keys.0,
+            &keys.1,
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with rejection discarded.");
+    assert_eq!(config_propose_entry(&testkit), Some(config_proposal));
+
+    // Validator cannot confirm the proposal after the rejection.
+    let block = testkit.create_block_with_transaction(
+        ConfigVote {
+            propose_hash: proposal_hash,
+        }
+        .sign(SUPERVISOR_INSTANCE_ID, keys.0, &keys.1),
+    );
+    let status = block.transactions[0].status();
+    assert_eq!(status, Err(&Error::AttemptToVoteTwice.into()));
+
+    // The second rejection makes the approval impossible.
+    let keys = testkit.network().validators()[2].service_keypair();
+    testkit
+        .create_block_with_transaction(rejection.sign(SUPERVISOR_INSTANCE_ID, keys.0, &keys.1))
+        .transactions[0]
+        .status()
+        .expect("Transaction with rejection discarded.");
+    assert_eq!(config_propose_entry(&testkit), None);
+
+    testkit.create_blocks_until(cfg_change_height.next());
+    assert_eq!(testkit.consensus_config(), base_consensus_config);
+}
+
+#[test]
+fn test_cancel_config_by_author() {
+    let mut testkit = testkit_with_supervisor(4);
+    let initiator_id = testkit.network().us().validator_id().unwrap();
+
+    let cfg_change_height = Height(6);
+    let consensus_config = consensus_config_propose_first_variant(&testkit);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config.clone())
+        .build();
+    let proposal_hash = config_proposal.object_hash();
+
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal.clone(),
+            initiator_id,
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+
+    let cancel = ConfigCancel {
+        propose_hash: proposal_hash,
+    };
+    // Only the author of the proposal can cancel it.
+    let keys = testkit.network().validators()[1].service_keypair();
+    let block = testkit.create_block_with_transaction(cancel.clone().sign(
+        SUPERVISOR_INSTANCE_ID,
+        keys.0,
+        &keys.1,
+    ));
+    let status = block.transactions[0].status();
+    assert_eq!(status, Err(&Error::NotProposalAuthor.into()));
+    assert_eq!(config_propose_entry(&testkit), Some(config_proposal));
+
+    let keys = testkit.network().us().service_keypair();
+    testkit
+        .create_block_with_transaction(cancel.sign(SUPERVISOR_INSTANCE_ID, keys.0, &keys.1))
+        .transactions[0]
+        .status()
+        .expect("Transaction with cancellation discarded.");
+    assert_eq!(config_propose_entry(&testkit), None);
+
+    // Votes for the canceled proposal are not accepted.
+    let keys = testkit.network().validators()[1].service_keypair();
+    let block = testkit.create_block_with_transaction(
+        ConfigVote {
+            propose_hash: proposal_hash,
+        }
+        .sign(SUPERVISOR_INSTANCE_ID, keys.0, &keys.1),
+    );
+    let status = block.transactions[0].status();
+    assert_eq!(status, Err(&Error::ConfigProposeNotRegistered.into()));
+}
+
 #[test]
 fn test_propose_config_change_by_incorrect_validator() {
     let mut testkit = testkit_with_supervisor(1);
@@ -18,12 +18,15 @@ use exonum_testkit::{ApiKind, TestKit, TestKitApi};
 use exonum::blockchain::ConsensusConfig;
 use exonum::{
     crypto::Hash,
-    helpers::ValidatorId,
-    runtime::{rust::Transaction, SUPERVISOR_INSTANCE_ID},
+    helpers::{Height, ValidatorId},
+    runtime::{rust::Transaction, SnapshotExt, SUPERVISOR_INSTANCE_ID},
 };
 
 use crate::utils::*;
-use exonum_supervisor::{ConfigProposalWithHash, ConfigPropose, ConfigVote};
+use exonum_supervisor::{
+    supervisor_name, ConfigCancel, ConfigProposalOutcome, ConfigProposalWithHash, ConfigPropose,
+    ConfigVote, ProposalHistory, ProposalHistoryQuery, Schema,
+};
 
 fn actual_consensus_config(api: &TestKitApi) -> ConsensusConfig {
     api.public(ApiKind::Service("supervisor"))
@@ -55,6 +58,15 @@ fn confirm_config(api: &TestKitApi, confirm: ConfigVote) -> Hash {
     hash
 }
 
+fn cancel_config(api: &TestKitApi, cancel: ConfigCancel) -> Hash {
+    let hash: Hash = api
+        .private(ApiKind::Service("supervisor"))
+        .query(&cancel)
+        .post("cancel-config")
+        .unwrap();
+    hash
+}
+
 fn configuration_number(api: &TestKitApi) -> u64 {
     api.private(ApiKind::Service("supervisor"))
         .get("configuration-number")
@@ -202,3 +214,75 @@ fn test_configuration_number() {
     let new_configuration_number = configuration_number(&testkit.api());
     assert_eq!(new_configuration_number, 1);
 }
+
+/// Checks that finished proposals are recorded in the proposal history.
+#[test]
+fn test_config_proposal_history() {
+    let mut testkit = testkit_with_supervisor(2);
+    let history: ProposalHistory = testkit
+        .api()
+        .public(ApiKind::Service("supervisor"))
+        .get("config-proposal-history")
+        .unwrap();
+    assert_eq!(history.total, 0);
+    assert!(history.records.is_empty());
+
+    // Apply some config.
+    apply_config(&mut testkit);
+
+    // Create a proposal and cancel it.
+    let config_proposal = ConfigProposeBuilder::new(Height(10))
+        .configuration_number(1)
+        .extend_consensus_config_propose(consensus_config_propose_second_variant(&testkit))
+        .build();
+    let proposal_hash = config_proposal.object_hash();
+    create_proposal(&testkit.api(), config_proposal.clone());
+    testkit.create_block();
+    let tx_hash = cancel_config(
+        &testkit.api(),
+        ConfigCancel {
+            propose_hash: proposal_hash,
+        },
+    );
+    let block = testkit.create_block();
+    testkit.api().exonum_api().assert_tx_success(tx_hash);
+    assert_eq!(current_config_proposal(&testkit.api()), None);
+
+    let history: ProposalHistory = testkit
+        .api()
+        .public(ApiKind::Service("supervisor"))
+        .get("config-proposal-history")
+        .unwrap();
+    assert_eq!(history.total, 2);
+    assert_eq!(history.records[0].outcome, ConfigProposalOutcome::Applied);
+    assert_eq!(history.records[0].height, CFG_CHANGE_HEIGHT);
+    assert_eq!(history.records[0].confirmations.len(), 2);
+
+    // Check the page of the history obtained via the private API.
+    let history: ProposalHistory = testkit
+        .api()
+        .private(ApiKind::Service("supervisor"))
+        .query(&ProposalHistoryQuery {
+            start: Some(1),
+            count: Some(1),
+        })
+        .get("config-proposal-history")
+        .unwrap();
+    assert_eq!(history.total, 2);
+    assert_eq!(history.records.len(), 1);
+
+    let record = &history.records[0];
+    let our_key = testkit.network().us().service_keypair().0;
+    assert_eq!(record.outcome, ConfigProposalOutcome::Canceled);
+    assert_eq!(record.height, block.height());
+    assert_eq!(record.proposal.propose_hash, proposal_hash);
+    assert_eq!(record.proposal.config_propose, config_proposal);
+    assert_eq!(record.proposer, Some(our_key));
+    assert_eq!(record.confirmations, vec![our_key]);
+    assert!(record.rejections.is_empty());
+
+    // Votes for the finalized proposal are kept only in the history.
+    let snapshot = testkit.snapshot();
+    let schema = Schema::new(snapshot.for_service(supervisor_name()).unwrap());
+    assert_eq!(schema.config_confirms.confirmations(&proposal_hash), 0);
+}
@@ -0,0 +1,191 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_testkit::{ApiKind, TestKit, TestKitApi};
+
+use exonum::{
+    crypto::Hash,
+    helpers::Height,
+    runtime::{
+        rust::Transaction, ArtifactId, RuntimeIdentifier, SnapshotExt, SUPERVISOR_INSTANCE_ID,
+    },
+};
+use exonum_merkledb::ObjectHash;
+use exonum_supervisor::{supervisor_name, DeployPlan, Error, Schema, StartService};
+
+use crate::{
+    artifact_default, artifact_exists,
+    inc::{SERVICE_ID, SERVICE_NAME},
+    service_instance_exists, testkit_with_inc_service,
+    testkit_with_inc_service_and_static_instance,
+    utils::{check_service_actual_param, consensus_config_propose_first_variant},
+};
+
+fn request_deploy_plan(api: &TestKitApi, plan: DeployPlan) -> Hash {
+    let hash: Hash = api
+        .private(ApiKind::Service("supervisor"))
+        .query(&plan)
+        .post("request-deploy-plan")
+        .unwrap();
+    hash
+}
+
+fn pending_deploy_plan(api: &TestKitApi) -> Option<DeployPlan> {
+    api.public(ApiKind::Service("supervisor"))
+        .get("deploy-plan")
+        .unwrap()
+}
+
+/// Returns the height of the block in which the changes of the failed plan were applied.
+fn deploy_plan_failure(testkit: &TestKit, plan: &DeployPlan) -> Option<Height> {
+    let snapshot = testkit.snapshot();
+    let snapshot = snapshot.for_service(supervisor_name()).unwrap();
+    Schema::new(snapshot)
+        .failed_deploy_plans
+        .get(&plan.object_hash())
+        .map(Height)
+}
+
+/// Returns the number of the votes for the plan.
+fn deploy_plan_votes(testkit: &TestKit, plan: &DeployPlan) -> usize {
+    let snapshot = testkit.snapshot();
+    let snapshot = snapshot.for_service(supervisor_name()).unwrap();
+    Schema::new(snapshot)
+        .deploy_plan_requests
+        .confirmations(plan)
+}
+
+fn start_service_request(artifact: ArtifactId, name: &str) -> StartService {
+    StartService {
+        artifact,
+        name: name.into(),
+        config: Vec::default(),
+        instance_id: None,
+    }
+}
+
+/// Checks that the plan deploys the artifact, starts the service and applies
+/// the consensus config.
+#[test]
+fn test_deploy_plan_normal_workflow() {
+    let mut testkit = testkit_with_inc_service();
+    let artifact = artifact_default();
+    let instance_name = "plan_service";
+    let new_consensus_config = consensus_config_propose_first_variant(&testkit);
+
+    let plan = DeployPlan::new(Height(5))
+        .deploy_artifact(artifact.clone(), Vec::default())
+        .start_service(start_service_request(artifact.clone(), instance_name))
+        .consensus_config(new_consensus_config.clone());
+
+    let hash = request_deploy_plan(&testkit.api(), plan.clone());
+    testkit.create_block();
+    let api = testkit.api();
+    api.exonum_api().assert_tx_success(hash);
+    assert_eq!(pending_deploy_plan(&api), Some(plan.clone()));
+    assert!(!artifact_exists(&api, &artifact.name));
+
+    // Block with the deploy confirmation completes the deployment.
+    testkit.create_block();
+    let api = testkit.api();
+    assert!(artifact_exists(&api, &artifact.name));
+    assert!(!service_instance_exists(&api, instance_name));
+
+    // Changes of the plan are applied at the next height.
+    testkit.create_block();
+    let api = testkit.api();
+    assert_eq!(pending_deploy_plan(&api), None);
+    assert!(service_instance_exists(&api, instance_name));
+    assert_eq!(testkit.consensus_config(), new_consensus_config);
+    assert_eq!(deploy_plan_failure(&testkit, &plan), None);
+    assert_eq!(deploy_plan_votes(&testkit, &plan), 0);
+}
+
+/// Checks that the plan with incorrect changes is discarded without applying any of them.
+#[test]
+fn test_deploy_plan_with_incorrect_changes() {
+    let mut testkit = testkit_with_inc_service_and_static_instance();
+    let artifact = artifact_default();
+    let consensus_config = testkit.consensus_config();
+
+    // Instance with the same name is already running.
+    let plan = DeployPlan::new(Height(5))
+        .deploy_artifact(artifact.clone(), Vec::default())
+        .consensus_config(consensus_config_propose_first_variant(&testkit))
+        .start_service(start_service_request(artifact.clone(), SERVICE_NAME));
+
+    let hash = request_deploy_plan(&testkit.api(), plan.clone());
+    testkit.create_block();
+    testkit.api().exonum_api().assert_tx_success(hash);
+
+    // Artifact is already deployed, so the changes are processed at the next height.
+    testkit.create_block();
+    assert_eq!(pending_deploy_plan(&testkit.api()), None);
+    assert_eq!(testkit.consensus_config(), consensus_config);
+    assert_eq!(deploy_plan_failure(&testkit, &plan), Some(Height(2)));
+    // Votes for the failed plan are removed, so it can be requested anew.
+    assert_eq!(deploy_plan_votes(&testkit, &plan), 0);
+}
+
+/// Checks that changes of the plan are rolled back if the application of one of them
+/// fails after the others are applied, and the plan is marked as failed.
+#[test]
+fn test_deploy_plan_rollback() {
+    let mut testkit = testkit_with_inc_service_and_static_instance();
+    let artifact = artifact_default();
+    let consensus_config = testkit.consensus_config();
+
+    // The service writes its new config and then returns an error.
+    let plan = DeployPlan::new(Height(5))
+        .deploy_artifact(artifact, Vec::default())
+        .consensus_config(consensus_config_propose_first_variant(&testkit))
+        .service_config(SERVICE_ID, "apply_error".to_owned());
+
+    let hash = request_deploy_plan(&testkit.api(), plan.clone());
+    testkit.create_block();
+    testkit.api().exonum_api().assert_tx_success(hash);
+
+    // The application of the changes fails and is rolled back.
+    testkit.create_block();
+    assert_eq!(pending_deploy_plan(&testkit.api()), Some(plan.clone()));
+    assert_eq!(testkit.consensus_config(), consensus_config);
+    check_service_actual_param(&testkit, None);
+
+    // The plan is marked as failed instead of being retried.
+    testkit.create_block();
+    assert_eq!(pending_deploy_plan(&testkit.api()), None);
+    assert_eq!(deploy_plan_failure(&testkit, &plan), Some(Height(2)));
+    assert_eq!(testkit.consensus_config(), consensus_config);
+    check_service_actual_param(&testkit, None);
+}
+
+#[test]
+fn test_deploy_plan_with_unknown_artifact() {
+    let mut testkit = testkit_with_inc_service();
+    let unknown_artifact = ArtifactId {
+        runtime_id: RuntimeIdentifier::Rust as _,
+        name: "unknown:1.0.0".into(),
+    };
+
+    let plan = DeployPlan::new(Height(5))
+        .deploy_artifact(artifact_default(), Vec::default())
+        .start_service(start_service_request(unknown_artifact, "plan_service"));
+
+    let keys = testkit.network().us().service_keypair();
+    let block =
+        testkit.create_block_with_transaction(plan.sign(SUPERVISOR_INSTANCE_ID, keys.0, &keys.1));
+    let status = block.transactions[0].status();
+    assert_eq!(status, Err(&Error::UnknownArtifact.into()));
+    assert_eq!(pending_deploy_plan(&testkit.api()), None);
+}
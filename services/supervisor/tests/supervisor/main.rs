@@ -38,7 +38,9 @@ use crate::{
 mod config;
 mod config_api;
 mod consensus_config;
+mod deploy_plan;
 mod inc;
+mod policy;
 mod proto;
 mod utils;
 
@@ -82,6 +84,12 @@ fn find_instance_id(api: &TestKitApi, instance_name: &str) -> InstanceId {
         .id
 }
 
+fn next_instance_id(api: &TestKitApi) -> InstanceId {
+    api.public(ApiKind::Service("supervisor"))
+        .get("next-instance-id")
+        .unwrap()
+}
+
 fn deploy_artifact(api: &TestKitApi, request: DeployRequest) -> crypto::Hash {
     let hash: crypto::Hash = api
         .private(ApiKind::Service("supervisor"))
@@ -169,6 +177,7 @@ fn start_service_request(
         artifact,
         name: name.into(),
         config: Vec::default(),
+        instance_id: None,
     };
 
     ConfigPropose::new(0, deadline_height).start_service(request)
@@ -529,11 +538,13 @@ fn test_start_two_services_in_one_request() {
         artifact: artifact.clone(),
         name: instance_name_1.into(),
         config: Vec::default(),
+        instance_id: None,
     };
     let request_2 = StartService {
         artifact: artifact.clone(),
         name: instance_name_2.into(),
         config: Vec::default(),
+        instance_id: None,
     };
 
     let request = ConfigPropose::new(0, deadline)
@@ -992,11 +1003,13 @@ fn test_id_assignment() {
         artifact: artifact.clone(),
         name: instance_name_1.into(),
         config: Vec::default(),
+        instance_id: None,
     };
     let request_2 = StartService {
         artifact: artifact.clone(),
         name: instance_name_2.into(),
         config: Vec::default(),
+        instance_id: None,
     };
 
     let request = ConfigPropose::new(0, deadline)
@@ -1039,6 +1052,7 @@ fn test_id_assignment_sparse() {
         artifact: artifact.clone(),
         name: instance_name.into(),
         config: Vec::default(),
+        instance_id: None,
     };
 
     let request = ConfigPropose::new(0, deadline).start_service(request);
@@ -1051,3 +1065,73 @@ fn test_id_assignment_sparse() {
     let api = testkit.api();
     assert_eq!(find_instance_id(&api, instance_name), max_builtin_id + 1);
 }
+
+/// Checks that the instance can be started with the explicit ID, and that the previewed ID
+/// is assigned to the next instance without skipping IDs reserved in the same request.
+#[test]
+fn test_explicit_instance_id() {
+    let mut testkit = testkit_with_inc_service();
+    deploy_default(&mut testkit);
+
+    let next_id = next_instance_id(&testkit.api());
+    assert_eq!(next_id, SUPERVISOR_INSTANCE_ID + 1);
+
+    let artifact = artifact_default();
+    let deadline = testkit.height().next();
+
+    // The first instance reserves the ID which would be assigned to the second one.
+    let request_1 = StartService {
+        artifact: artifact.clone(),
+        name: "inc".into(),
+        config: Vec::default(),
+        instance_id: Some(next_id),
+    };
+    let request_2 = StartService {
+        artifact: artifact.clone(),
+        name: "inc2".into(),
+        config: Vec::default(),
+        instance_id: None,
+    };
+
+    let request = ConfigPropose::new(0, deadline)
+        .start_service(request_2)
+        .start_service(request_1);
+
+    let api = testkit.api();
+    let hash = start_service(&api, request);
+    testkit.create_block();
+    api.exonum_api().assert_tx_success(hash);
+
+    let api = testkit.api();
+    assert_eq!(find_instance_id(&api, "inc"), next_id);
+    assert_eq!(find_instance_id(&api, "inc2"), next_id + 1);
+    assert_eq!(next_instance_id(&api), next_id + 2);
+}
+
+/// Checks that the ID of the existing instance cannot be requested.
+#[test]
+fn test_explicit_instance_id_exists() {
+    let mut testkit = testkit_with_inc_service();
+    deploy_default(&mut testkit);
+    let instance_id = start_service_instance(&mut testkit, "inc");
+
+    let request = StartService {
+        artifact: artifact_default(),
+        name: "inc2".into(),
+        config: Vec::default(),
+        instance_id: Some(instance_id),
+    };
+    let request = ConfigPropose::new(1, testkit.height().next()).start_service(request);
+
+    let api = testkit.api();
+    let hash = start_service(&api, request);
+    testkit.create_block();
+
+    let expected_status = Err(ExecutionError {
+        kind: ExecutionErrorKind::Service { code: 18 },
+        description: "Instance with the given identifier already exists.".into(),
+    });
+    api.exonum_api()
+        .assert_tx_status(hash, &expected_status.into());
+    assert!(!service_instance_exists(&testkit.api(), "inc2"));
+}
@@ -0,0 +1,278 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use exonum_merkledb::{BinaryValue, ObjectHash};
+use exonum_testkit::{ApiKind, TestKitBuilder};
+
+use exonum::{
+    crypto,
+    helpers::{Height, ValidatorId},
+    keys::Keys,
+    runtime::SUPERVISOR_INSTANCE_ID,
+};
+
+use crate::{utils::*, SERVICE_ID as CONFIG_SERVICE_ID};
+use exonum_supervisor::{
+    mode::ModeKind, ApprovalPolicy, ApprovalThreshold, ConfigChange, DecentralizedSupervisor,
+    Error, InstancePolicy, ServiceConfig, SimpleSupervisor, SupervisorConfig, ValidatorWeight,
+};
+
+#[test]
+fn test_consensus_policy_requires_all_validators() {
+    let config = SupervisorConfig {
+        consensus_policy: Some(ApprovalPolicy::new(ApprovalThreshold::All)),
+        ..SupervisorConfig::default()
+    };
+    let mut testkit = testkit_with_supervisor_config(4, config);
+    let base_consensus_config = testkit.consensus_config();
+
+    let cfg_change_height = Height(4);
+    let consensus_config = consensus_config_propose_first_variant(&testkit);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config.clone())
+        .build();
+    let proposal_hash = config_proposal.object_hash();
+
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+
+    // Byzantine majority of validators is not enough to approve the consensus change.
+    let mut confirmations =
+        build_confirmation_transactions(&testkit, proposal_hash, ValidatorId(0));
+    confirmations.pop();
+    testkit.create_block_with_transactions(confirmations);
+    testkit.create_blocks_until(cfg_change_height.next());
+    assert_eq!(config_propose_entry(&testkit), None);
+    assert_eq!(testkit.consensus_config(), base_consensus_config);
+
+    // Proposal confirmed by all validators is applied.
+    let cfg_change_height = Height(10);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config.clone())
+        .configuration_number(1)
+        .build();
+    let proposal_hash = config_proposal.object_hash();
+
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+    testkit.create_block_with_transactions(build_confirmation_transactions(
+        &testkit,
+        proposal_hash,
+        ValidatorId(0),
+    ));
+    testkit.create_blocks_until(cfg_change_height);
+    assert_eq!(testkit.consensus_config(), consensus_config);
+}
+
+#[test]
+fn test_weighted_validators() {
+    let keys = (0..4)
+        .map(|_| {
+            let (consensus_pk, consensus_sk) = crypto::gen_keypair();
+            let (service_pk, service_sk) = crypto::gen_keypair();
+            Keys::from_keys(consensus_pk, consensus_sk, service_pk, service_sk)
+        })
+        .collect::<Vec<_>>();
+    let config = SupervisorConfig {
+        validator_weights: vec![ValidatorWeight {
+            service_key: keys[0].service_pk(),
+            weight: 4,
+        }],
+        consensus_policy: Some(ApprovalPolicy::new(ApprovalThreshold::Fraction {
+            numerator: 1,
+            denominator: 2,
+        })),
+        ..SupervisorConfig::default()
+    };
+    let mut testkit = TestKitBuilder::validator()
+        .with_keys(keys)
+        .with_rust_service(DecentralizedSupervisor::builtin_instance(config))
+        .create();
+
+    let cfg_change_height = Height(3);
+    let consensus_config = consensus_config_propose_first_variant(&testkit);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config.clone())
+        .build();
+
+    // The vote of the validator with weight 4 out of total 7 is enough to apply the change.
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+    testkit.create_blocks_until(cfg_change_height);
+    assert_eq!(testkit.consensus_config(), consensus_config);
+}
+
+#[test]
+fn test_admin_key_reconfigures_service() {
+    let admin_keys = crypto::gen_keypair();
+    let config = SupervisorConfig {
+        instance_policies: vec![InstancePolicy {
+            instance_id: CONFIG_SERVICE_ID,
+            policy: ApprovalPolicy::new(ApprovalThreshold::ByzantineMajority)
+                .with_admin_key(admin_keys.0),
+        }],
+        ..SupervisorConfig::default()
+    };
+    let mut testkit = testkit_with_supervisor_config(4, config);
+
+    // Admin of the service cannot propose consensus changes.
+    let config_proposal = ConfigProposeBuilder::new(CFG_CHANGE_HEIGHT)
+        .extend_consensus_config_propose(consensus_config_propose_first_variant(&testkit))
+        .extend_service_config_propose("admin".to_owned())
+        .build();
+    let block = testkit.create_block_with_transaction(
+        config_proposal.sign_for_supervisor(admin_keys.0, &admin_keys.1),
+    );
+    assert_eq!(
+        block.transactions[0].status(),
+        Err(&Error::UnknownAuthor.into())
+    );
+
+    // Service configuration is changed by the admin alone.
+    let cfg_change_height = Height(4);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_service_config_propose("admin".to_owned())
+        .build();
+    testkit
+        .create_block_with_transaction(
+            config_proposal.sign_for_supervisor(admin_keys.0, &admin_keys.1),
+        )
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+    testkit.create_blocks_until(cfg_change_height);
+    assert_eq!(config_propose_entry(&testkit), None);
+    check_service_actual_param(&testkit, Some("admin".to_owned()));
+}
+
+#[test]
+fn test_switch_from_simple_to_decentralized_mode() {
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_rust_service(SimpleSupervisor::new())
+        .create();
+    let base_consensus_config = testkit.consensus_config();
+
+    // In the simple mode, the supervisor config is changed by a single validator.
+    let supervisor_config = SupervisorConfig {
+        mode: Some(ModeKind::Decentralized),
+        ..SupervisorConfig::default()
+    };
+    let cfg_change_height = Height(3);
+    let mut config_proposal = ConfigProposeBuilder::new(cfg_change_height).build();
+    config_proposal
+        .changes
+        .push(ConfigChange::Service(ServiceConfig {
+            instance_id: SUPERVISOR_INSTANCE_ID,
+            params: supervisor_config.clone().into_bytes(),
+        }));
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+    testkit.create_blocks_until(cfg_change_height);
+
+    let actual_config: SupervisorConfig = testkit
+        .api()
+        .public(ApiKind::Service("supervisor"))
+        .get("supervisor-config")
+        .unwrap();
+    assert_eq!(actual_config, supervisor_config);
+
+    // In the decentralized mode, a single vote is not enough anymore.
+    let cfg_change_height = Height(6);
+    let config_proposal = ConfigProposeBuilder::new(cfg_change_height)
+        .extend_consensus_config_propose(consensus_config_propose_first_variant(&testkit))
+        .configuration_number(1)
+        .build();
+    testkit
+        .create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ))
+        .transactions[0]
+        .status()
+        .expect("Transaction with change propose discarded.");
+    testkit.create_blocks_until(cfg_change_height.next());
+    assert_eq!(config_propose_entry(&testkit), None);
+    assert_eq!(testkit.consensus_config(), base_consensus_config);
+}
+
+#[test]
+fn test_supervisor_config_with_too_large_values() {
+    let mut testkit = testkit_with_supervisor_and_service(4);
+    let too_large = u64::from(u32::max_value()) + 1;
+    let validator_key = testkit.network().validators()[1].service_keypair().0;
+
+    let configs = vec![
+        SupervisorConfig {
+            validator_weights: vec![ValidatorWeight {
+                service_key: validator_key,
+                weight: too_large,
+            }],
+            ..SupervisorConfig::default()
+        },
+        SupervisorConfig {
+            consensus_policy: Some(ApprovalPolicy::new(ApprovalThreshold::Fraction {
+                numerator: 1,
+                denominator: too_large,
+            })),
+            ..SupervisorConfig::default()
+        },
+    ];
+
+    for supervisor_config in configs {
+        let mut config_proposal = ConfigProposeBuilder::new(Height(5)).build();
+        config_proposal
+            .changes
+            .push(ConfigChange::Service(ServiceConfig {
+                instance_id: SUPERVISOR_INSTANCE_ID,
+                params: supervisor_config.into_bytes(),
+            }));
+        let block = testkit.create_block_with_transaction(sign_config_propose_transaction(
+            &testkit,
+            config_proposal,
+            ValidatorId(0),
+        ));
+        assert!(block.transactions[0].status().is_err());
+    }
+    assert_eq!(config_propose_entry(&testkit), None);
+}
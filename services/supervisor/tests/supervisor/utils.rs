@@ -28,7 +28,7 @@ use crate::{
 };
 use exonum_supervisor::{
     supervisor_name, ConfigChange, ConfigPropose, ConfigVote, DecentralizedSupervisor, Schema,
-    ServiceConfig,
+    ServiceConfig, SupervisorConfig,
 };
 
 pub const CFG_CHANGE_HEIGHT: Height = Height(2);
@@ -163,6 +163,17 @@ pub fn testkit_with_supervisor_and_service(validator_count: u16) -> TestKit {
         .create()
 }
 
+pub fn testkit_with_supervisor_config(validator_count: u16, config: SupervisorConfig) -> TestKit {
+    let service = ConfigChangeService;
+    let collection =
+        InstanceCollection::new(service).with_instance(CONFIG_SERVICE_ID, CONFIG_SERVICE_NAME, ());
+    TestKitBuilder::validator()
+        .with_validators(validator_count)
+        .with_rust_service(DecentralizedSupervisor::builtin_instance(config))
+        .with_rust_service(collection)
+        .create()
+}
+
 pub fn testkit_with_supervisor_and_2_services(validator_count: u16) -> TestKit {
     let service = ConfigChangeService;
     let collection = InstanceCollection::new(service)
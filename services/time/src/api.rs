@@ -15,9 +15,60 @@
 //! Exonum-time API.
 
 use chrono::{DateTime, Utc};
-use exonum::{crypto::PublicKey, runtime::rust::api};
+use exonum::{
+    blockchain::{BlockProof, IndexCoordinates, SchemaOrigin},
+    crypto::{Hash, PublicKey},
+    helpers::Height,
+    impl_api_schema,
+    runtime::rust::api,
+};
+use exonum_merkledb::{access::Access, ListProof, MapProof};
 
-use crate::TimeSchema;
+use crate::{
+    config::Config,
+    schema::{TimeRecord, TimeSchema, TIME_HISTORY_INDEX_ID},
+};
+
+/// Query for the consolidated time at the certain height.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeightQuery {
+    /// Height of the block.
+    pub height: u64,
+}
+
+impl_api_schema!(HeightQuery { height: u64 });
+
+/// Query for the first height at which the consolidated time exceeds the certain time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeQuery {
+    /// Time to compare the consolidated time with.
+    pub time: DateTime<Utc>,
+}
+
+impl_api_schema!(TimeQuery {
+    time: DateTime<Utc>,
+});
+
+/// Record of the consolidated time history together with the proof of its authenticity.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeRecordInfo {
+    /// Found record, if any.
+    pub record: Option<TimeRecord>,
+    /// Proof of the latest block.
+    pub block_proof: BlockProof,
+    /// Proof of the time history table in the blockchain state.
+    pub to_table: MapProof<IndexCoordinates, Hash>,
+    /// Proof of the records adjacent to the search boundary. Comparing them
+    /// with the query proves that the found record, if any, is the requested one.
+    pub to_records: ListProof<TimeRecord>,
+}
+
+impl_api_schema!(TimeRecordInfo {
+    record: Option<TimeRecord>,
+    block_proof: BlockProof,
+    to_table: MapProof<IndexCoordinates, Hash>,
+    to_records: ListProof<TimeRecord>,
+});
 
 /// Structure for saving public key of the validator and last known local time.
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +79,11 @@ pub struct ValidatorTime {
     pub time: Option<DateTime<Utc>>,
 }
 
+impl_api_schema!(ValidatorTime {
+    public_key: PublicKey,
+    time: Option<DateTime<Utc>>,
+});
+
 /// Implement the public API for Exonum time.
 #[derive(Debug, Clone)]
 pub struct PublicApi;
@@ -41,11 +97,74 @@ impl PublicApi {
         Ok(TimeSchema::new(state.service_data()).time.get())
     }
 
+    /// Endpoint for getting the configuration of the time oracle.
+    pub fn config(state: &api::ServiceApiState<'_>, _query: ()) -> api::Result<Config> {
+        Ok(TimeSchema::new(state.service_data())
+            .config
+            .get()
+            .unwrap_or_default())
+    }
+
+    /// Endpoint for getting the consolidated time at the given height.
+    pub fn time_at_height(
+        state: &api::ServiceApiState<'_>,
+        query: HeightQuery,
+    ) -> api::Result<TimeRecordInfo> {
+        let schema = TimeSchema::new(state.service_data());
+        let index = schema.history_index_at_height(Height(query.height));
+        // The search boundary lies between the found record and the next one.
+        let boundary = index.map_or(0, |index| index + 1);
+        Ok(Self::record_info(state, &schema, index, boundary))
+    }
+
+    /// Endpoint for getting the first height at which the consolidated time
+    /// is greater than the given one.
+    pub fn first_height_after(
+        state: &api::ServiceApiState<'_>,
+        query: TimeQuery,
+    ) -> api::Result<TimeRecordInfo> {
+        let schema = TimeSchema::new(state.service_data());
+        let index = schema.history_index_after(query.time);
+        // The search boundary lies between the previous record and the found one.
+        let boundary = index.unwrap_or_else(|| schema.time_history.len());
+        Ok(Self::record_info(state, &schema, index, boundary))
+    }
+
+    fn record_info<T: Access>(
+        state: &api::ServiceApiState<'_>,
+        schema: &TimeSchema<T>,
+        index: Option<u64>,
+        boundary: u64,
+    ) -> TimeRecordInfo {
+        let blockchain_schema = state.data().for_core();
+        let block_proof = blockchain_schema
+            .block_and_precommits(blockchain_schema.height())
+            .expect("Latest block is missing");
+        let to_table = blockchain_schema.state_hash_aggregator().get_proof(
+            SchemaOrigin::Service(state.instance().id).coordinate_for(TIME_HISTORY_INDEX_ID),
+        );
+
+        TimeRecordInfo {
+            record: index.and_then(|index| schema.time_history.get(index)),
+            block_proof,
+            to_table,
+            to_records: schema
+                .time_history
+                .get_range_proof(boundary.saturating_sub(1)..boundary + 1),
+        }
+    }
+
     /// Extend API.
     pub fn wire(self, builder: &mut api::ServiceApiBuilder) {
         builder
             .public_scope()
-            .endpoint("v1/current_time", Self::current_time);
+            .endpoint("v1/current_time", Self::current_time)
+            .endpoint("v1/config", Self::config)
+            .endpoint("v1/time_history/at_height", Self::time_at_height)
+            .endpoint(
+                "v1/time_history/first_height_after",
+                Self::first_height_after,
+            );
     }
 }
 
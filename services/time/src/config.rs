@@ -0,0 +1,140 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::Duration;
+use exonum::{
+    api::{ApiSchema, Schema},
+    runtime::{rust::CallContext, DispatcherError, ExecutionError},
+};
+use exonum_oracle::proto::Config as OracleConfigProto;
+use exonum_proto::ProtobufConvert;
+use exonum_supervisor::Configure;
+
+use crate::{proto, schema::TimeSchema, transactions::Error, TimeService};
+
+pub use exonum_oracle::config::Config as OracleConfig;
+
+/// Configuration of the time oracle.
+///
+/// The parameters shared with the generic oracle are set by [`OracleConfig`]: the maximum
+/// age of the validator times in blocks and the minimum number of fresh times required
+/// to update the consolidated time. The default configuration corresponds to the original
+/// consolidation rule: all known times of the validators are taken into account,
+/// and the consolidated time is updated as soon as 2/3+1 of the validators have sent
+/// their times.
+///
+/// [`OracleConfig`]: struct.OracleConfig.html
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BinaryValue, ObjectHash)]
+pub struct Config {
+    /// Parameters of the consolidation shared with the generic oracle.
+    #[serde(flatten)]
+    pub oracle: OracleConfig,
+    /// Maximum forward drift of the consolidated time during a single update, in seconds.
+    /// If the validator times are further ahead, the consolidated time is moved forward
+    /// by this value only. If not specified, the drift is not limited.
+    /// Values greater than `MAX_DURATION_SECS` are rejected.
+    pub max_forward_drift: Option<u64>,
+}
+
+impl ApiSchema for Config {
+    fn api_schema() -> Schema {
+        // Parameters of the oracle are flattened into the configuration.
+        let mut schema = OracleConfig::api_schema();
+        schema["properties"]["max_forward_drift"] = Option::<u64>::api_schema();
+        schema
+    }
+}
+
+/// The maximum value of the durations in the configuration, in seconds.
+pub const MAX_DURATION_SECS: u64 = i64::max_value() as u64 / 1000;
+
+impl Config {
+    /// Returns the maximum forward drift of the consolidated time, or `None` for values
+    /// not representable as `Duration`; these values are rejected by `check_config`.
+    pub fn max_forward_drift(&self) -> Option<Duration> {
+        self.max_forward_drift
+            .filter(|&value| value <= MAX_DURATION_SECS)
+            .map(|value| Duration::seconds(value as i64))
+    }
+}
+
+impl ProtobufConvert for Config {
+    type ProtoStruct = proto::Config;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let oracle = self.oracle.to_pb();
+        let mut pb = Self::ProtoStruct::new();
+        pb.set_max_age(oracle.get_max_age());
+        pb.set_min_sources(oracle.get_min_sources());
+        pb.set_max_forward_drift(self.max_forward_drift.unwrap_or(0));
+        pb
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> Result<Self, failure::Error> {
+        let mut oracle = OracleConfigProto::new();
+        oracle.set_max_age(pb.get_max_age());
+        oracle.set_min_sources(pb.get_min_sources());
+        Ok(Self {
+            oracle: OracleConfig::from_pb(oracle)?,
+            // As with the oracle parameters, zero value denotes the unlimited drift.
+            max_forward_drift: Some(pb.get_max_forward_drift()).filter(|&value| value != 0),
+        })
+    }
+}
+
+/// Verifies that the configuration is applicable to the current validators.
+pub(crate) fn check_config(
+    context: &CallContext<'_>,
+    config: &Config,
+) -> Result<(), ExecutionError> {
+    exonum_oracle::config::check_config(context, &config.oracle)?;
+    if config
+        .max_forward_drift
+        .map_or(false, |value| value > MAX_DURATION_SECS)
+    {
+        return Err(Error::InvalidConfig.into());
+    }
+    Ok(())
+}
+
+impl Configure for TimeService {
+    type Params = Config;
+
+    fn verify_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+        check_config(&context, &params)
+    }
+
+    fn apply_config(
+        &self,
+        context: CallContext<'_>,
+        params: Self::Params,
+    ) -> Result<(), ExecutionError> {
+        context
+            .caller()
+            .as_supervisor()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+
+        TimeSchema::new(context.service_data()).config.set(params);
+        Ok(())
+    }
+}
@@ -33,8 +33,12 @@ extern crate exonum_derive;
 
 /// Node API.
 pub mod api;
+/// Service configuration.
+pub mod config;
 /// Protobuf generated structs.
 pub mod proto;
+/// Timers set by other services.
+pub mod scheduler;
 /// Database schema.
 pub mod schema;
 /// System time provider.
@@ -45,15 +49,17 @@ pub mod transactions;
 use exonum::{
     crypto::Hash,
     runtime::{
-        rust::{api::ServiceApiBuilder, AfterCommitContext, Service},
-        BlockchainData,
+        rust::{api::ServiceApiBuilder, AfterCommitContext, CallContext, Service},
+        BlockchainData, DispatcherError, ExecutionError,
     },
 };
-use exonum_merkledb::Snapshot;
+use exonum_merkledb::{BinaryValue, Snapshot};
 
 use std::sync::Arc;
 
 use crate::{
+    config::{check_config, Config},
+    scheduler::{ScheduledCall, Scheduler, TimeCallbackCall},
     schema::TimeSchema,
     time_provider::{SystemTimeProvider, TimeProvider},
     transactions::{TimeOracleInterface, TxTime},
@@ -64,17 +70,58 @@ use crate::{
 
 /// Define the service.
 #[derive(Debug, ServiceDispatcher)]
-#[service_dispatcher(implements("TimeOracleInterface"))]
+#[service_dispatcher(implements(
+    "TimeOracleInterface",
+    "Scheduler",
+    "exonum_supervisor::Configure<Params = Config>"
+))]
 pub struct TimeService {
     /// Current time.
     time: Arc<dyn TimeProvider>,
 }
 
 impl Service for TimeService {
+    fn initialize(&self, context: CallContext<'_>, params: Vec<u8>) -> Result<(), ExecutionError> {
+        // Empty parameters correspond to the default configuration.
+        let config =
+            Config::from_bytes(params.into()).map_err(DispatcherError::malformed_arguments)?;
+        check_config(&context, &config)?;
+        TimeSchema::new(context.service_data()).config.set(config);
+        Ok(())
+    }
+
     fn state_hash(&self, data: BlockchainData<&dyn Snapshot>) -> Vec<Hash> {
         TimeSchema::new(data.for_executing_service()).state_hash()
     }
 
+    /// Fires timers which time has been reached by the consolidated time.
+    ///
+    /// Each callback is isolated: if it fails or panics, its changes are reverted and the error
+    /// is logged, while the timer is removed from the schedule anyway.
+    fn before_commit(&self, mut context: CallContext<'_>) {
+        let due_calls = TimeSchema::new(context.service_data()).take_due_calls();
+        for ScheduledCall { instance_id, timer } in due_calls {
+            log::trace!(
+                "Firing timer {:?} of the service instance with ID {}",
+                timer,
+                instance_id
+            );
+
+            let result = context.isolate(|mut context| {
+                context
+                    .interface::<TimeCallbackCall<'_>>(instance_id)
+                    .and_then(|mut callback| callback.on_time(timer))
+            });
+            if let Err(e) = result {
+                log::warn!(
+                    "Timer of the service instance with ID {} failed. {}",
+                    instance_id,
+                    e
+                );
+            }
+        }
+    }
+
     /// Creates transaction after commit of the block.
     fn after_commit(&self, context: AfterCommitContext<'_>) {
         if let Some(broadcast) = context.broadcaster() {
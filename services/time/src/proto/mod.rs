@@ -16,6 +16,6 @@
 
 #![allow(bare_trait_objects)]
 
-pub use self::service::TxTime;
+pub use self::service::{Config, ScheduledCall, TimeRecord, Timer, TxTime};
 
 include!(concat!(env!("OUT_DIR"), "/protobuf_mod.rs"));
@@ -0,0 +1,252 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interfaces allowing other services to be notified when the consolidated time
+//! reaches the certain value.
+//!
+//! A service sets a timer by calling the [`Scheduler`] interface of the time oracle
+//! with the help of [`SchedulerCall`]. Once the consolidated time reaches the time
+//! of the timer, the time oracle calls the [`TimeCallback`] interface of the service
+//! within its `before_commit` hook. Thus, the service should implement this interface
+//! in order to receive notifications.
+//!
+//! Fired timers are removed from the schedule even if the callback fails; changes made
+//! by a failed callback are reverted.
+//!
+//! [`Scheduler`]: trait.Scheduler.html
+//! [`SchedulerCall`]: struct.SchedulerCall.html
+//! [`TimeCallback`]: trait.TimeCallback.html
+
+use chrono::{DateTime, Utc};
+use exonum::runtime::{
+    rust::{CallContext, Interface},
+    DispatcherError, ExecutionError, InstanceId, MethodId,
+};
+use exonum_merkledb::BinaryValue;
+use exonum_proto::ProtobufConvert;
+
+use crate::{proto, schema::TimeSchema, transactions::Error, TimeService};
+
+/// Fully qualified name of the [`Scheduler`] interface.
+///
+/// [`Scheduler`]: trait.Scheduler.html
+pub const SCHEDULER_INTERFACE_NAME: &str = "exonum.time.Scheduler";
+
+/// Fully qualified name of the [`TimeCallback`] interface.
+///
+/// [`TimeCallback`]: trait.TimeCallback.html
+pub const TIME_CALLBACK_INTERFACE_NAME: &str = "exonum.time.TimeCallback";
+
+/// Identifier of the [`Scheduler::schedule`] method.
+///
+/// [`Scheduler::schedule`]: trait.Scheduler.html#tymethod.schedule
+const SCHEDULE_METHOD_ID: MethodId = 0;
+
+/// Identifier of the [`TimeCallback::on_time`] method.
+///
+/// [`TimeCallback::on_time`]: trait.TimeCallback.html#tymethod.on_time
+const ON_TIME_METHOD_ID: MethodId = 0;
+
+/// Maximum number of the timers which a single service instance may have scheduled
+/// at the same time.
+pub const MAX_TIMERS_PER_INSTANCE: u64 = 1_000;
+
+/// Maximum size of the timer payload in bytes.
+pub const MAX_TIMER_PAYLOAD_SIZE: usize = 1_024;
+
+/// Timer set by a service to be notified when the consolidated time reaches the certain time.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash,
+)]
+#[protobuf_convert(source = "proto::Timer")]
+pub struct Timer {
+    /// Time at which the timer fires.
+    pub time: DateTime<Utc>,
+    /// Arbitrary data passed back to the service when the timer fires, e.g.,
+    /// an identifier of the auction which should be closed.
+    pub payload: Vec<u8>,
+}
+
+impl Timer {
+    /// Creates a new timer firing at the specified time.
+    pub fn new(time: DateTime<Utc>, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            time,
+            payload: payload.into(),
+        }
+    }
+}
+
+/// Timer scheduled by the service instance.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash,
+)]
+#[protobuf_convert(source = "proto::ScheduledCall")]
+pub struct ScheduledCall {
+    /// Identifier of the service instance which set the timer.
+    pub instance_id: InstanceId,
+    /// Timer set by the service instance.
+    pub timer: Timer,
+}
+
+/// Interface of the time oracle for setting timers.
+pub trait Scheduler {
+    /// Sets a timer for the calling service instance. Setting the same timer twice
+    /// has no effect.
+    ///
+    /// # Execution policy
+    ///
+    /// This method can only be called by other service instances. If the time of the timer
+    /// has been already reached, the timer fires within the current block.
+    ///
+    /// The payload of the timer may not exceed [`MAX_TIMER_PAYLOAD_SIZE`] bytes, and
+    /// a service instance may not have more than [`MAX_TIMERS_PER_INSTANCE`] timers
+    /// which have not fired yet.
+    ///
+    /// [`MAX_TIMER_PAYLOAD_SIZE`]: constant.MAX_TIMER_PAYLOAD_SIZE.html
+    /// [`MAX_TIMERS_PER_INSTANCE`]: constant.MAX_TIMERS_PER_INSTANCE.html
+    fn schedule(&self, context: CallContext<'_>, timer: Timer) -> Result<(), ExecutionError>;
+}
+
+/// Interface which should be implemented by services to be notified about fired timers.
+pub trait TimeCallback {
+    /// Handles the fired timer.
+    ///
+    /// The method is called by the time oracle within its `before_commit` hook, so
+    /// the errors returned are only logged. If the method returns an error or panics,
+    /// the changes made by it are reverted, and the timer is not fired again.
+    fn on_time(&self, context: CallContext<'_>, timer: Timer) -> Result<(), ExecutionError>;
+}
+
+impl Interface for dyn Scheduler {
+    const INTERFACE_NAME: &'static str = SCHEDULER_INTERFACE_NAME;
+
+    fn dispatch(
+        &self,
+        context: CallContext<'_>,
+        method: MethodId,
+        payload: &[u8],
+    ) -> Result<(), ExecutionError> {
+        match method {
+            SCHEDULE_METHOD_ID => self.schedule(
+                context,
+                Timer::from_bytes(payload.into()).map_err(DispatcherError::malformed_arguments)?,
+            ),
+
+            other => {
+                let kind = DispatcherError::NoSuchMethod;
+                let message = format!(
+                    "Method with ID {} is absent in the 'Scheduler' interface of the instance `{}`",
+                    other,
+                    context.instance().name,
+                );
+                Err((kind, message)).map_err(From::from)
+            }
+        }
+    }
+}
+
+impl Interface for dyn TimeCallback {
+    const INTERFACE_NAME: &'static str = TIME_CALLBACK_INTERFACE_NAME;
+
+    fn dispatch(
+        &self,
+        context: CallContext<'_>,
+        method: MethodId,
+        payload: &[u8],
+    ) -> Result<(), ExecutionError> {
+        match method {
+            ON_TIME_METHOD_ID => self.on_time(
+                context,
+                Timer::from_bytes(payload.into()).map_err(DispatcherError::malformed_arguments)?,
+            ),
+
+            other => {
+                let kind = DispatcherError::NoSuchMethod;
+                let message = format!(
+                    "Method with ID {} is absent in the 'TimeCallback' interface of the instance `{}`",
+                    other,
+                    context.instance().name,
+                );
+                Err((kind, message)).map_err(From::from)
+            }
+        }
+    }
+}
+
+/// A helper struct for invoking the [`Scheduler`] interface methods on the time oracle instance.
+///
+/// [`Scheduler`]: trait.Scheduler.html
+#[derive(Debug)]
+pub struct SchedulerCall<'a>(CallContext<'a>);
+
+impl<'a> From<CallContext<'a>> for SchedulerCall<'a> {
+    fn from(context: CallContext<'a>) -> Self {
+        Self(context)
+    }
+}
+
+impl<'a> SchedulerCall<'a> {
+    /// Invoke the corresponding [method](trait.Scheduler.html#tymethod.schedule)
+    /// of the interface.
+    pub fn schedule(&mut self, timer: Timer) -> Result<(), ExecutionError> {
+        self.0
+            .call(SCHEDULER_INTERFACE_NAME, SCHEDULE_METHOD_ID, timer)
+    }
+}
+
+/// A helper struct for invoking the [`TimeCallback`] interface methods on the specified
+/// service instance.
+///
+/// [`TimeCallback`]: trait.TimeCallback.html
+#[derive(Debug)]
+pub struct TimeCallbackCall<'a>(CallContext<'a>);
+
+impl<'a> From<CallContext<'a>> for TimeCallbackCall<'a> {
+    fn from(context: CallContext<'a>) -> Self {
+        Self(context)
+    }
+}
+
+impl<'a> TimeCallbackCall<'a> {
+    /// Invoke the corresponding [method](trait.TimeCallback.html#tymethod.on_time)
+    /// of the interface.
+    pub fn on_time(&mut self, timer: Timer) -> Result<(), ExecutionError> {
+        self.0
+            .call(TIME_CALLBACK_INTERFACE_NAME, ON_TIME_METHOD_ID, timer)
+    }
+}
+
+impl Scheduler for TimeService {
+    fn schedule(&self, context: CallContext<'_>, timer: Timer) -> Result<(), ExecutionError> {
+        let instance_id = context
+            .caller()
+            .as_service()
+            .ok_or(DispatcherError::UnauthorizedCaller)?;
+        if timer.payload.len() > MAX_TIMER_PAYLOAD_SIZE {
+            return Err(Error::TimerPayloadTooLarge.into());
+        }
+
+        let mut schema = TimeSchema::new(context.service_data());
+        let call = ScheduledCall { instance_id, timer };
+        // Rescheduling an existing timer has no effect, so it is not limited.
+        if !schema.contains_call(&call)
+            && schema.timers_count(instance_id) >= MAX_TIMERS_PER_INSTANCE
+        {
+            return Err(Error::TooManyTimers.into());
+        }
+        schema.schedule_call(call);
+        Ok(())
+    }
+}
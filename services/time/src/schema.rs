@@ -16,26 +16,179 @@ use chrono::{DateTime, Utc};
 
 use exonum::{
     blockchain::ValidatorKeys,
-    crypto::{Hash, PublicKey},
+    crypto::{self, Hash, PublicKey},
+    helpers::Height,
+    impl_api_schema,
+    runtime::InstanceId,
 };
 use exonum_merkledb::{
     access::{Access, RawAccessMut},
-    Entry, ObjectHash, ProofMapIndex,
+    BinaryKey, Entry, KeySetIndex, MapIndex, ObjectHash, ProofListIndex, ProofMapIndex,
 };
+use exonum_oracle::aggregation::{AggregationParams, Aggregator, QuorumMin};
+use exonum_proto::ProtobufConvert;
+
+use crate::{config::Config, proto, scheduler::ScheduledCall};
+
+/// Consolidated time recorded at the certain height.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash,
+)]
+#[protobuf_convert(source = "proto::TimeRecord")]
+pub struct TimeRecord {
+    /// Height of the block in which the consolidated time was updated.
+    pub height: Height,
+    /// Consolidated time after the block.
+    pub time: DateTime<Utc>,
+}
+
+impl_api_schema!(TimeRecord {
+    height: Height,
+    time: DateTime<Utc>,
+});
+
+/// Key of a scheduled call. Keys are ordered by the times of the calls, and then
+/// by their hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScheduledCallKey {
+    /// Time at which the call fires.
+    pub time: DateTime<Utc>,
+    /// Hash of the call.
+    pub hash: Hash,
+}
+
+impl ScheduledCallKey {
+    /// Creates a key for the specified call.
+    pub fn new(call: &ScheduledCall) -> Self {
+        Self {
+            time: call.timer.time,
+            hash: call.object_hash(),
+        }
+    }
+}
+
+impl BinaryKey for ScheduledCallKey {
+    fn size(&self) -> usize {
+        self.time.size() + self.hash.size()
+    }
+
+    fn write(&self, buffer: &mut [u8]) -> usize {
+        let time_size = self.time.write(buffer);
+        time_size + self.hash.write(&mut buffer[time_size..])
+    }
+
+    fn read(buffer: &[u8]) -> Self {
+        let time = DateTime::<Utc>::read(buffer);
+        let hash = Hash::read(&buffer[time.size()..]);
+        Self { time, hash }
+    }
+}
+
+impl ObjectHash for ScheduledCallKey {
+    fn object_hash(&self) -> Hash {
+        let mut buffer = vec![0; self.size()];
+        self.write(&mut buffer);
+        crypto::hash(&buffer)
+    }
+}
 
 /// `Exonum-time` service database schema.
 #[derive(Debug, FromAccess)]
 pub struct TimeSchema<T: Access> {
     /// `DateTime` for every validator. May contain keys corresponding to past validators.
     pub validators_times: ProofMapIndex<T::Base, PublicKey, DateTime<Utc>>,
+    /// Heights of the blocks in which the latest times of the validators were sent.
+    pub validators_heights: ProofMapIndex<T::Base, PublicKey, u64>,
     /// Consolidated time.
     pub time: Entry<T::Base, DateTime<Utc>>,
+    /// Configuration of the time oracle.
+    pub config: Entry<T::Base, Config>,
+    /// History of the consolidated time. Contains a record for every height at which
+    /// the consolidated time was updated, in the ascending order of heights.
+    pub time_history: ProofListIndex<T::Base, TimeRecord>,
+    /// Timers set by other services which have not fired yet, keyed by their times
+    /// and hashes.
+    pub scheduled_calls: ProofMapIndex<T::Base, ScheduledCallKey, ScheduledCall>,
+    /// Keys of `scheduled_calls` in the ascending order. Unlike `scheduled_calls`,
+    /// which is iterated in the order of key hashes, this index allows to find the due calls
+    /// without scanning the whole schedule.
+    pub call_queue: KeySetIndex<T::Base, ScheduledCallKey>,
+    /// Number of the timers in `scheduled_calls` set by each service instance.
+    pub timer_counts: MapIndex<T::Base, InstanceId, u64>,
 }
 
+/// Index of the time history in the list returned by `TimeSchema::state_hash`.
+pub const TIME_HISTORY_INDEX_ID: u16 = 3;
+
 impl<T: Access> TimeSchema<T> {
-    /// Returns hashes for stored tables.
+    /// Returns hashes for stored tables. The time history hash is located
+    /// at `TIME_HISTORY_INDEX_ID`.
     pub fn state_hash(&self) -> Vec<Hash> {
-        vec![self.validators_times.object_hash(), self.time.object_hash()]
+        vec![
+            self.validators_times.object_hash(),
+            self.time.object_hash(),
+            self.config.object_hash(),
+            self.time_history.object_hash(),
+            self.scheduled_calls.object_hash(),
+            self.validators_heights.object_hash(),
+        ]
+    }
+
+    /// Returns the consolidated time after the block at the given height.
+    pub fn time_at_height(&self, height: Height) -> Option<DateTime<Utc>> {
+        let index = self.history_index_at_height(height)?;
+        self.time_history.get(index).map(|record| record.time)
+    }
+
+    /// Returns the index of the history record with the consolidated time at the given height,
+    /// i.e., of the latest record with the height not greater than the given one.
+    pub fn history_index_at_height(&self, height: Height) -> Option<u64> {
+        match self.history_partition_point(|record| record.height <= height) {
+            0 => None,
+            index => Some(index - 1),
+        }
+    }
+
+    /// Returns the index of the first history record with the consolidated time
+    /// greater than the given one.
+    pub fn history_index_after(&self, time: DateTime<Utc>) -> Option<u64> {
+        let index = self.history_partition_point(|record| record.time <= time);
+        if index < self.time_history.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the first history record for which the predicate is false.
+    /// Both heights and times of the records are ascending, so the predicates
+    /// used to search them are true for a prefix of the history.
+    fn history_partition_point(&self, predicate: impl Fn(&TimeRecord) -> bool) -> u64 {
+        let (mut low, mut high) = (0, self.time_history.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let record = self
+                .time_history
+                .get(middle)
+                .expect("Time history record is missing");
+            if predicate(&record) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    /// Checks whether the call is already in the schedule.
+    pub fn contains_call(&self, call: &ScheduledCall) -> bool {
+        self.call_queue.contains(&ScheduledCallKey::new(call))
+    }
+
+    /// Returns the number of the timers set by the service instance which have not
+    /// fired yet.
+    pub fn timers_count(&self, instance_id: InstanceId) -> u64 {
+        self.timer_counts.get(&instance_id).unwrap_or_default()
     }
 }
 
@@ -48,6 +201,7 @@ where
         &mut self,
         author: PublicKey,
         time: DateTime<Utc>,
+        height: Height,
     ) -> Result<(), ()> {
         match self.validators_times.get(&author) {
             // The validator time in the storage should be less than in the transaction.
@@ -55,42 +209,116 @@ where
             // Write the time for the validator.
             _ => {
                 self.validators_times.put(&author, time);
+                self.validators_heights.put(&author, height.0);
                 Ok(())
             }
         }
     }
 
-    pub(crate) fn update_consolidated_time(&mut self, validator_keys: &[ValidatorKeys]) {
-        // Find all known times for the validators.
-        let validator_times = {
-            let mut times = self
-                .validators_times
-                .iter()
-                .filter_map(|(public_key, time)| {
-                    validator_keys
-                        .iter()
-                        .find(|validator| validator.service_key == public_key)
-                        .map(|_| time)
+    pub(crate) fn update_consolidated_time(
+        &mut self,
+        validator_keys: &[ValidatorKeys],
+        config: &Config,
+        height: Height,
+    ) {
+        let current_time = self.time.get();
+        // Times of the validators which are no longer active, as well as stale times,
+        // are not taken into account.
+        let validator_times = validator_keys
+            .iter()
+            .filter(|validator| {
+                let reported_height = self.validators_heights.get(&validator.service_key);
+                reported_height.map_or(false, |reported_height| {
+                    config.oracle.is_fresh(reported_height, height.0)
                 })
-                .collect::<Vec<_>>();
-            // Ordering time from highest to lowest.
-            times.sort_by(|a, b| b.cmp(a));
-            times
-        };
+            })
+            .filter_map(|validator| self.validators_times.get(&validator.service_key))
+            .collect::<Vec<_>>();
 
-        // The largest number of Byzantine nodes.
-        let max_byzantine_nodes = (validator_keys.len() - 1) / 3;
-        if validator_times.len() <= 2 * max_byzantine_nodes {
-            return;
+        let mut params = AggregationParams::new(validator_keys.len());
+        if let Some(min_sources) = config.oracle.min_sources {
+            params = params.with_min_sources(min_sources as usize);
+        }
+        let mut selected_time = match QuorumMin.aggregate(validator_times, &params) {
+            Some(time) => time,
+            None => return,
+        };
+        // A drift leading out of the range of representable times is effectively unlimited.
+        let max_time = current_time.and_then(|time| {
+            config
+                .max_forward_drift()
+                .and_then(|max_drift| time.checked_add_signed(max_drift))
+        });
+        if let Some(max_time) = max_time {
+            if selected_time > max_time {
+                selected_time = max_time;
+            }
         }
 
-        match self.time.get() {
+        match current_time {
             // Selected time should be greater than the time in the storage.
-            Some(current_time) if current_time >= validator_times[max_byzantine_nodes] => {}
+            Some(current_time) if current_time >= selected_time => {}
             _ => {
                 // Change the time in the storage.
-                self.time.set(validator_times[max_byzantine_nodes]);
+                self.time.set(selected_time);
+                self.record_time(height, selected_time);
+            }
+        }
+    }
+
+    /// Adds the call to the schedule.
+    pub fn schedule_call(&mut self, call: ScheduledCall) {
+        let key = ScheduledCallKey::new(&call);
+        if self.call_queue.contains(&key) {
+            return;
+        }
+        let count = self.timers_count(call.instance_id);
+        self.timer_counts.put(&call.instance_id, count + 1);
+        self.scheduled_calls.put(&key, call);
+        self.call_queue.insert(key);
+    }
+
+    /// Removes the calls which time has been reached by the consolidated time from
+    /// the schedule and returns them in the order of their times.
+    pub fn take_due_calls(&mut self) -> Vec<ScheduledCall> {
+        let current_time = match self.time.get() {
+            Some(time) => time,
+            None => return Vec::new(),
+        };
+
+        let due_keys = self
+            .call_queue
+            .iter()
+            .take_while(|key| key.time <= current_time)
+            .collect::<Vec<_>>();
+        due_keys
+            .into_iter()
+            .map(|key| {
+                let call = self
+                    .scheduled_calls
+                    .get(&key)
+                    .expect("Scheduled call is missing");
+                self.scheduled_calls.remove(&key);
+                self.call_queue.remove(&key);
+                match self.timers_count(call.instance_id) {
+                    0 | 1 => self.timer_counts.remove(&call.instance_id),
+                    count => self.timer_counts.put(&call.instance_id, count - 1),
+                }
+                call
+            })
+            .collect()
+    }
+
+    /// Records the updated consolidated time to the history. Only the latest update
+    /// is kept for each height.
+    fn record_time(&mut self, height: Height, time: DateTime<Utc>) {
+        let record = TimeRecord { height, time };
+        match self.time_history.last() {
+            Some(ref last) if last.height == height => {
+                let index = self.time_history.len() - 1;
+                self.time_history.set(index, record);
             }
+            _ => self.time_history.push(record),
         }
     }
 }
@@ -25,6 +25,12 @@ pub enum Error {
     UnknownSender = 0,
     /// The validator time that is stored in storage is greater than the proposed one.
     ValidatorTimeIsGreater = 1,
+    /// The time oracle configuration is invalid.
+    InvalidConfig = 2,
+    /// The calling service instance has too many timers which have not fired yet.
+    TooManyTimers = 3,
+    /// The timer payload exceeds the maximum allowed size.
+    TimerPayloadTooLarge = 4,
 }
 
 /// Transaction that is sent by the validator after the commit of the block.
@@ -58,13 +64,17 @@ impl TimeOracleInterface for TimeService {
             .validator_id(author)
             .ok_or(Error::UnknownSender)?;
 
+        // Times are sent and recorded to the history at the height of the block
+        // being created.
+        let height = core_schema.height().next();
         let mut schema = TimeSchema::new(context.service_data());
         schema
-            .update_validator_time(author, arg.time)
+            .update_validator_time(author, arg.time, height)
             .map_err(|()| Error::ValidatorTimeIsGreater)?;
 
         let validator_keys = core_schema.consensus_config().validator_keys;
-        schema.update_consolidated_time(&validator_keys);
+        let config = schema.config.get().unwrap_or_default();
+        schema.update_consolidated_time(&validator_keys, &config, height);
         Ok(())
     }
 }
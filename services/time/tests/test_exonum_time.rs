@@ -19,24 +19,36 @@ extern crate pretty_assertions;
 
 use chrono::{DateTime, Duration, TimeZone, Utc};
 use exonum::{
-    blockchain::ExecutionErrorKind,
-    crypto::{gen_keypair, PublicKey},
+    blockchain::{ExecutionErrorKind, SchemaOrigin},
+    crypto::{gen_keypair, Hash, PublicKey},
     helpers::Height,
-    runtime::{rust::Transaction, InstanceId, SnapshotExt},
+    runtime::{
+        rust::{CallContext, Service, Transaction},
+        BlockchainData, DispatcherError, ExecutionError, InstanceId, SnapshotExt,
+    },
 };
-use exonum_merkledb::{access::Access, Snapshot};
+use exonum_derive::{exonum_interface, FromAccess, ServiceDispatcher, ServiceFactory};
+use exonum_merkledb::{access::Access, ListIndex, ObjectHash, Snapshot};
 use exonum_supervisor::{ConfigPropose, SimpleSupervisor};
-use exonum_testkit::{ApiKind, InstanceCollection, TestKitApi, TestKitBuilder, TestNode};
+use exonum_testkit::{ApiKind, InstanceCollection, TestKit, TestKitApi, TestKitBuilder, TestNode};
 
 use std::{collections::HashMap, iter::FromIterator};
 
 use exonum_time::{
-    api::ValidatorTime, schema::TimeSchema, time_provider::MockTimeProvider, transactions::Error,
-    transactions::TxTime, TimeServiceFactory,
+    api::{HeightQuery, TimeQuery, TimeRecordInfo, ValidatorTime},
+    config::{Config, OracleConfig, MAX_DURATION_SECS},
+    scheduler::{SchedulerCall, TimeCallback, Timer, MAX_TIMER_PAYLOAD_SIZE},
+    schema::{TimeRecord, TimeSchema, TIME_HISTORY_INDEX_ID},
+    time_provider::MockTimeProvider,
+    transactions::Error,
+    transactions::TxTime,
+    TimeServiceFactory,
 };
 
 const INSTANCE_ID: InstanceId = 112;
 const INSTANCE_NAME: &str = "my-time";
+const TIMER_SERVICE_ID: InstanceId = 113;
+const TIMER_SERVICE_NAME: &str = "timer";
 
 struct TimeServiceInstance;
 
@@ -598,3 +610,480 @@ fn test_endpoint_api() {
     assert_current_validators_times_eq(&mut api, &current_validators_times);
     assert_all_validators_times_eq(&mut api, &all_validators_times);
 }
+
+fn testkit_with_time_config(validator_count: u16, config: Config) -> TestKit {
+    TestKitBuilder::validator()
+        .with_validators(validator_count)
+        .with_rust_service(
+            InstanceCollection::new(TimeServiceFactory::default()).with_instance(
+                INSTANCE_ID,
+                INSTANCE_NAME,
+                config,
+            ),
+        )
+        .create()
+}
+
+#[test]
+fn test_stale_validator_times() {
+    let config = Config {
+        oracle: OracleConfig {
+            max_age: Some(1),
+            ..OracleConfig::default()
+        },
+        ..Config::default()
+    };
+    let mut testkit = testkit_with_time_config(4, config);
+    let validators = testkit.network().validators().to_vec();
+    let tx_time = |validator: &TestNode, time| {
+        let (pub_key, sec_key) = validator.service_keypair();
+        TxTime { time }.sign(INSTANCE_ID, pub_key, &sec_key)
+    };
+
+    let time0 = Utc::now();
+    testkit.create_block_with_transactions(
+        validators[..3]
+            .iter()
+            .map(|validator| tx_time(validator, time0)),
+    );
+    assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time0));
+
+    let time1 = time0 + Duration::seconds(100);
+    testkit.create_block_with_transactions(
+        validators[..2]
+            .iter()
+            .map(|validator| tx_time(validator, time1)),
+    );
+    assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time1));
+
+    // The time of the third validator has been sent two blocks ago and is stale now,
+    // so there are not enough fresh sources to update the consolidated time.
+    let time2 = time1 + Duration::seconds(100);
+    testkit.create_block_with_transactions(
+        validators[..2]
+            .iter()
+            .map(|validator| tx_time(validator, time2)),
+    );
+    assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time1));
+
+    testkit.create_block_with_transaction(tx_time(&validators[3], time2));
+    assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time2));
+}
+
+#[test]
+fn test_max_forward_drift() {
+    let config = Config {
+        max_forward_drift: Some(60),
+        ..Config::default()
+    };
+    let mut testkit = testkit_with_time_config(1, config);
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+
+    let time0 = Utc::now();
+    testkit.create_block_with_transaction(TxTime { time: time0 }.sign(
+        INSTANCE_ID,
+        pub_key,
+        &sec_key,
+    ));
+    assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time0));
+
+    let time1 = time0 + Duration::seconds(1000);
+    testkit.create_block_with_transaction(TxTime { time: time1 }.sign(
+        INSTANCE_ID,
+        pub_key,
+        &sec_key,
+    ));
+    assert_eq!(
+        get_schema(&testkit.snapshot()).time.get(),
+        Some(time0 + Duration::seconds(60))
+    );
+}
+
+#[test]
+fn test_change_config() {
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(1)
+        .with_rust_service(TimeServiceInstance)
+        .with_rust_service(SimpleSupervisor::new())
+        .create();
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+
+    let new_config = Config {
+        oracle: OracleConfig {
+            max_age: Some(60),
+            min_sources: Some(1),
+        },
+        max_forward_drift: Some(3600),
+    };
+    let cfg_change_height = Height(5);
+    testkit.create_block_with_transaction(
+        ConfigPropose::new(0, cfg_change_height)
+            .service_config(INSTANCE_ID, new_config.clone())
+            .sign_for_supervisor(pub_key, &sec_key),
+    );
+    testkit.create_blocks_until(cfg_change_height);
+
+    let config: Config = testkit
+        .api()
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .get("v1/config")
+        .unwrap();
+    assert_eq!(config, new_config);
+
+    // Configuration requiring more sources than validators is discarded.
+    let invalid_config = Config {
+        oracle: OracleConfig {
+            min_sources: Some(2),
+            ..OracleConfig::default()
+        },
+        ..Config::default()
+    };
+    let block = testkit.create_block_with_transaction(
+        ConfigPropose::new(1, cfg_change_height.next())
+            .service_config(INSTANCE_ID, invalid_config)
+            .sign_for_supervisor(pub_key, &sec_key),
+    );
+    assert!(block[0].status().is_err());
+
+    // Configuration with durations not representable as `Duration` is discarded.
+    let invalid_config = Config {
+        max_forward_drift: Some(MAX_DURATION_SECS + 1),
+        ..Config::default()
+    };
+    let block = testkit.create_block_with_transaction(
+        ConfigPropose::new(1, cfg_change_height.next().next())
+            .service_config(INSTANCE_ID, invalid_config)
+            .sign_for_supervisor(pub_key, &sec_key),
+    );
+    assert!(block[0].status().is_err());
+}
+
+#[test]
+fn test_max_durations_do_not_overflow() {
+    let config = Config {
+        max_forward_drift: Some(MAX_DURATION_SECS),
+        ..Config::default()
+    };
+    let mut testkit = testkit_with_time_config(1, config);
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+
+    // Bounds out of the range of representable times don't limit the times.
+    let times = [Utc::now(), Utc::now() + Duration::days(365 * 1000)];
+    for &time in &times {
+        let tx = TxTime { time }.sign(INSTANCE_ID, pub_key, &sec_key);
+        testkit.create_block_with_transaction(tx);
+        assert_eq!(get_schema(&testkit.snapshot()).time.get(), Some(time));
+    }
+}
+
+#[test]
+fn test_time_history() {
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(1)
+        .with_rust_service(TimeServiceInstance)
+        .create();
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+
+    let time0 = Utc::now();
+    testkit.create_block_with_transaction(TxTime { time: time0 }.sign(
+        INSTANCE_ID,
+        pub_key,
+        &sec_key,
+    ));
+    testkit.create_block();
+    let time1 = time0 + Duration::seconds(10);
+    testkit.create_block_with_transaction(TxTime { time: time1 }.sign(
+        INSTANCE_ID,
+        pub_key,
+        &sec_key,
+    ));
+
+    let snapshot = testkit.snapshot();
+    let schema = get_schema(&snapshot);
+    assert_eq!(
+        schema.time_history.iter().collect::<Vec<_>>(),
+        vec![
+            TimeRecord {
+                height: Height(1),
+                time: time0,
+            },
+            TimeRecord {
+                height: Height(3),
+                time: time1,
+            },
+        ]
+    );
+    assert_eq!(schema.time_at_height(Height(0)), None);
+    assert_eq!(schema.time_at_height(Height(1)), Some(time0));
+    assert_eq!(schema.time_at_height(Height(2)), Some(time0));
+    assert_eq!(schema.time_at_height(Height(3)), Some(time1));
+    assert_eq!(
+        schema.history_index_after(time0 - Duration::seconds(1)),
+        Some(0)
+    );
+    assert_eq!(schema.history_index_after(time0), Some(1));
+    assert_eq!(schema.history_index_after(time1), None);
+
+    let api = testkit.api();
+    let info: TimeRecordInfo = api
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .query(&HeightQuery { height: 2 })
+        .get("v1/time_history/at_height")
+        .unwrap();
+    assert_eq!(
+        info.record,
+        Some(TimeRecord {
+            height: Height(1),
+            time: time0,
+        })
+    );
+    assert_eq!(info.block_proof.block.height(), Height(3));
+
+    // The time history is authenticated by the state hash of the latest block.
+    let history_coordinates =
+        SchemaOrigin::Service(INSTANCE_ID).coordinate_for(TIME_HISTORY_INDEX_ID);
+    let to_table = info
+        .to_table
+        .check_against_hash(*info.block_proof.block.state_hash())
+        .unwrap();
+    let (_, &history_hash) = to_table
+        .entries()
+        .find(|(coordinates, _)| **coordinates == history_coordinates)
+        .unwrap();
+    assert_eq!(history_hash, schema.time_history.object_hash());
+    info.to_records.check_against_hash(history_hash).unwrap();
+
+    let info: TimeRecordInfo = api
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .query(&TimeQuery { time: time0 })
+        .get("v1/time_history/first_height_after")
+        .unwrap();
+    assert_eq!(
+        info.record,
+        Some(TimeRecord {
+            height: Height(3),
+            time: time1,
+        })
+    );
+
+    let info: TimeRecordInfo = api
+        .public(ApiKind::Service(INSTANCE_NAME))
+        .query(&TimeQuery { time: time1 })
+        .get("v1/time_history/first_height_after")
+        .unwrap();
+    assert_eq!(info.record, None);
+}
+
+#[test]
+fn test_time_history_index_id() {
+    let testkit = TestKitBuilder::validator()
+        .with_validators(1)
+        .with_rust_service(TimeServiceInstance)
+        .create();
+    let snapshot = testkit.snapshot();
+    let schema = get_schema(&snapshot);
+    assert_eq!(
+        schema.state_hash()[TIME_HISTORY_INDEX_ID as usize],
+        schema.time_history.object_hash()
+    );
+}
+
+#[derive(Debug, FromAccess)]
+struct TimerSchema<T: Access> {
+    fired_timers: ListIndex<T::Base, Timer>,
+}
+
+#[exonum_interface]
+pub trait TimerInterface {
+    fn set_timer(&self, context: CallContext<'_>, arg: Timer) -> Result<(), ExecutionError>;
+}
+
+/// Service setting timers in the time oracle.
+#[derive(Debug, ServiceDispatcher, ServiceFactory)]
+#[service_dispatcher(implements("TimerInterface", "TimeCallback"))]
+#[service_factory(artifact_name = "timer")]
+struct TimerService;
+
+impl TimerInterface for TimerService {
+    fn set_timer(&self, mut context: CallContext<'_>, arg: Timer) -> Result<(), ExecutionError> {
+        context
+            .interface::<SchedulerCall<'_>>(INSTANCE_ID)?
+            .schedule(arg)
+    }
+}
+
+/// Payload of the timers which callback panics after recording the timer.
+const PANICKING_TIMER_PAYLOAD: &[u8] = b"panic";
+/// Payload of the timers which callback returns an error after recording the timer.
+const FAILING_TIMER_PAYLOAD: &[u8] = b"error";
+
+impl TimeCallback for TimerService {
+    fn on_time(&self, context: CallContext<'_>, timer: Timer) -> Result<(), ExecutionError> {
+        if context.caller().as_service() != Some(INSTANCE_ID) {
+            return Err(DispatcherError::UnauthorizedCaller.into());
+        }
+        TimerSchema::new(context.service_data())
+            .fired_timers
+            .push(timer.clone());
+
+        if timer.payload == PANICKING_TIMER_PAYLOAD {
+            panic!("Timer callback panicked");
+        } else if timer.payload == FAILING_TIMER_PAYLOAD {
+            Err(DispatcherError::MalformedArguments.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Service for TimerService {
+    fn state_hash(&self, _data: BlockchainData<&dyn Snapshot>) -> Vec<Hash> {
+        vec![]
+    }
+}
+
+fn init_timer_testkit() -> TestKit {
+    TestKitBuilder::validator()
+        .with_validators(1)
+        .with_rust_service(TimeServiceInstance)
+        .with_rust_service(InstanceCollection::new(TimerService).with_instance(
+            TIMER_SERVICE_ID,
+            TIMER_SERVICE_NAME,
+            (),
+        ))
+        .create()
+}
+
+#[test]
+fn test_scheduled_timers() {
+    let mut testkit = init_timer_testkit();
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+    let tx_time = |time| TxTime { time }.sign(INSTANCE_ID, pub_key, &sec_key);
+    let (user_pub_key, user_sec_key) = gen_keypair();
+    let set_timer = |timer: &Timer| {
+        timer
+            .clone()
+            .sign(TIMER_SERVICE_ID, user_pub_key, &user_sec_key)
+    };
+
+    let time0 = Utc::now();
+    let timer1 = Timer::new(time0 + Duration::seconds(10), vec![1]);
+    let timer2 = Timer::new(time0 + Duration::seconds(20), vec![2]);
+    let timer3 = Timer::new(time0 + Duration::seconds(40), vec![3]);
+    let block = testkit.create_block_with_transactions(vec![
+        tx_time(time0),
+        set_timer(&timer2),
+        set_timer(&timer3),
+        set_timer(&timer1),
+    ]);
+    assert!(block.iter().all(|tx| tx.status().is_ok()));
+
+    let snapshot = testkit.snapshot();
+    assert_eq!(get_schema(&snapshot).scheduled_calls.values().count(), 3);
+    assert_eq!(get_schema(&snapshot).timers_count(TIMER_SERVICE_ID), 3);
+    let timer_schema = TimerSchema::new(snapshot.for_service(TIMER_SERVICE_NAME).unwrap());
+    assert!(timer_schema.fired_timers.is_empty());
+
+    // Timers fire in the order of their times once the consolidated time reaches them.
+    testkit.create_block_with_transaction(tx_time(time0 + Duration::seconds(30)));
+    let snapshot = testkit.snapshot();
+    let timer_schema = TimerSchema::new(snapshot.for_service(TIMER_SERVICE_NAME).unwrap());
+    assert_eq!(
+        timer_schema.fired_timers.iter().collect::<Vec<_>>(),
+        vec![timer1, timer2]
+    );
+    let schema = get_schema(&snapshot);
+    assert_eq!(
+        schema
+            .scheduled_calls
+            .values()
+            .map(|call| call.timer)
+            .collect::<Vec<_>>(),
+        vec![timer3]
+    );
+    assert_eq!(schema.call_queue.iter().count(), 1);
+    assert_eq!(schema.timers_count(TIMER_SERVICE_ID), 1);
+}
+
+#[test]
+fn test_timer_limits() {
+    let mut testkit = init_timer_testkit();
+    let (user_pub_key, user_sec_key) = gen_keypair();
+    let set_timer = |timer: &Timer| {
+        timer
+            .clone()
+            .sign(TIMER_SERVICE_ID, user_pub_key, &user_sec_key)
+    };
+
+    let time = Utc::now() + Duration::seconds(10);
+    let timer = Timer::new(time, vec![1; MAX_TIMER_PAYLOAD_SIZE]);
+    let large_timer = Timer::new(time, vec![1; MAX_TIMER_PAYLOAD_SIZE + 1]);
+    let block = testkit.create_block_with_transactions(vec![
+        set_timer(&timer),
+        set_timer(&large_timer),
+        set_timer(&timer),
+    ]);
+    assert!(block[0].status().is_ok());
+    assert_eq!(
+        block[1].status().unwrap_err().kind,
+        ExecutionErrorKind::service(Error::TimerPayloadTooLarge as u8)
+    );
+    // Setting the same timer twice has no effect.
+    assert!(block[2].status().is_ok());
+
+    let snapshot = testkit.snapshot();
+    let schema = get_schema(&snapshot);
+    assert_eq!(schema.scheduled_calls.values().count(), 1);
+    assert_eq!(schema.timers_count(TIMER_SERVICE_ID), 1);
+}
+
+#[test]
+fn test_failing_timer_callbacks() {
+    let mut testkit = init_timer_testkit();
+    let (pub_key, sec_key) = testkit.network().validators()[0].service_keypair();
+    let tx_time = |time| TxTime { time }.sign(INSTANCE_ID, pub_key, &sec_key);
+    let (user_pub_key, user_sec_key) = gen_keypair();
+    let set_timer = |timer: &Timer| {
+        timer
+            .clone()
+            .sign(TIMER_SERVICE_ID, user_pub_key, &user_sec_key)
+    };
+
+    let time0 = Utc::now();
+    let timer1 = Timer::new(time0 + Duration::seconds(10), vec![1]);
+    let panicking_timer = Timer::new(time0 + Duration::seconds(15), PANICKING_TIMER_PAYLOAD);
+    let failing_timer = Timer::new(time0 + Duration::seconds(17), FAILING_TIMER_PAYLOAD);
+    let timer2 = Timer::new(time0 + Duration::seconds(20), vec![2]);
+    let block = testkit.create_block_with_transactions(vec![
+        tx_time(time0),
+        set_timer(&timer1),
+        set_timer(&panicking_timer),
+        set_timer(&failing_timer),
+        set_timer(&timer2),
+    ]);
+    assert!(block.iter().all(|tx| tx.status().is_ok()));
+
+    // Failed callbacks neither prevent other timers from firing, nor leave their changes
+    // in the storage.
+    let time1 = time0 + Duration::seconds(30);
+    testkit.create_block_with_transaction(tx_time(time1));
+    let snapshot = testkit.snapshot();
+    let timer_schema = TimerSchema::new(snapshot.for_service(TIMER_SERVICE_NAME).unwrap());
+    assert_eq!(
+        timer_schema.fired_timers.iter().collect::<Vec<_>>(),
+        vec![timer1.clone(), timer2.clone()]
+    );
+    let schema = get_schema(&snapshot);
+    assert_eq!(schema.time.get(), Some(time1));
+    // Failed timers are removed from the schedule and do not fire again.
+    assert_eq!(schema.scheduled_calls.values().count(), 0);
+    assert_eq!(schema.call_queue.iter().count(), 0);
+
+    testkit.create_block_with_transaction(tx_time(time1 + Duration::seconds(10)));
+    let snapshot = testkit.snapshot();
+    let timer_schema = TimerSchema::new(snapshot.for_service(TIMER_SERVICE_NAME).unwrap());
+    assert_eq!(
+        timer_schema.fired_timers.iter().collect::<Vec<_>>(),
+        vec![timer1, timer2]
+    );
+}
@@ -204,6 +204,12 @@ where
     where
         R: DeserializeOwned + 'static,
     {
+        Self::response_to_api_result(self.get_response(endpoint))
+    }
+
+    /// Sends a get request to the testing API endpoint and returns the raw response.
+    /// This can be used to check response headers and status codes.
+    pub fn get_response(self, endpoint: &str) -> Response {
         let params = self
             .query
             .as_ref()
@@ -229,8 +235,7 @@ where
         if let Some(modifier) = self.modifier {
             builder = modifier(builder);
         }
-        let response = builder.send().expect("Unable to send request");
-        Self::response_to_api_result(response)
+        builder.send().expect("Unable to send request")
     }
 
     /// Sends a post request to the testing API endpoint and decodes response as
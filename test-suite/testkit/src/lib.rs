@@ -147,6 +147,7 @@ pub use crate::{
 pub mod compare;
 pub mod proto;
 
+use chrono::Utc;
 use exonum::{
     api::{
         backends::actix::{ApiRuntimeConfig, SystemRuntimeConfig},
@@ -166,6 +167,7 @@ use futures::{sync::mpsc, Future, Stream};
 use tokio_core::reactor::Core;
 
 use std::{
+    cmp,
     collections::BTreeMap,
     fmt, iter, mem,
     net::SocketAddr,
@@ -277,6 +279,9 @@ impl TestKit {
                     ExternalMessage::Transaction(tx) => {
                         BlockchainMut::add_transactions_into_db_pool(db.as_ref(), iter::once(tx));
                     }
+                    ExternalMessage::Transactions(txs) => {
+                        BlockchainMut::add_transactions_into_db_pool(db.as_ref(), txs);
+                    }
                     ExternalMessage::PeerAdd(_)
                     | ExternalMessage::Enable(_)
                     | ExternalMessage::Shutdown => { /* Ignored */ }
@@ -456,17 +461,25 @@ impl TestKit {
         let saved_consensus_config = self.consensus_config();
         let validator_id = self.leader().validator_id().unwrap();
 
+        // Block times must not decrease, even if the system clock is adjusted.
+        let last_block_time = self.blockchain.as_ref().last_block().timestamp();
+        let block_time = cmp::max(Utc::now(), last_block_time);
+
         let guard = self.processing_lock.lock().unwrap();
         let (block_hash, patch) = self.blockchain.create_patch(
             validator_id,
             new_block_height,
+            block_time,
             tx_hashes,
             &mut BTreeMap::new(),
         );
 
-        let propose =
-            self.leader()
-                .create_propose(new_block_height, last_hash, tx_hashes.iter().cloned());
+        let propose = self.leader().create_propose(
+            new_block_height,
+            last_hash,
+            tx_hashes.iter().cloned(),
+            block_time,
+        );
         let precommits: Vec<_> = self
             .network()
             .validators()
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use exonum::{
@@ -230,6 +231,7 @@ impl TestNode {
         height: Height,
         last_hash: crypto::Hash,
         tx_hashes: impl IntoIterator<Item = crypto::Hash>,
+        time: DateTime<Utc>,
     ) -> Verified<Propose> {
         Verified::from_value(
             Propose::new(
@@ -239,6 +241,7 @@ impl TestNode {
                 Round::first(),
                 last_hash,
                 tx_hashes,
+                time,
             ),
             self.keys.consensus_pk(),
             &self.keys.consensus_sk(),
@@ -19,6 +19,7 @@ use exonum::{
     crypto::Hash,
     explorer::{BlockWithTransactions, BlockchainExplorer},
     helpers::Height,
+    impl_api_schema,
 };
 use futures::{sync::oneshot, Future};
 
@@ -95,6 +96,11 @@ pub struct TestKitStatus {
     pub configuration: ConsensusConfig,
 }
 
+impl_api_schema!(TestKitStatus {
+    height: Height,
+    configuration: ConsensusConfig,
+});
+
 impl Handler<GetStatus> for TestKitActor {
     type Result = api::Result<TestKitStatus>;
 
@@ -112,6 +118,10 @@ struct CreateBlock {
     tx_hashes: Option<Vec<Hash>>,
 }
 
+impl_api_schema!(CreateBlock {
+    tx_hashes: Option<Vec<Hash>>,
+});
+
 impl Message for CreateBlock {
     type Result = api::Result<BlockWithTransactions>;
 }
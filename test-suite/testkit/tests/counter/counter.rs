@@ -21,6 +21,7 @@ use exonum::{
         ApiBackend,
     },
     crypto::Hash,
+    impl_api_schema,
     runtime::{
         rust::{
             api::{ServiceApiBuilder, ServiceApiState},
@@ -128,6 +129,8 @@ pub struct TransactionResponse {
     pub tx_hash: Hash,
 }
 
+impl_api_schema!(TransactionResponse { tx_hash: Hash });
+
 #[derive(Debug, Clone, Copy)]
 struct CounterApi;
 
@@ -113,6 +113,55 @@ fn test_inc_count_api() {
     assert_eq!(counter, 5);
 }
 
+#[test]
+fn test_api_description() {
+    let (_testkit, api) = init_testkit();
+
+    let description: Value = api
+        .public(ApiKind::Service("counter"))
+        .get("openapi.json")
+        .unwrap();
+    let paths = &description["paths"];
+    let count = &paths["/api/services/counter/count"];
+    assert!(count["get"].get("parameters").is_none());
+    assert_eq!(
+        count["get"]["responses"]["200"]["content"]["application/json"]["schema"]["type"],
+        json!("integer")
+    );
+    assert_eq!(count["post"]["requestBody"]["required"], json!(true));
+    assert_eq!(
+        paths["/api/services/counter/v1/counter-with-auth"]["get"]["x-raw-handler"],
+        json!(true)
+    );
+    assert!(paths.get("/api/services/counter/reset").is_none());
+
+    let description: Value = api
+        .private(ApiKind::Service("counter"))
+        .get("openapi.json")
+        .unwrap();
+    assert_eq!(description["x-api-access"], json!("private"));
+    assert!(description["paths"]["/api/services/counter/reset"]
+        .get("post")
+        .is_some());
+
+    let description: Value = api.public(ApiKind::Explorer).get("openapi.json").unwrap();
+    let blocks = &description["paths"]["/api/explorer/v1/blocks"]["get"];
+    let query = &blocks["parameters"][0]["schema"];
+    assert_eq!(query["type"], json!("object"));
+    assert_eq!(query["properties"]["count"]["type"], json!("integer"));
+    assert_eq!(query["properties"]["latest"]["nullable"], json!(true));
+    assert_eq!(query["required"], json!(["count"]));
+
+    let response = &blocks["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(response["properties"]["blocks"]["type"], json!("array"));
+    let block = &response["properties"]["blocks"]["items"];
+    assert_eq!(block["properties"]["state_hash"]["type"], json!("string"));
+    assert_eq!(block["properties"]["precommits"]["type"], json!("array"));
+    let required = block["required"].as_array().unwrap();
+    assert!(required.contains(&json!("height")));
+    assert!(!required.contains(&json!("precommits")));
+}
+
 #[test]
 fn test_inc_count_with_multiple_transactions() {
     let (mut testkit, api) = init_testkit();
@@ -440,6 +489,8 @@ fn test_explorer_blocks_basic() {
                 "prev_hash": crypto::Hash::zero(),
                 "tx_hash": HashTag::empty_list_hash(),
                 "state_hash": blocks[0].block.state_hash(),
+                "timestamp": blocks[0].block.timestamp(),
+                "additional_headers": blocks[0].block.additional_headers(),
             }],
         })
     );
@@ -483,6 +534,7 @@ fn test_explorer_blocks_basic() {
                 "prev_hash": blocks[1].block.object_hash(),
                 "tx_hash": HashTag::empty_list_hash(),
                 "state_hash": blocks[0].block.state_hash(),
+                "timestamp": blocks[0].block.timestamp(),
                 "precommits": [precommit],
             }],
         })
@@ -503,6 +555,7 @@ fn test_explorer_blocks_basic() {
                 "prev_hash": blocks[1].block.object_hash(),
                 "tx_hash": HashTag::empty_list_hash(),
                 "state_hash": blocks[0].block.state_hash(),
+                "timestamp": blocks[0].block.timestamp(),
                 "time": precommit.payload().time(),
             }],
         })
@@ -531,6 +584,51 @@ fn test_explorer_api_block_request() {
     );
 }
 
+#[test]
+fn test_explorer_wait_for_commit() {
+    use exonum::explorer::TransactionInfo;
+    use std::time::{Duration, Instant};
+
+    let (mut testkit, api) = init_testkit();
+    let tx_hash = inc_count(&api, 5);
+    testkit.poll_events();
+
+    // The testkit does not notify the waiting requests about the created blocks,
+    // so the request resolves to the transaction in the pool once the timeout expires.
+    let start = Instant::now();
+    let info: TransactionInfo = api
+        .public(ApiKind::Explorer)
+        .get(&format!(
+            "v1/transactions/wait?hash={}&timeout=100",
+            tx_hash.to_hex()
+        ))
+        .unwrap();
+    assert!(info.is_in_pool());
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    // Transactions committed before the request are returned immediately.
+
+    testkit.create_block();
+    let info: TransactionInfo = api
+        .public(ApiKind::Explorer)
+        .get(&format!("v1/transactions/wait?hash={}", tx_hash.to_hex()))
+        .unwrap();
+    let committed = info.as_committed().unwrap();
+    assert_eq!(committed.content().object_hash(), tx_hash);
+    assert_eq!(committed.location().block_height(), Height(1));
+    assert!(committed.status().is_ok());
+
+    let unknown_hash = crypto::hash(b"unknown");
+    let response = api
+        .public(ApiKind::Explorer)
+        .get::<TransactionInfo>(&format!(
+            "v1/transactions/wait?hash={}&timeout=100",
+            unknown_hash.to_hex()
+        ))
+        .unwrap_err();
+    assert_matches!(response, ApiError::NotFound(_));
+}
+
 #[test]
 fn test_explorer_blocks_skip_empty_small() {
     use exonum::api::node::public::explorer::BlocksRange;
@@ -712,6 +810,180 @@ fn test_explorer_blocks_loaded_info() {
         .all(|info| info.time.is_none() && info.precommits.is_some()));
 }
 
+#[test]
+fn test_explorer_transactions_list() {
+    use exonum::api::node::public::explorer::TransactionsList;
+
+    let (mut testkit, api) = init_testkit();
+    testkit.create_blocks_until(Height(6));
+    let tx_hashes: Vec<_> = (0..3)
+        .map(|i| {
+            let tx_hash = inc_count(&api, i + 1);
+            testkit.create_block();
+            tx_hash
+        })
+        .collect();
+
+    let TransactionsList { transactions, next } = api
+        .public(ApiKind::Explorer)
+        .get("v1/transactions/list?count=2")
+        .unwrap();
+    let hashes: Vec<_> = transactions
+        .iter()
+        .map(|tx| tx.content().object_hash())
+        .collect();
+    assert_eq!(hashes, vec![tx_hashes[2], tx_hashes[1]]);
+    let next = next.unwrap();
+    assert_eq!(next.block_height(), Height(8));
+
+    let TransactionsList { transactions, next } = api
+        .public(ApiKind::Explorer)
+        .get(&format!(
+            "v1/transactions/list?count=2&instance_id={}&before_height={}&before_position={}",
+            SERVICE_ID,
+            next.block_height(),
+            next.position_in_block()
+        ))
+        .unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].content().object_hash(), tx_hashes[0]);
+    assert!(next.is_none());
+
+    let TransactionsList { transactions, .. } = api
+        .public(ApiKind::Explorer)
+        .get("v1/transactions/list?count=10&status=failure")
+        .unwrap();
+    assert!(transactions.is_empty());
+
+    let result: Result<TransactionsList, _> = api
+        .public(ApiKind::Explorer)
+        .get("v1/transactions/list?count=1001");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_explorer_transactions_by_author() {
+    use exonum::api::node::public::explorer::TransactionsList;
+
+    let (mut testkit, api) = init_testkit();
+    let tx_hashes: Vec<_> = (0..3)
+        .map(|i| {
+            let tx_hash = inc_count(&api, i + 1);
+            testkit.create_block();
+            tx_hash
+        })
+        .collect();
+    let (pubkey, key) = crypto::gen_keypair();
+    let other_tx = Increment::new(5).sign(SERVICE_ID, pubkey, &key);
+    testkit.create_block_with_transaction(other_tx.clone());
+
+    let author = testkit.us().service_keypair().0;
+    let TransactionsList { transactions, next } = api
+        .public(ApiKind::Explorer)
+        .get(&format!(
+            "v1/transactions/by_author?author={}&count=10",
+            author.to_hex()
+        ))
+        .unwrap();
+    let hashes: Vec<_> = transactions
+        .iter()
+        .map(|tx| tx.content().object_hash())
+        .collect();
+    assert_eq!(hashes, vec![tx_hashes[2], tx_hashes[1], tx_hashes[0]]);
+    assert!(next.is_none());
+
+    let TransactionsList { transactions, .. } = api
+        .public(ApiKind::Explorer)
+        .get(&format!(
+            "v1/transactions/by_author?author={}&count=10",
+            pubkey.to_hex()
+        ))
+        .unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].content(), &other_tx);
+}
+
+#[test]
+fn test_batch_transactions() {
+    use exonum::api::node::public::explorer::{
+        BatchTransactionResponse, TransactionHex, TransactionsBatch,
+    };
+    use exonum_merkledb::BinaryValue;
+    use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+    let (mut testkit, api) = init_testkit();
+    let (pubkey, key) = crypto::gen_keypair();
+    let txs: Vec<_> = (1..=3)
+        .map(|by| Increment::new(by).sign(SERVICE_ID, pubkey, &key))
+        .collect();
+
+    let mut batch: Vec<_> = txs
+        .iter()
+        .map(|tx| TransactionHex {
+            tx_body: hex::encode(tx.to_bytes()),
+        })
+        .collect();
+    batch.push(TransactionHex {
+        tx_body: "c0ffee".to_owned(),
+    });
+    let responses: Vec<BatchTransactionResponse> = api
+        .public(ApiKind::Explorer)
+        .query(&batch)
+        .post("v1/transactions/batch")
+        .unwrap();
+    assert_eq!(responses.len(), 4);
+    for (response, tx) in responses.iter().zip(&txs) {
+        assert_eq!(response.tx_hash, Some(tx.object_hash()));
+        assert_eq!(response.error, None);
+    }
+    assert_eq!(responses[3].tx_hash, None);
+    assert!(responses[3].error.is_some());
+
+    testkit.create_block();
+    let counter: u64 = api
+        .public(ApiKind::Service("counter"))
+        .get("count")
+        .unwrap();
+    assert_eq!(counter, 6);
+
+    // Submit a batch in the Protobuf format.
+    let tx = Increment::new(4).sign(SERVICE_ID, pubkey, &key);
+    let mut invalid_tx = tx.as_raw().clone();
+    invalid_tx.payload.push(0);
+    let batch = TransactionsBatch {
+        transactions: vec![tx.as_raw().clone(), invalid_tx.clone()],
+    };
+    let body = batch.to_bytes();
+    let responses: Vec<BatchTransactionResponse> = api
+        .public(ApiKind::Explorer)
+        .with(move |builder| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/x-protobuf"),
+            );
+            builder.headers(headers).body(body.clone())
+        })
+        .post("v1/transactions/batch")
+        .unwrap();
+    assert_eq!(
+        responses[0],
+        BatchTransactionResponse {
+            tx_hash: Some(tx.object_hash()),
+            error: None,
+        }
+    );
+    assert_eq!(responses[1].tx_hash, Some(invalid_tx.object_hash()));
+    assert!(responses[1].error.is_some());
+
+    testkit.create_block();
+    let counter: u64 = api
+        .public(ApiKind::Service("counter"))
+        .get("count")
+        .unwrap();
+    assert_eq!(counter, 10);
+}
+
 #[test]
 fn test_explorer_single_block() {
     use exonum::helpers::Height;
@@ -15,6 +15,7 @@
 use exonum::{
     crypto::{Hash, PublicKey},
     helpers::Height,
+    impl_api_schema,
     runtime::{
         rust::{
             api::{self, ServiceApiBuilder},
@@ -170,6 +171,8 @@ struct BalanceQuery {
     pub_key: PublicKey,
 }
 
+impl_api_schema!(BalanceQuery { pub_key: PublicKey });
+
 /// Shortcut to get data on wallets.
 impl CryptocurrencyApi {
     /// Endpoint for retrieving a single wallet.
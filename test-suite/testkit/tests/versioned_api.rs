@@ -0,0 +1,153 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the versioned and deprecated service API endpoints.
+
+use chrono::{DateTime, TimeZone, Utc};
+use exonum::{
+    api::{self, Deprecation, VersionedEndpoint},
+    crypto::Hash,
+    runtime::{
+        rust::{api::ServiceApiBuilder, Service},
+        BlockchainData, InstanceId,
+    },
+};
+use exonum_derive::{exonum_interface, ServiceDispatcher, ServiceFactory};
+use exonum_merkledb::Snapshot;
+use exonum_testkit::{ApiKind, TestKit};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+const SERVICE_NAME: &str = "versioned";
+const SERVICE_ID: InstanceId = 100;
+const MIGRATION_LINK: &str = "https://exonum.com/doc/";
+
+fn removal_time() -> DateTime<Utc> {
+    Utc.ymd(2100, 1, 1).and_hms(0, 0, 0)
+}
+
+#[exonum_interface]
+trait VersionedApiInterface {}
+
+#[derive(Debug, ServiceDispatcher, ServiceFactory)]
+#[service_dispatcher(implements("VersionedApiInterface"))]
+#[service_factory(
+    artifact_name = "versioned-api",
+    artifact_version = "1.0.0",
+    proto_sources = "exonum::proto::schema"
+)]
+struct VersionedApiService;
+
+impl VersionedApiInterface for VersionedApiService {}
+
+impl Service for VersionedApiService {
+    fn state_hash(&self, _data: BlockchainData<&dyn Snapshot>) -> Vec<Hash> {
+        vec![]
+    }
+
+    fn wire_api(&self, builder: &mut ServiceApiBuilder) {
+        // Every version of the endpoint returns its version number.
+        builder
+            .public_scope()
+            .versioned_endpoint(
+                VersionedEndpoint::new(0, "value").deprecated(
+                    Deprecation::new().with_removal_time(Utc.ymd(2000, 1, 1).and_hms(0, 0, 0)),
+                ),
+                |_state, _query: ()| Ok::<_, api::Error>(0_u16),
+            )
+            .versioned_endpoint(
+                VersionedEndpoint::new(1, "value").deprecated(
+                    Deprecation::new()
+                        .with_removal_time(removal_time())
+                        .with_link(MIGRATION_LINK),
+                ),
+                |_state, _query: ()| Ok::<_, api::Error>(1_u16),
+            )
+            .versioned_endpoint(VersionedEndpoint::new(2, "value"), |_state, _query: ()| {
+                Ok::<_, api::Error>(2_u16)
+            });
+    }
+}
+
+fn init_testkit() -> TestKit {
+    TestKit::for_rust_service(VersionedApiService, SERVICE_NAME, SERVICE_ID, ())
+}
+
+#[test]
+fn test_current_endpoint() {
+    let mut testkit = init_testkit();
+    let api = testkit.api();
+
+    let response = api
+        .public(ApiKind::Service(SERVICE_NAME))
+        .get_response("v2/value");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("deprecation").is_none());
+    assert!(response.headers().get("sunset").is_none());
+
+    let version: u16 = api
+        .public(ApiKind::Service(SERVICE_NAME))
+        .get("v2/value")
+        .unwrap();
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn test_deprecated_endpoint() {
+    let mut testkit = init_testkit();
+    let api = testkit.api();
+
+    // Deprecated endpoint is still functional, but reports the deprecation in the headers.
+    let mut response = api
+        .public(ApiKind::Service(SERVICE_NAME))
+        .get_response("v1/value");
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers["deprecation"], "true");
+    assert_eq!(headers["sunset"], "Fri, 01 Jan 2100 00:00:00 GMT");
+    assert_eq!(
+        headers["link"],
+        format!("<{}>; rel=\"deprecation\"", MIGRATION_LINK)
+    );
+    assert_eq!(response.json::<u16>().unwrap(), 1);
+}
+
+#[test]
+fn test_removed_endpoint() {
+    let mut testkit = init_testkit();
+    let api = testkit.api();
+
+    let response = api
+        .public(ApiKind::Service(SERVICE_NAME))
+        .get_response("v0/value");
+    assert_eq!(response.status(), StatusCode::GONE);
+}
+
+#[test]
+fn test_versioned_endpoints_description() {
+    let mut testkit = init_testkit();
+    let api = testkit.api();
+
+    let description: Value = api
+        .public(ApiKind::Service(SERVICE_NAME))
+        .get("openapi.json")
+        .unwrap();
+    let paths = &description["paths"];
+    let deprecated = &paths["/api/services/versioned/v1/value"]["get"];
+    assert_eq!(deprecated["deprecated"], json!(true));
+    assert_eq!(deprecated["x-removal-time"], json!(removal_time()));
+    assert!(paths["/api/services/versioned/v2/value"]["get"]
+        .get("deprecated")
+        .is_none());
+}